@@ -0,0 +1,38 @@
+#![feature(test)]
+extern crate test;
+
+use rovo::{
+    autograd::full,
+    c10::TensorOptions,
+    core::{InferenceMode, NoGradGuard},
+    init_rovo,
+    nn::{Linear, Module},
+};
+use test::Bencher;
+
+fn setup() -> (Linear, rovo::tensor::Tensor) {
+    init_rovo();
+    let linear = Linear::new(16, 8);
+    let x = full(&[4, 16], 0.5, TensorOptions::default());
+    (linear, x)
+}
+
+#[bench]
+fn linear_forward(b: &mut Bencher) {
+    let (linear, x) = setup();
+    b.iter(|| linear.forward(&[&x]));
+}
+
+#[bench]
+fn linear_forward_no_grad(b: &mut Bencher) {
+    let (linear, x) = setup();
+    let _guard = NoGradGuard::default();
+    b.iter(|| linear.forward(&[&x]));
+}
+
+#[bench]
+fn linear_forward_inference_mode(b: &mut Bencher) {
+    let (linear, x) = setup();
+    let _guard = InferenceMode::default();
+    b.iter(|| linear.forward(&[&x]));
+}
@@ -8,9 +8,37 @@ macro_rules! AT_PRIVATE_CASE_TYPE{
     }
 }
 
+// Case for one of the extra types a dispatch macro takes, e.g. the `Half` in
+// `AT_DISPATCH_FLOATING_TYPES_AND2!(Half, _, ...)`. `_` adds no case.
+#[macro_export]
+macro_rules! AT_PRIVATE_CASE_EXTRA_TYPE {
+    (_, $_ident: expr, $($args:expr),+) => {};
+    (Half, $_ident: expr, $($args:expr),+) => {
+        $crate::AT_PRIVATE_CASE_TYPE!($_ident, $crate::c10::ScalarType::Half, $crate::c10::Half, $($args)+);
+    };
+    (BFloat16, $_ident: expr, $($args:expr),+) => {
+        $crate::AT_PRIVATE_CASE_TYPE!($_ident, $crate::c10::ScalarType::BFloat16, $crate::c10::BFloat16, $($args)+);
+    };
+    (Bool, $_ident: expr, $($args:expr),+) => {
+        $crate::AT_PRIVATE_CASE_TYPE!($_ident, $crate::c10::ScalarType::Bool, bool, $($args)+);
+    };
+}
+
+// Cases for Byte, Char, Short, Int and Long.
+#[macro_export]
+macro_rules! AT_PRIVATE_CASE_INTEGRAL_TYPES {
+    ($TYPE: expr, $($args:expr),+) => {
+        $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::Byte, u8, $($args)+);
+        $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::Char, i8, $($args)+);
+        $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::Short, i16, $($args)+);
+        $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::Int, i32, $($args)+);
+        $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::Long, i64, $($args)+);
+    };
+}
+
 #[macro_export]
 macro_rules! AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2 {
-    (_, _, $TYPE: expr, $name: expr, $($args:expr),+) => {{
+    ($SCALARTYPE1: tt, $SCALARTYPE2: tt, $TYPE: expr, $name: expr, $($args:expr),+) => {{
         // match $TYPE {
         //     ScalarType::Int => {
         //         type Scalart = i32;
@@ -19,15 +47,43 @@ macro_rules! AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2 {
         //     _ => todo!()
         // };
         $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::Float, f32, $($args)+);
-        $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::Int, i32, $($args)+);
         $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::Double, f64, $($args)+);
-        $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::Long, i64, $($args)+);
+        $crate::AT_PRIVATE_CASE_INTEGRAL_TYPES!($TYPE, $($args)+);
+        $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::ComplexFloat, $crate::c10::ComplexFloat, $($args)+);
+        $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::ComplexDouble, $crate::c10::ComplexDouble, $($args)+);
+        $crate::AT_PRIVATE_CASE_EXTRA_TYPE!($SCALARTYPE1, $TYPE, $($args)+);
+        $crate::AT_PRIVATE_CASE_EXTRA_TYPE!($SCALARTYPE2, $TYPE, $($args)+);
+    }};
+}
+
+#[macro_export]
+macro_rules! AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND3 {
+    ($SCALARTYPE1: tt, $SCALARTYPE2: tt, $SCALARTYPE3: tt, $TYPE: expr, $name: expr, $($args:expr),+) => {{
+        $crate::AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2!($SCALARTYPE1, $SCALARTYPE2, $TYPE, $name, $($args)+);
+        $crate::AT_PRIVATE_CASE_EXTRA_TYPE!($SCALARTYPE3, $TYPE, $($args)+);
+    }};
+}
+
+#[macro_export]
+macro_rules! AT_DISPATCH_ALL_TYPES {
+    ($TYPE: expr, $name: expr, $($args:expr),+) => {{
+        $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::Float, f32, $($args)+);
+        $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::Double, f64, $($args)+);
+        $crate::AT_PRIVATE_CASE_INTEGRAL_TYPES!($TYPE, $($args)+);
+    }};
+}
+
+#[macro_export]
+macro_rules! AT_DISPATCH_COMPLEX_TYPES {
+    ($TYPE: expr, $name: expr, $($args:expr),+) => {{
+        $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::ComplexFloat, $crate::c10::ComplexFloat, $($args)+);
+        $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::ComplexDouble, $crate::c10::ComplexDouble, $($args)+);
     }};
 }
 
 #[macro_export]
 macro_rules! AT_DISPATCH_ALL_TYPES_AND {
-    (_,$TYPE: expr, $name: expr, $($args:expr),+) => {{
+    ($SCALARTYPE: tt, $TYPE: expr, $name: expr, $($args:expr),+) => {{
         // match $TYPE {
         //     ScalarType::Int => {
         //         type Scalart = i32;
@@ -36,20 +92,37 @@ macro_rules! AT_DISPATCH_ALL_TYPES_AND {
         //     _ => todo!()
         // };
         $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::Float, f32, $($args)+);
-        $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::Int, i32, $($args)+);
         $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::Double, f64, $($args)+);
-        $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::Long, i64, $($args)+);
+        $crate::AT_PRIVATE_CASE_INTEGRAL_TYPES!($TYPE, $($args)+);
+        $crate::AT_PRIVATE_CASE_EXTRA_TYPE!($SCALARTYPE, $TYPE, $($args)+);
     }};
 }
 
 #[macro_export]
 macro_rules! AT_DISPATCH_FLOATING_TYPES_AND2{
-    (_,_,$TYPE: expr, $name: expr, $($args:expr),+)=>{{
+    ($SCALARTYPE1: tt, $SCALARTYPE2: tt, $TYPE: expr, $name: expr, $($args:expr),+)=>{{
         $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::Float, f32, $($args)+);
         $crate::AT_PRIVATE_CASE_TYPE!($TYPE, $crate::c10::ScalarType::Double, f64, $($args)+);
+        $crate::AT_PRIVATE_CASE_EXTRA_TYPE!($SCALARTYPE1, $TYPE, $($args)+);
+        $crate::AT_PRIVATE_CASE_EXTRA_TYPE!($SCALARTYPE2, $TYPE, $($args)+);
     }}
 }
 
+#[macro_export]
+macro_rules! AT_DISPATCH_INTEGRAL_TYPES {
+    ($TYPE: expr, $name: expr, $($args:expr),+) => {{
+        $crate::AT_PRIVATE_CASE_INTEGRAL_TYPES!($TYPE, $($args)+);
+    }};
+}
+
+#[macro_export]
+macro_rules! AT_DISPATCH_FLOATING_AND_COMPLEX_TYPES_AND2 {
+    ($SCALARTYPE1: tt, $SCALARTYPE2: tt, $TYPE: expr, $name: expr, $($args:expr),+) => {{
+        $crate::AT_DISPATCH_FLOATING_TYPES_AND2!($SCALARTYPE1, $SCALARTYPE2, $TYPE, $name, $($args)+);
+        $crate::AT_DISPATCH_COMPLEX_TYPES!($TYPE, $name, $($args)+);
+    }};
+}
+
 #[macro_export]
 macro_rules! AT_DISPATCH_FLOATING_TYPES{
     ($TYPE: expr, $name: expr, $($args:expr),+)=>{{
@@ -1,4 +1,4 @@
-use crate::c10::Scalar;
+use crate::c10::{is_complex_type, Scalar, ScalarType};
 use crate::tensor::{Tensor, TensorIterator};
 use crate::{aten::scalar_to_tensor, c10::DeviceType};
 
@@ -48,13 +48,20 @@ pub fn add_out<'a>(
     other: &Tensor,
     alpha: Scalar,
 ) -> &'a Tensor {
+    if self_.is_sparse() || other.is_sparse() {
+        return super::add_out_sparse(result, self_, other, &alpha);
+    }
     let mut iter = TensorIterator::binary_op(result, self_, other, true);
     add_kernel(&mut iter, alpha);
+    iter.cast_outputs();
     return result;
 }
 
 pub fn add(self_: &Tensor, other: &Tensor, alpha: impl Into<Scalar>) -> Tensor {
     let alpha: Scalar = alpha.into();
+    if self_.is_sparse() || other.is_sparse() {
+        return super::add_sparse(self_, other, &alpha);
+    }
     let result = Tensor::default();
     let mut iter = TensorIterator::binary_op(&result, self_, other, false);
     add_kernel(&mut iter, alpha);
@@ -62,23 +69,73 @@ pub fn add(self_: &Tensor, other: &Tensor, alpha: impl Into<Scalar>) -> Tensor {
 }
 
 pub fn div_out<'a>(result: &'a Tensor, self_: &Tensor, other: &Tensor) -> &'a Tensor {
-    let mut iter = TensorIterator::binary_op(result, self_, other, true);
+    let mut iter = TensorIterator::binary_float_op(result, self_, other);
     super::cpu::div_kernel(&mut iter);
+    iter.cast_outputs();
     return result;
 }
 
 pub fn div(self_: &Tensor, other: &Tensor) -> Tensor {
     let result = Tensor::default();
-    let mut iter = TensorIterator::binary_op(&result, self_, other, false);
+    let mut iter = TensorIterator::binary_float_op(&result, self_, other);
     super::cpu::div_kernel(&mut iter);
     return result;
 }
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RoundingMode {
+    Trunc,
+    Floor,
+}
+
+// Division rounded to an integral value, in the common dtype of the inputs.
+pub fn div_mode_out<'a>(
+    result: &'a Tensor,
+    self_: &Tensor,
+    other: &Tensor,
+    rounding_mode: Option<RoundingMode>,
+) -> &'a Tensor {
+    let mode = match rounding_mode {
+        Some(mode) => mode,
+        None => return div_out(result, self_, other),
+    };
+    let mut iter = TensorIterator::binary_op(result, self_, other, true);
+    assert!(
+        iter.dtype() != ScalarType::Bool && !is_complex_type(iter.dtype()),
+        "div with rounding mode {:?} is not supported for {:?} tensors",
+        mode,
+        iter.dtype()
+    );
+    match mode {
+        RoundingMode::Trunc => super::cpu::div_trunc_kernel(&mut iter),
+        RoundingMode::Floor => super::cpu::div_floor_kernel(&mut iter),
+    }
+    iter.cast_outputs();
+    result
+}
+
+pub fn div_mode(self_: &Tensor, other: &Tensor, rounding_mode: Option<RoundingMode>) -> Tensor {
+    let result = Tensor::default();
+    div_mode_out(&result, self_, other, rounding_mode);
+    result
+}
+
+pub fn floor_divide(self_: &Tensor, other: &Tensor) -> Tensor {
+    div_mode(self_, other, Some(RoundingMode::Floor))
+}
 pub fn mul_out<'a>(result: &'a Tensor, self_: &Tensor, other: &Tensor) -> &'a Tensor {
+    if self_.is_sparse() || other.is_sparse() {
+        return super::mul_out_sparse(result, self_, other);
+    }
     let mut iter = TensorIterator::binary_op(result, self_, other, true);
     super::cpu::mul_kernel(&mut iter);
+    iter.cast_outputs();
     return result;
 }
 pub fn mul(self_: &Tensor, other: &Tensor) -> Tensor {
+    if self_.is_sparse() || other.is_sparse() {
+        return super::mul_sparse(self_, other);
+    }
     let result = Tensor::default();
     let mut iter = TensorIterator::binary_op(&result, self_, other, false);
     super::cpu::mul_kernel(&mut iter);
@@ -88,6 +145,7 @@ pub fn mul(self_: &Tensor, other: &Tensor) -> Tensor {
 pub fn sub_out<'a>(result: &'a Tensor, self_: &Tensor, other: &Tensor) -> &'a Tensor {
     let mut iter = TensorIterator::binary_op(result, self_, other, true);
     super::cpu::sub_kernel(&mut iter);
+    iter.cast_outputs();
     return result;
 }
 pub fn sub(self_: &Tensor, other: &Tensor, _alpha: impl Into<Scalar>) -> Tensor {
@@ -0,0 +1,208 @@
+use super::{cpu, empty, make_tensor, zeros};
+use crate::c10::{
+    isFloatingType, is_complex_type, to_complex_type, to_value_type, ScalarType, TypeMeta,
+};
+use crate::tensor::{Tensor, TensorIterator, TensorIteratorConfig};
+
+/* ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~ Views ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~*/
+
+// View of `self_` with the given dtype, sharing its storage and version counter.
+// Strides and the storage offset are in elements of `dtype`.
+fn view_with_dtype(
+    self_: &Tensor,
+    dtype: &TypeMeta,
+    size: &[usize],
+    strides: &[usize],
+    storage_offset: usize,
+) -> Tensor {
+    let result = make_tensor(self_.storage(), dtype);
+    let impl_ = result.get_unsafe_tensor_impl();
+    impl_.set_storage_offset(storage_offset);
+    impl_.set_sizes_and_strides(size, strides);
+    impl_.set_version_counter(self_.get_unsafe_tensor_impl().version_counter().clone());
+    result
+}
+
+fn component_view(self_: &Tensor, component: usize) -> Tensor {
+    let strides: Vec<_> = self_.strides().iter().map(|s| s * 2).collect();
+    view_with_dtype(
+        self_,
+        &to_value_type(self_.scalar_type()).into(),
+        self_.sizes(),
+        &strides,
+        self_.storage_offset() * 2 + component,
+    )
+}
+
+// Real view of a complex tensor with an extra last dimension of size 2 holding the
+// real and imaginary parts.
+pub fn view_as_real(self_: &Tensor) -> Tensor {
+    assert!(
+        is_complex_type(self_.scalar_type()),
+        "view_as_real is only supported for complex tensors"
+    );
+    let mut sizes = self_.sizes().to_vec();
+    sizes.push(2);
+    let mut strides: Vec<_> = self_.strides().iter().map(|s| s * 2).collect();
+    strides.push(1);
+    view_with_dtype(
+        self_,
+        &to_value_type(self_.scalar_type()).into(),
+        &sizes,
+        &strides,
+        self_.storage_offset() * 2,
+    )
+}
+
+// Inverse of view_as_real.
+pub fn view_as_complex(self_: &Tensor) -> Tensor {
+    assert!(
+        self_.scalar_type() == ScalarType::Float
+            || self_.scalar_type() == ScalarType::Double,
+        "view_as_complex is only supported for Float and Double tensors, but got a tensor of scalar type: {:?}",
+        self_.scalar_type()
+    );
+    let (last, sizes) = self_
+        .sizes()
+        .split_last()
+        .expect("Input tensor must have one or more dimensions");
+    let (last_stride, strides) = self_.strides().split_last().unwrap();
+    assert!(*last == 2, "Tensor must have a last dimension of size 2");
+    assert!(
+        *last_stride == 1,
+        "Tensor must have a last dimension with stride 1"
+    );
+    assert!(
+        strides.iter().all(|s| s.is_multiple_of(2)) && self_.storage_offset().is_multiple_of(2),
+        "Tensor must have a stride divisible by 2 for all but last dimension and a storage offset divisible by 2"
+    );
+    let strides: Vec<_> = strides.iter().map(|s| s / 2).collect();
+    view_with_dtype(
+        self_,
+        &to_complex_type(self_.scalar_type()).into(),
+        sizes,
+        &strides,
+        self_.storage_offset() / 2,
+    )
+}
+
+// Real part of a complex tensor as a view, real tensors are returned as is.
+pub fn real(self_: &Tensor) -> Tensor {
+    if is_complex_type(self_.scalar_type()) {
+        component_view(self_, 0)
+    } else {
+        self_.clone()
+    }
+}
+
+pub fn imag(self_: &Tensor) -> Tensor {
+    assert!(
+        is_complex_type(self_.scalar_type()),
+        "imag is not implemented for tensors with non-complex dtypes."
+    );
+    component_view(self_, 1)
+}
+
+/* ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~ Unary ops ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~*/
+
+// Iterator writing a new tensor of `dtype` from `inputs`, which have the same dtype.
+fn iterator_with_output_dtype(inputs: &[&Tensor], dtype: ScalarType) -> (Tensor, TensorIterator) {
+    let result = empty(&[0], inputs[0].options().set_dtype_(dtype), None);
+    let mut config = TensorIteratorConfig::default();
+    config.check_all_same_dtype(false).add_output(&result);
+    for input in inputs {
+        config.add_input(input);
+    }
+    let iter = config.build();
+    (result, iter)
+}
+
+// Absolute value, real for complex tensors.
+pub fn abs(self_: &Tensor) -> Tensor {
+    let (result, mut iter) =
+        iterator_with_output_dtype(&[self_], to_value_type(self_.scalar_type()));
+    cpu::abs_kernel(&mut iter);
+    result
+}
+
+// Argument of complex numbers, 0 or pi for real ones.
+pub fn angle(self_: &Tensor) -> Tensor {
+    let dtype = self_.scalar_type();
+    assert!(
+        is_complex_type(dtype) || isFloatingType(dtype),
+        "angle is not implemented for tensors of scalar type {:?}",
+        dtype
+    );
+    let (result, mut iter) = iterator_with_output_dtype(&[self_], to_value_type(dtype));
+    cpu::angle_kernel(&mut iter);
+    result
+}
+
+// Complex conjugate, a copy for complex tensors. Real tensors are returned as is.
+pub fn conj(self_: &Tensor) -> Tensor {
+    if !is_complex_type(self_.scalar_type()) {
+        return self_.clone();
+    }
+    if self_.is_sparse() {
+        return super::sparse_with_values(self_, conj(&self_.values()));
+    }
+    if self_.is_sparse_csr() {
+        return super::sparse_csr_with_values(self_, conj(&self_.values()));
+    }
+    let (result, mut iter) = iterator_with_output_dtype(&[self_], self_.scalar_type());
+    cpu::conj_kernel(&mut iter);
+    result
+}
+
+pub fn sgn(self_: &Tensor) -> Tensor {
+    let (result, mut iter) = iterator_with_output_dtype(&[self_], self_.scalar_type());
+    cpu::sgn_kernel(&mut iter);
+    result
+}
+
+// grad * i * z / |z|^2, 0 where z is 0. The angle of real numbers is piecewise constant.
+pub fn angle_backward(grad: &Tensor, self_: &Tensor) -> Tensor {
+    if !is_complex_type(self_.scalar_type()) {
+        return zeros(self_.sizes(), self_.options());
+    }
+    let grad = grad.to_dtype(self_.scalar_type());
+    let (result, mut iter) = iterator_with_output_dtype(&[&grad, self_], self_.scalar_type());
+    cpu::angle_backward_kernel(&mut iter);
+    result
+}
+
+/* ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~ Factories ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~*/
+
+fn check_complex_inputs(name: &str, a: &Tensor, b: &Tensor) {
+    assert!(
+        a.scalar_type() == b.scalar_type(),
+        "Expected object of scalar type {:?} but got scalar type {:?} for second argument of {}",
+        a.scalar_type(),
+        b.scalar_type(),
+        name
+    );
+    assert!(
+        a.scalar_type() == ScalarType::Float || a.scalar_type() == ScalarType::Double,
+        "Expected both inputs of {} to be Float or Double tensors but got {:?}",
+        name,
+        a.scalar_type()
+    );
+}
+
+// abs * (cos(angle) + i sin(angle)).
+pub fn polar(abs: &Tensor, angle: &Tensor) -> Tensor {
+    check_complex_inputs("polar", abs, angle);
+    let (result, mut iter) =
+        iterator_with_output_dtype(&[abs, angle], to_complex_type(abs.scalar_type()));
+    cpu::polar_kernel(&mut iter);
+    result
+}
+
+// real + i imag.
+pub fn complex(real: &Tensor, imag: &Tensor) -> Tensor {
+    check_complex_inputs("complex", real, imag);
+    let (result, mut iter) =
+        iterator_with_output_dtype(&[real, imag], to_complex_type(real.scalar_type()));
+    cpu::complex_kernel(&mut iter);
+    result
+}
@@ -1,3 +1,4 @@
+use crate::util_autograd::increment_version;
 use crate::{
     c10::KCPU,
     tensor::{Tensor, TensorIteratorConfig},
@@ -42,5 +43,6 @@ fn copy_impl<'a>(self_: &'a Tensor, src: &Tensor, non_blocking: bool) -> &'a Ten
 
 pub fn copy_<'a>(self_: &'a Tensor, src: &Tensor, non_blocking: bool) -> &'a Tensor {
     copy_impl(self_, src, non_blocking);
+    increment_version(self_);
     return self_;
 }
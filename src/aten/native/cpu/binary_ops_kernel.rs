@@ -1,41 +1,150 @@
 use crate::aten::native::loops;
 use crate::tensor::TensorIterator;
 use crate::Closure;
-use crate::AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2;
 use crate::{c10::*, AT_DISPATCH_FLOATING_TYPES};
+use crate::{
+    AT_DISPATCH_FLOATING_AND_COMPLEX_TYPES_AND2, AT_DISPATCH_FLOATING_TYPES_AND2,
+    AT_DISPATCH_INTEGRAL_TYPES,
+};
+use num::{Float, Zero};
 
+// Integer arithmetic wraps around on overflow, like in C++.
 pub fn add_kernel(iter: &mut TensorIterator, alpha: Scalar) {
-    if iter.dtype() == ScalarType::Bool {
-        todo!()
-    } else {
-        AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2!(_, _, iter.dtype(), "add_cpu/sub_cpu", || {
+    let dtype = iter.dtype();
+    if dtype == ScalarType::Bool {
+        let alpha = alpha.to_bool();
+        loops::cpu_kernel(
+            iter,
+            Closure::new(|args: [bool; 2]| -> bool { args[0] || (alpha && args[1]) }),
+        )
+    } else if isIntegralType(dtype, false) {
+        AT_DISPATCH_INTEGRAL_TYPES!(dtype, "add_cpu/sub_cpu", || {
             let alpha: Scalart = alpha.to();
             loops::cpu_kernel_vec(
                 iter,
-                Closure::new(|args: [Scalart; 2]| -> Scalart { args[0] + alpha * args[1] }),
+                Closure::new(|args: [Scalart; 2]| -> Scalart {
+                    args[0].wrapping_add(alpha.wrapping_mul(args[1]))
+                }),
             )
         })
+    } else {
+        AT_DISPATCH_FLOATING_AND_COMPLEX_TYPES_AND2!(
+            Half,
+            BFloat16,
+            dtype,
+            "add_cpu/sub_cpu",
+            || {
+                let alpha: Scalart = alpha.to();
+                loops::cpu_kernel_vec(
+                    iter,
+                    Closure::new(|args: [Scalart; 2]| -> Scalart { args[0] + alpha * args[1] }),
+                )
+            }
+        )
     }
 }
 
+// True division, integer inputs are promoted to floating point by the iterator.
 pub fn div_kernel(iter: &mut TensorIterator) {
-    if isIntegralType(iter.dtype(), false) {
-        todo!()
+    AT_DISPATCH_FLOATING_AND_COMPLEX_TYPES_AND2!(Half, BFloat16, iter.dtype(), "div_cpu", || {
+        loops::cpu_kernel_vec(
+            iter,
+            Closure::new(|args: [Scalart; 2]| -> Scalart { args[0] / args[1] }),
+        )
+    })
+}
+
+// Division rounded towards zero.
+pub fn div_trunc_kernel(iter: &mut TensorIterator) {
+    let dtype = iter.dtype();
+    if isIntegralType(dtype, false) {
+        AT_DISPATCH_INTEGRAL_TYPES!(dtype, "div_trunc_cpu", || {
+            loops::cpu_kernel(
+                iter,
+                Closure::new(|args: [Scalart; 2]| -> Scalart {
+                    assert!(args[1] != 0, "ZeroDivisionError");
+                    args[0].wrapping_div(args[1])
+                }),
+            )
+        })
+    } else {
+        AT_DISPATCH_FLOATING_TYPES_AND2!(Half, BFloat16, dtype, "div_trunc_cpu", || {
+            loops::cpu_kernel_vec(
+                iter,
+                Closure::new(|args: [Scalart; 2]| -> Scalart { (args[0] / args[1]).trunc() }),
+            )
+        })
+    }
+}
+
+// Division rounded towards negative infinity, like Python's `//`.
+pub fn div_floor_kernel(iter: &mut TensorIterator) {
+    let dtype = iter.dtype();
+    if isIntegralType(dtype, false) {
+        AT_DISPATCH_INTEGRAL_TYPES!(dtype, "div_floor_cpu", || {
+            loops::cpu_kernel(
+                iter,
+                Closure::new(|args: [Scalart; 2]| -> Scalart {
+                    let (a, b) = (args[0], args[1]);
+                    assert!(b != 0, "ZeroDivisionError");
+                    let quot = a.wrapping_div(b);
+                    if (a < Scalart::zero()) != (b < Scalart::zero()) && a.wrapping_rem(b) != 0 {
+                        quot - 1
+                    } else {
+                        quot
+                    }
+                }),
+            )
+        })
     } else {
-        AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2!(_, _, iter.dtype(), "div_cpu", || {
+        AT_DISPATCH_FLOATING_TYPES_AND2!(Half, BFloat16, dtype, "div_floor_cpu", || {
             loops::cpu_kernel_vec(
                 iter,
-                Closure::new(|args: [Scalart; 2]| -> Scalart { args[0] / args[1] }),
+                Closure::new(|args: [Scalart; 2]| -> Scalart {
+                    div_floor_floating(args[0], args[1])
+                }),
             )
         })
     }
 }
 
+// Computes the quotient from the remainder so that it is consistent with fmod.
+fn div_floor_floating<T: Float>(a: T, b: T) -> T {
+    if b.is_zero() {
+        return a / b;
+    }
+    let rem = a % b;
+    let mut div = (a - rem) / b;
+    if !rem.is_zero() && (b < T::zero()) != (rem < T::zero()) {
+        div = div - T::one();
+    }
+    if div.is_zero() {
+        return T::zero().copysign(a / b);
+    }
+    let floor_div = div.floor();
+    if div - floor_div > T::from(0.5).unwrap() {
+        floor_div + T::one()
+    } else {
+        floor_div
+    }
+}
+
 pub fn mul_kernel(iter: &mut TensorIterator) {
-    if iter.dtype() == ScalarType::Bool {
-        // loops::cpu_kernel(iter, [=](bool a, bool b) -> bool { return a && b; });
+    let dtype = iter.dtype();
+    if dtype == ScalarType::Bool {
+        loops::cpu_kernel(
+            iter,
+            Closure::new(|args: [bool; 2]| -> bool { args[0] && args[1] }),
+        )
+    } else if isIntegralType(dtype, false) {
+        AT_DISPATCH_INTEGRAL_TYPES!(dtype, "mul_cpu", || {
+            loops::cpu_kernel_vec(
+                iter,
+                Closure::new(|args: [Scalart; 2]| -> Scalart { args[0].wrapping_mul(args[1]) }),
+            )
+        })
     } else {
-        AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2!(_, _, iter.dtype(), "mul_cpu", || {
+        AT_DISPATCH_FLOATING_AND_COMPLEX_TYPES_AND2!(Half, BFloat16, dtype, "mul_cpu", || {
             loops::cpu_kernel_vec(
                 iter,
                 Closure::new(|args: [Scalart; 2]| -> Scalart { args[0] * args[1] }),
@@ -45,10 +154,20 @@ pub fn mul_kernel(iter: &mut TensorIterator) {
 }
 
 pub fn sub_kernel(iter: &mut TensorIterator) {
-    if iter.dtype() == ScalarType::Bool {
-        todo!()
+    let dtype = iter.dtype();
+    assert!(
+        dtype != ScalarType::Bool,
+        "Subtraction, the `-` operator, with two bool tensors is not supported. Use the `^` or `logical_xor()` operator instead."
+    );
+    if isIntegralType(dtype, false) {
+        AT_DISPATCH_INTEGRAL_TYPES!(dtype, "sub_cpu", || {
+            loops::cpu_kernel_vec(
+                iter,
+                Closure::new(|args: [Scalart; 2]| -> Scalart { args[0].wrapping_sub(args[1]) }),
+            )
+        })
     } else {
-        AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2!(_, _, iter.dtype(), "sub_cpu", || {
+        AT_DISPATCH_FLOATING_AND_COMPLEX_TYPES_AND2!(Half, BFloat16, dtype, "sub_cpu", || {
             loops::cpu_kernel_vec(
                 iter,
                 Closure::new(|args: [Scalart; 2]| -> Scalart { args[0] - args[1] }),
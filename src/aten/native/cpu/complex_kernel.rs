@@ -0,0 +1,164 @@
+use crate::aten::native::loops;
+use crate::c10::{isIntegralType, is_complex_type, to_value_type};
+use crate::tensor::TensorIterator;
+use crate::Closure;
+use crate::{AT_DISPATCH_FLOATING_TYPES, AT_DISPATCH_INTEGRAL_TYPES};
+use num::complex::Complex;
+use num::Zero;
+
+// Complex kernels dispatch on the type of the real and imaginary parts, so `Scalart`
+// is f32 or f64 and the elements are `Complex<Scalart>`.
+
+pub fn abs_kernel(iter: &mut TensorIterator) {
+    let input_dtype = iter.dtype_(1);
+    if is_complex_type(input_dtype) {
+        AT_DISPATCH_FLOATING_TYPES!(to_value_type(input_dtype), "abs_cpu", || {
+            loops::cpu_kernel(
+                iter,
+                Closure::new(|args: [Complex<Scalart>; 1]| -> Scalart { args[0].norm() }),
+            )
+        })
+    } else if isIntegralType(input_dtype, false) {
+        AT_DISPATCH_INTEGRAL_TYPES!(input_dtype, "abs_cpu", || {
+            loops::cpu_kernel(
+                iter,
+                Closure::new(|args: [Scalart; 1]| -> Scalart {
+                    if args[0] < Scalart::zero() {
+                        args[0].wrapping_neg()
+                    } else {
+                        args[0]
+                    }
+                }),
+            )
+        })
+    } else {
+        AT_DISPATCH_FLOATING_TYPES!(input_dtype, "abs_cpu", || {
+            loops::cpu_kernel(
+                iter,
+                Closure::new(|args: [Scalart; 1]| -> Scalart { args[0].abs() }),
+            )
+        })
+    }
+}
+
+pub fn angle_kernel(iter: &mut TensorIterator) {
+    let input_dtype = iter.dtype_(1);
+    if is_complex_type(input_dtype) {
+        AT_DISPATCH_FLOATING_TYPES!(to_value_type(input_dtype), "angle_cpu", || {
+            loops::cpu_kernel(
+                iter,
+                Closure::new(|args: [Complex<Scalart>; 1]| -> Scalart { args[0].arg() }),
+            )
+        })
+    } else {
+        AT_DISPATCH_FLOATING_TYPES!(input_dtype, "angle_cpu", || {
+            loops::cpu_kernel(
+                iter,
+                Closure::new(|args: [Scalart; 1]| -> Scalart {
+                    let a = args[0];
+                    if a.is_nan() {
+                        a
+                    } else if a < 0.0 {
+                        std::f64::consts::PI as Scalart
+                    } else {
+                        0.0
+                    }
+                }),
+            )
+        })
+    }
+}
+
+// Inputs are the gradient, converted to complex, and the input of angle.
+pub fn angle_backward_kernel(iter: &mut TensorIterator) {
+    AT_DISPATCH_FLOATING_TYPES!(to_value_type(iter.dtype()), "angle_backward_cpu", || {
+        loops::cpu_kernel(
+            iter,
+            Closure::new(|args: [Complex<Scalart>; 2]| -> Complex<Scalart> {
+                let (grad, z) = (args[0], args[1]);
+                if z.is_zero() {
+                    z
+                } else {
+                    grad * Complex::i() * z / z.norm_sqr()
+                }
+            }),
+        )
+    })
+}
+
+pub fn conj_kernel(iter: &mut TensorIterator) {
+    AT_DISPATCH_FLOATING_TYPES!(to_value_type(iter.dtype()), "conj_cpu", || {
+        loops::cpu_kernel(
+            iter,
+            Closure::new(|args: [Complex<Scalart>; 1]| -> Complex<Scalart> { args[0].conj() }),
+        )
+    })
+}
+
+// z / |z| for complex numbers and the sign for real ones, 0 at 0.
+pub fn sgn_kernel(iter: &mut TensorIterator) {
+    let dtype = iter.dtype();
+    if is_complex_type(dtype) {
+        AT_DISPATCH_FLOATING_TYPES!(to_value_type(dtype), "sgn_cpu", || {
+            loops::cpu_kernel(
+                iter,
+                Closure::new(|args: [Complex<Scalart>; 1]| -> Complex<Scalart> {
+                    let z = args[0];
+                    if z.is_zero() {
+                        z
+                    } else {
+                        z / z.norm()
+                    }
+                }),
+            )
+        })
+    } else if isIntegralType(dtype, false) {
+        AT_DISPATCH_INTEGRAL_TYPES!(dtype, "sgn_cpu", || {
+            loops::cpu_kernel(
+                iter,
+                Closure::new(|args: [Scalart; 1]| -> Scalart {
+                    let a = args[0];
+                    (a > Scalart::zero()) as Scalart - (a < Scalart::zero()) as Scalart
+                }),
+            )
+        })
+    } else {
+        AT_DISPATCH_FLOATING_TYPES!(dtype, "sgn_cpu", || {
+            loops::cpu_kernel(
+                iter,
+                Closure::new(|args: [Scalart; 1]| -> Scalart {
+                    let a = args[0];
+                    if a > 0.0 {
+                        1.0
+                    } else if a < 0.0 {
+                        -1.0
+                    } else {
+                        0.0
+                    }
+                }),
+            )
+        })
+    }
+}
+
+pub fn polar_kernel(iter: &mut TensorIterator) {
+    AT_DISPATCH_FLOATING_TYPES!(iter.dtype_(1), "polar_cpu", || {
+        loops::cpu_kernel(
+            iter,
+            Closure::new(|args: [Scalart; 2]| -> Complex<Scalart> {
+                Complex::from_polar(args[0], args[1])
+            }),
+        )
+    })
+}
+
+pub fn complex_kernel(iter: &mut TensorIterator) {
+    AT_DISPATCH_FLOATING_TYPES!(iter.dtype_(1), "complex_cpu", || {
+        loops::cpu_kernel(
+            iter,
+            Closure::new(|args: [Scalart; 2]| -> Complex<Scalart> {
+                Complex::new(args[0], args[1])
+            }),
+        )
+    })
+}
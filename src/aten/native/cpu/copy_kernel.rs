@@ -1,23 +1,42 @@
+use num::Zero;
+
 use crate::{
     aten::native::{cpu_kernel, cpu_kernel_vec},
-    c10::{cast_with_inter_type, DeviceType},
+    c10::{cast_with_inter_type, DeviceType, ScalarType},
     tensor::TensorIterator,
-    Closure, AT_DISPATCH_ALL_TYPES_AND, AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2,
+    Closure, AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2, AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND3,
 };
 
 pub fn copy_kernel(_: DeviceType, iter: &mut TensorIterator, _non_blocking: bool) {
     let dtype = iter.dtype_(0);
     if dtype == iter.dtype_(1) {
-        AT_DISPATCH_ALL_TYPES_AND!(_, dtype, "copy_kernel", || {
+        AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND3!(Half, BFloat16, Bool, dtype, "copy_kernel", || {
             cpu_kernel_vec(
                 iter,
                 Closure::new(|args: [Scalart; 1]| -> Scalart { args[0] }),
             )
         });
+    } else if dtype == ScalarType::Bool {
+        // Any non zero value is true.
+        AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2!(Half, BFloat16, iter.dtype_(1), "copy_", || {
+            cpu_kernel(
+                iter,
+                Closure::new(|src: [Scalart; 1]| -> bool { !src[0].is_zero() }),
+            )
+        })
+    } else if iter.dtype_(1) == ScalarType::Bool {
+        AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2!(Half, BFloat16, dtype, "copy_", || {
+            cpu_kernel(
+                iter,
+                Closure::new(|src: [bool; 1]| -> Scalart {
+                    cast_with_inter_type::<u8, Scalart>(src[0] as u8)
+                }),
+            )
+        })
     } else {
-        AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2!(_, _, dtype, "copy_", || {
+        AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2!(Half, BFloat16, dtype, "copy_", || {
             type Dest = Scalart;
-            AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2!(_, _, iter.dtype_(1), "copy_", || {
+            AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2!(Half, BFloat16, iter.dtype_(1), "copy_", || {
                 let closure = Closure::new(|src: [Scalart; 1]| -> Dest {
                     cast_with_inter_type::<Scalart, Dest>(src[0])
                 });
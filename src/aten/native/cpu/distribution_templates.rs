@@ -1,14 +1,23 @@
 use crate::aten::native;
-use crate::c10::ScalarType;
+use crate::c10::{is_reduced_floating_point, ScalarType};
 use crate::core::*;
 use crate::tensor::{Tensor, TensorIterator};
 use crate::Closure;
 use crate::AT_DISPATCH_FLOATING_TYPES_AND2;
 
 pub fn uniform_kernel(mut iter: TensorIterator, from: f64, to: f64, gen: &mut dyn GeneratorImpl) {
-    AT_DISPATCH_FLOATING_TYPES_AND2!(_, _, iter.dtype(), "uniform_cpu_kernel", || {
-        let from = from as Scalart;
-        let to = to as Scalart;
+    AT_DISPATCH_FLOATING_TYPES_AND2!(Half, BFloat16, iter.dtype(), "uniform_cpu_kernel", || {
+        // Half and BFloat16 values are drawn as f32 and rounded.
+        if is_reduced_floating_point::<Scalart>() {
+            let uniform = UniformRealDistribution::new(from as f32, to as f32);
+            let closure = Closure::new(|_args: [f64; 0]| -> Scalart {
+                num::cast(uniform.call(gen)).unwrap()
+            });
+            native::cpu_serial_kernel(&mut iter, closure);
+            return;
+        }
+        let from: Scalart = num::cast(from).unwrap();
+        let to: Scalart = num::cast(to).unwrap();
         let uniform = UniformRealDistribution::new(from, to);
         let closure = Closure::new(|_args: [f64; 0]| uniform.call(gen));
         native::cpu_serial_kernel(&mut iter, closure);
@@ -24,28 +33,15 @@ impl NormalKernel {
 
 pub fn normal_kernel(self_: &Tensor, mean: f64, std: f64, gen: &mut dyn GeneratorImpl) {
     let size = self_.numel();
-    if self_.scalar_type() == ScalarType::Float && size >= 16 {
+    if self_.scalar_type() == ScalarType::Float && size >= 16 && self_.is_contiguous() {
         normal_fill(self_, mean as f32, std as f32, gen);
     } else {
         let the_type = self_.scalar_type();
         match the_type {
             ScalarType::Byte => {}
             ScalarType::Int => {}
-            ScalarType::Float => {
-                type Scalar = f32;
-                if size >= 16 && self_.is_contiguous() {
-                    normal_fill(self_, mean as Scalar, std as Scalar, gen);
-                } else {
-                    let mut iter = crate::tensor::TensorIterator::nullary_op(self_);
-
-                    let closure = Closure::new(|_args: [f64; 0]| {
-                        let normal = NormalDistribution::new(mean, std);
-                        normal.call(gen) as Scalar
-                    });
-                    crate::aten::native::cpu_serial_kernel(&mut iter, closure);
-                }
-            }
-            ScalarType::Double => {}
+            ScalarType::Float => normal_serial::<f32>(self_, mean, std, gen),
+            ScalarType::Double => normal_serial::<f64>(self_, mean, std, gen),
             _ => {}
         }
         //  const auto& the_type = self.scalar_type();
@@ -92,6 +88,57 @@ pub fn normal_kernel(self_: &Tensor, mean: f64, std: f64, gen: &mut dyn Generato
     }
 }
 
-fn normal_fill<T>(_self_: &Tensor, _mean: T, _std: T, _gen: &mut dyn GeneratorImpl) {
-    todo!()
+fn normal_serial<T>(self_: &Tensor, mean: f64, std: f64, gen: &mut dyn GeneratorImpl)
+where
+    T: num::Float + num::NumCast,
+{
+    if self_.numel() >= 16 && self_.is_contiguous() {
+        normal_fill(self_, T::from(mean).unwrap(), T::from(std).unwrap(), gen);
+    } else {
+        let mut iter = crate::tensor::TensorIterator::nullary_op(self_);
+        let closure = Closure::new(|_args: [f64; 0]| -> T {
+            let normal = NormalDistribution::new(mean, std);
+            T::from(normal.call(gen)).unwrap()
+        });
+        crate::aten::native::cpu_serial_kernel(&mut iter, closure);
+    }
+}
+
+// Box-Muller transform of 16 uniform samples in place, the first 8 give the radii and
+// the last 8 the angles.
+fn normal_fill_16<T: num::Float>(data: &mut [T], mean: T, std: T) {
+    let two = T::one() + T::one();
+    let two_pi = two * T::from(std::f64::consts::PI).unwrap();
+    for j in 0..8 {
+        // Maps [0, 1) to (0, 1] for the log.
+        let u1 = T::one() - data[j];
+        let u2 = data[j + 8];
+        let radius = (-two * u1.ln()).sqrt();
+        let theta = two_pi * u2;
+        data[j] = radius * theta.cos() * std + mean;
+        data[j + 8] = radius * theta.sin() * std + mean;
+    }
+}
+
+fn normal_fill<T>(self_: &Tensor, mean: T, std: T, gen: &mut dyn GeneratorImpl)
+where
+    T: num::Float + num::NumCast,
+{
+    let size = self_.numel();
+    let data = unsafe { std::slice::from_raw_parts_mut(self_.data_ptr_casted::<T>(), size) };
+    let uniform = UniformRealDistribution::new(T::zero(), T::one());
+    for x in data.iter_mut() {
+        *x = uniform.call(gen);
+    }
+    for i in (0..size - 15).step_by(16) {
+        normal_fill_16(&mut data[i..i + 16], mean, std);
+    }
+    if !size.is_multiple_of(16) {
+        // Recompute the last 16 values.
+        let tail = &mut data[size - 16..];
+        for x in tail.iter_mut() {
+            *x = uniform.call(gen);
+        }
+        normal_fill_16(tail, mean, std);
+    }
 }
@@ -1,5 +1,5 @@
 use crate::aten::native::loops;
-use crate::c10::{Scalar, ScalarType};
+use crate::c10::{BFloat16, ComplexDouble, ComplexFloat, Half, Scalar, ScalarType};
 use crate::tensor::TensorIterator;
 use crate::Closure;
 
@@ -30,6 +30,54 @@ pub fn fill_kernel(mut iter: TensorIterator, value: Scalar) {
             let closure = Closure::new(op);
             loops::cpu_kernel_vec(&mut iter, closure);
         }
+        ScalarType::Half => {
+            let value = value.to::<Half>();
+            let op = move |_args: [f32; 0]| value;
+            let closure = Closure::new(op);
+            loops::cpu_kernel_vec(&mut iter, closure);
+        }
+        ScalarType::BFloat16 => {
+            let value = value.to::<BFloat16>();
+            let op = move |_args: [f32; 0]| value;
+            let closure = Closure::new(op);
+            loops::cpu_kernel_vec(&mut iter, closure);
+        }
+        ScalarType::ComplexFloat => {
+            let value = value.to::<ComplexFloat>();
+            let op = move |_args: [f32; 0]| value;
+            let closure = Closure::new(op);
+            loops::cpu_kernel_vec(&mut iter, closure);
+        }
+        ScalarType::ComplexDouble => {
+            let value = value.to::<ComplexDouble>();
+            let op = move |_args: [f32; 0]| value;
+            let closure = Closure::new(op);
+            loops::cpu_kernel_vec(&mut iter, closure);
+        }
+        ScalarType::Byte => {
+            let value = value.to::<u8>();
+            let op = move |_args: [f32; 0]| value;
+            let closure = Closure::new(op);
+            loops::cpu_kernel_vec(&mut iter, closure);
+        }
+        ScalarType::Char => {
+            let value = value.to::<i8>();
+            let op = move |_args: [f32; 0]| value;
+            let closure = Closure::new(op);
+            loops::cpu_kernel_vec(&mut iter, closure);
+        }
+        ScalarType::Short => {
+            let value = value.to::<i16>();
+            let op = move |_args: [f32; 0]| value;
+            let closure = Closure::new(op);
+            loops::cpu_kernel_vec(&mut iter, closure);
+        }
+        ScalarType::Bool => {
+            let value = value.to_bool();
+            let op = move |_args: [f32; 0]| value;
+            let closure = Closure::new(op);
+            loops::cpu_kernel_vec(&mut iter, closure);
+        }
         _ => todo!(),
     }
 }
@@ -20,4 +20,7 @@ mod sum_kernel;
 pub use sum_kernel::*;
 
 mod softmax_kernel;
-pub use softmax_kernel::*;
\ No newline at end of file
+pub use softmax_kernel::*;
+
+mod complex_kernel;
+pub use complex_kernel::*;
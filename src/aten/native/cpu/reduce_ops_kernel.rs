@@ -5,7 +5,7 @@ use crate::{
         GRAIN_SIZE,
     },
     tensor::TensorIterator,
-    AT_DISPATCH_ALL_TYPES_AND, AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2,
+    AT_DISPATCH_ALL_TYPES, AT_DISPATCH_ALL_TYPES_AND,
 };
 use std::ptr::NonNull;
 
@@ -20,7 +20,7 @@ pub fn argmax_kernel_impl(iter: &TensorIterator) {
 }
 
 pub fn mean_kernel_impl(iter: &TensorIterator) {
-    AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2!(_, _, iter.dtype(), "mean_cpu", || {
+    AT_DISPATCH_ALL_TYPES!(iter.dtype(), "mean_cpu", || {
         let factor = iter.num_output_elements() as Scalart / iter.numel() as Scalart;
         binary_kernel_reduce(iter, MeanOps::<Scalart, Scalart>::new(factor), 0 as Scalart);
     });
@@ -1,9 +1,13 @@
-use num::Float;
+use num::{complex::Complex, Float, Zero};
 
 use crate::{
-    aten::native::basic_loop, util::vec256::Vec256, Closure, AT_DISPATCH_FLOATING_TYPES_AND2,
+    aten::native::basic_loop, util::vec256::Vec256, Closure, AT_DISPATCH_FLOATING_TYPES,
+    AT_DISPATCH_FLOATING_TYPES_AND2, AT_DISPATCH_INTEGRAL_TYPES,
+};
+use crate::{
+    c10::{isIntegralType, is_complex_type, is_reduced_floating_point, to_value_type, ScalarType},
+    tensor::TensorIterator,
 };
-use crate::{c10::isIntegralType, tensor::TensorIterator};
 use std::{ffi::c_void, marker::PhantomData, mem::size_of, ptr::NonNull};
 
 pub fn UNARY_OUTER_LOOP<F>(data: &mut [NonNull<u8>], strides: &[usize], n: usize, f: F)
@@ -20,9 +24,40 @@ where
 }
 
 pub fn sum_kernel_impl(iter: &TensorIterator) {
-    if isIntegralType(iter.dtype(), true) {}
-    AT_DISPATCH_FLOATING_TYPES_AND2!(_, _, iter.dtype(), "sum_cpu", move || {
-        iter.output().fill_(0 as Scalart);
+    if iter.dtype() == ScalarType::Bool {
+        // Sums of bool tensors are true if any element is.
+        iter.output().fill_(false);
+        iter.parallel_reduce(
+            |data: &[NonNull<u8>], strides: &[usize], size0: usize, size1: usize| {
+                elementwise_sum::<bool>(data, strides, size0, size1, |a, b| a || b)
+            },
+        );
+        return;
+    }
+    if isIntegralType(iter.dtype(), false) {
+        AT_DISPATCH_INTEGRAL_TYPES!(iter.dtype(), "sum_cpu", move || {
+            iter.output().fill_(0);
+            iter.parallel_reduce(
+                |data: &[NonNull<u8>], strides: &[usize], size0: usize, size1: usize| {
+                    elementwise_sum::<Scalart>(data, strides, size0, size1, Scalart::wrapping_add)
+                },
+            );
+        });
+        return;
+    }
+    if is_complex_type(iter.dtype()) {
+        AT_DISPATCH_FLOATING_TYPES!(to_value_type(iter.dtype()), "sum_cpu", move || {
+            iter.output().fill_(Complex::<Scalart>::zero());
+            iter.parallel_reduce(
+                |data: &[NonNull<u8>], strides: &[usize], size0: usize, size1: usize| {
+                    elementwise_sum::<Complex<Scalart>>(data, strides, size0, size1, |a, b| a + b)
+                },
+            );
+        });
+        return;
+    }
+    AT_DISPATCH_FLOATING_TYPES_AND2!(Half, BFloat16, iter.dtype(), "sum_cpu", move || {
+        iter.output().fill_(Scalart::zero());
         iter.parallel_reduce(
             |data: &[NonNull<u8>], strides: &[usize], mut size0: usize, mut size1: usize| {
                 let mut in_strides = [strides[1], strides[3]];
@@ -53,6 +88,10 @@ pub fn sum_kernel_impl(iter: &TensorIterator) {
                 }
                 let out_stride = out_strides[1];
                 assert_eq!(out_strides[0], 0);
+                if is_reduced_floating_point::<Scalart>() {
+                    f32_accumulated_sum::<Scalart>(data, in_strides, out_stride, size0, size1);
+                    return;
+                }
                 let vec256_size = Vec256::<Scalart>::size();
                 if in_strides[0] == std::mem::size_of::<Scalart>() && size0 >= vec256_size {
                     // Contiguous inner reduction
@@ -287,7 +326,7 @@ fn vectorized_inner_sum<T: Float>(
     size1: usize,
 ) {
     let vec_size = Vec256::<T>::size();
-    let size0_vec_size = Vec256::<T>::size();
+    let size0_vec_size = size0 / vec_size;
     let type_size = size_of::<T>();
     let vec_stride = vec_size * type_size;
     for j in 0..size1 {
@@ -370,6 +409,24 @@ fn vectorized_outer_sum<T: Float>(
     }
 }
 
+// Sums Half and BFloat16 inputs in f32, rounding once per output element.
+fn f32_accumulated_sum<T: Float>(
+    data: &[NonNull<u8>],
+    in_strides: [usize; 2],
+    out_stride: usize,
+    size0: usize,
+    size1: usize,
+) {
+    for j in 0..size1 {
+        let row_in = unsafe { data[1].as_ptr().add(j * in_strides[1]) };
+        let mut acc = 0f32;
+        for i in 0..size0 {
+            acc += load::<T>(row_in, in_strides[0], i).to_f32().unwrap();
+        }
+        accumulate_result(data[0].as_ptr(), out_stride, j, T::from(acc).unwrap());
+    }
+}
+
 fn scalar_outer_sum<T: Float>(
     data: &[NonNull<u8>],
     in_strides: [usize; 2],
@@ -394,8 +451,28 @@ fn scalar_outer_sum<T: Float>(
             break;
         }
         let row_in = unsafe { data[1].as_ptr().add(j * in_strides[1]) };
-        let ans = RowSum::<T>::call(row_in, in_strides[1], size0);
+        let ans = RowSum::<T>::call(row_in, in_strides[0], size0);
         accumulate_result(data[0].as_ptr(), out_stride, j, ans);
         j += 1;
     }
 }
+
+// Sums one element at a time, for the types without a vectorized sum. `strides` are
+// the output and input strides of the inner dimension followed by those of the outer one.
+fn elementwise_sum<T: Copy>(
+    data: &[NonNull<u8>],
+    strides: &[usize],
+    size0: usize,
+    size1: usize,
+    add: impl Fn(T, T) -> T,
+) {
+    for j in 0..size1 {
+        for i in 0..size0 {
+            unsafe {
+                let out = data[0].as_ptr().add(i * strides[0] + j * strides[2]) as *mut T;
+                let in_ = data[1].as_ptr().add(i * strides[1] + j * strides[3]) as *const T;
+                *out = add(*out, *in_);
+            }
+        }
+    }
+}
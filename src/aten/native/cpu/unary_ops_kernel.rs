@@ -1,9 +1,13 @@
 use crate::aten::native::{cpu, loops};
 use crate::aten::util;
+use crate::c10::{isIntegralType, ScalarType};
 use crate::core::{get_default_cpu_generator, Generator};
 use crate::tensor::{Tensor, TensorIterator};
 use crate::Closure;
-use crate::AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2;
+use crate::{
+    AT_DISPATCH_ALL_TYPES, AT_DISPATCH_FLOATING_AND_COMPLEX_TYPES_AND2, AT_DISPATCH_FLOATING_TYPES,
+    AT_DISPATCH_INTEGRAL_TYPES,
+};
 
 pub fn uniform_kernel(iter: TensorIterator, from: f64, to: f64, mut gen: Option<Generator>) {
     let default_gen = &mut get_default_cpu_generator();
@@ -18,12 +22,12 @@ pub fn normal_kernel(self_: &Tensor, mean: f64, std: f64, mut gen: Option<Genera
 }
 
 pub fn sigmoid_kernel(iter: &mut TensorIterator) {
-    AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2!(_, _, iter.dtype(), "sigmoid_cpu", || {
+    AT_DISPATCH_ALL_TYPES!(iter.dtype(), "sigmoid_cpu", || {
         loops::cpu_kernel_vec(
             iter,
             Closure::new(|args: [Scalart; 1]| -> Scalart {
                 let a = args[0];
-                let exp_a: Scalart = f32::exp(-a as f32) as Scalart;
+                let exp_a: Scalart = f64::exp(-num::cast::<Scalart, f64>(a).unwrap()) as Scalart;
                 let one: Scalart = 1 as Scalart;
                 one / (one + exp_a)
             }),
@@ -32,10 +36,33 @@ pub fn sigmoid_kernel(iter: &mut TensorIterator) {
 }
 
 pub fn neg_kernel(iter: &mut TensorIterator) {
-    AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2!(_, _, iter.dtype(), "neg_cpu", || {
+    let dtype = iter.dtype();
+    assert!(
+        dtype != ScalarType::Bool,
+        "Negation, the `-` operator, on a bool tensor is not supported. If you are trying to invert a mask, use the `!` operator instead."
+    );
+    if isIntegralType(dtype, false) {
+        AT_DISPATCH_INTEGRAL_TYPES!(dtype, "neg_cpu", || {
+            loops::cpu_kernel_vec(
+                iter,
+                Closure::new(|args: [Scalart; 1]| -> Scalart { args[0].wrapping_neg() }),
+            )
+        })
+    } else {
+        AT_DISPATCH_FLOATING_AND_COMPLEX_TYPES_AND2!(Half, BFloat16, dtype, "neg_cpu", || {
+            loops::cpu_kernel_vec(
+                iter,
+                Closure::new(|args: [Scalart; 1]| -> Scalart { -args[0] }),
+            )
+        })
+    }
+}
+
+pub fn exp_kernel(iter: &mut TensorIterator) {
+    AT_DISPATCH_FLOATING_TYPES!(iter.dtype(), "exp_cpu", || {
         loops::cpu_kernel_vec(
             iter,
-            Closure::new(|args: [Scalart; 1]| -> Scalart { -args[0] }),
+            Closure::new(|args: [Scalart; 1]| -> Scalart { args[0].exp() }),
         )
     })
 }
@@ -1,4 +1,7 @@
-use num::{cast, NumCast};
+use crate::c10::{ComplexDouble, ComplexFloat};
+use num::traits::{WrappingAdd, WrappingMul};
+use num::{cast, NumCast, PrimInt};
+use std::any::{Any, TypeId};
 use std::ffi::c_void;
 #[repr(i8)]
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -37,7 +40,7 @@ pub fn normalize_last_dims(
     }
 }
 
-pub fn gemm<T: NumCast>(
+pub fn gemm<T: NumCast + 'static>(
     trans_a: TransposeType,
     trans_b: TransposeType,
     m: usize,
@@ -53,8 +56,9 @@ pub fn gemm<T: NumCast>(
     mut ldc: usize,
 ) {
     normalize_last_dims(trans_a, trans_b, m, n, k, &mut lda, &mut ldb, &mut ldc);
-    match std::any::type_name::<T>() {
-        "f32" => gemm_f32(
+    let id = TypeId::of::<T>();
+    if id == TypeId::of::<f32>() {
+        gemm_f32(
             trans_a,
             trans_b,
             m,
@@ -68,8 +72,63 @@ pub fn gemm<T: NumCast>(
             cast(beta).unwrap(),
             c as *mut f32,
             cast(ldc).unwrap(),
-        ),
-        _ => todo!(),
+        )
+    } else if id == TypeId::of::<f64>() {
+        gemm_f64(
+            trans_a,
+            trans_b,
+            m,
+            n,
+            k,
+            cast(alpha).unwrap(),
+            a as *const f64,
+            cast(lda).unwrap(),
+            b as *const f64,
+            cast(ldb).unwrap(),
+            cast(beta).unwrap(),
+            c as *mut f64,
+            cast(ldc).unwrap(),
+        )
+    } else if let (Some(&alpha), Some(&beta)) = (
+        (&alpha as &dyn Any).downcast_ref::<ComplexFloat>(),
+        (&beta as &dyn Any).downcast_ref::<ComplexFloat>(),
+    ) {
+        gemm_complex_f32(
+            trans_a,
+            trans_b,
+            m,
+            n,
+            k,
+            alpha,
+            a as *const ComplexFloat,
+            lda,
+            b as *const ComplexFloat,
+            ldb,
+            beta,
+            c as *mut ComplexFloat,
+            ldc,
+        )
+    } else if let (Some(&alpha), Some(&beta)) = (
+        (&alpha as &dyn Any).downcast_ref::<ComplexDouble>(),
+        (&beta as &dyn Any).downcast_ref::<ComplexDouble>(),
+    ) {
+        gemm_complex_f64(
+            trans_a,
+            trans_b,
+            m,
+            n,
+            k,
+            alpha,
+            a as *const ComplexDouble,
+            lda,
+            b as *const ComplexDouble,
+            ldb,
+            beta,
+            c as *mut ComplexDouble,
+            ldc,
+        )
+    } else {
+        todo!()
     }
 }
 
@@ -107,3 +166,164 @@ pub fn gemm_f32(
         );
     }
 }
+
+#[allow(clippy::too_many_arguments)]
+fn gemm_f64(
+    trans_a: TransposeType,
+    trans_b: TransposeType,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: f64,
+    a: *const f64,
+    mut lda: usize,
+    b: *const f64,
+    mut ldb: usize,
+    beta: f64,
+    c: *mut f64,
+    mut ldc: usize,
+) {
+    normalize_last_dims(trans_a, trans_b, m, n, k, &mut lda, &mut ldb, &mut ldc);
+    unsafe {
+        blas_sys::dgemm_(
+            &(trans_a as i8),
+            &(trans_b as i8),
+            &(m as i32),
+            &(n as i32),
+            &(k as i32),
+            &alpha,
+            a,
+            &(lda as i32),
+            b,
+            &(ldb as i32),
+            &beta,
+            c,
+            &(ldc as i32),
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn gemm_complex_f32(
+    trans_a: TransposeType,
+    trans_b: TransposeType,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: ComplexFloat,
+    a: *const ComplexFloat,
+    mut lda: usize,
+    b: *const ComplexFloat,
+    mut ldb: usize,
+    beta: ComplexFloat,
+    c: *mut ComplexFloat,
+    mut ldc: usize,
+) {
+    normalize_last_dims(trans_a, trans_b, m, n, k, &mut lda, &mut ldb, &mut ldc);
+    // Complex<T> is repr(C) and has the layout of BLAS complex numbers.
+    unsafe {
+        blas_sys::cgemm_(
+            &(trans_a as i8),
+            &(trans_b as i8),
+            &(m as i32),
+            &(n as i32),
+            &(k as i32),
+            &alpha as *const ComplexFloat as *const _,
+            a as *const _,
+            &(lda as i32),
+            b as *const _,
+            &(ldb as i32),
+            &beta as *const ComplexFloat as *const _,
+            c as *mut _,
+            &(ldc as i32),
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn gemm_complex_f64(
+    trans_a: TransposeType,
+    trans_b: TransposeType,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: ComplexDouble,
+    a: *const ComplexDouble,
+    mut lda: usize,
+    b: *const ComplexDouble,
+    mut ldb: usize,
+    beta: ComplexDouble,
+    c: *mut ComplexDouble,
+    mut ldc: usize,
+) {
+    normalize_last_dims(trans_a, trans_b, m, n, k, &mut lda, &mut ldb, &mut ldc);
+    // Complex<T> is repr(C) and has the layout of BLAS complex numbers.
+    unsafe {
+        blas_sys::zgemm_(
+            &(trans_a as i8),
+            &(trans_b as i8),
+            &(m as i32),
+            &(n as i32),
+            &(k as i32),
+            &alpha as *const ComplexDouble as *const _,
+            a as *const _,
+            &(lda as i32),
+            b as *const _,
+            &(ldb as i32),
+            &beta as *const ComplexDouble as *const _,
+            c as *mut _,
+            &(ldc as i32),
+        );
+    }
+}
+
+// Reference gemm for the integral types BLAS does not support, with arithmetic that
+// wraps around on overflow. Like in BLAS, c is not read when beta is zero.
+#[allow(clippy::too_many_arguments)]
+pub fn gemm_integral<T: PrimInt + WrappingAdd + WrappingMul>(
+    trans_a: TransposeType,
+    trans_b: TransposeType,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: T,
+    a: *const c_void,
+    mut lda: usize,
+    b: *const c_void,
+    mut ldb: usize,
+    beta: T,
+    c: *mut c_void,
+    mut ldc: usize,
+) {
+    normalize_last_dims(trans_a, trans_b, m, n, k, &mut lda, &mut ldb, &mut ldc);
+    let (a, b, c) = (a as *const T, b as *const T, c as *mut T);
+    for j in 0..n {
+        for i in 0..m {
+            let mut dot = T::zero();
+            for l in 0..k {
+                let (a_il, b_lj) = unsafe {
+                    (
+                        *a.add(if trans_a == TransposeType::NoTranspose {
+                            i + l * lda
+                        } else {
+                            l + i * lda
+                        }),
+                        *b.add(if trans_b == TransposeType::NoTranspose {
+                            l + j * ldb
+                        } else {
+                            j + l * ldb
+                        }),
+                    )
+                };
+                dot = dot.wrapping_add(&a_il.wrapping_mul(&b_lj));
+            }
+            let out = unsafe { &mut *c.add(i + j * ldc) };
+            *out = if beta.is_zero() {
+                alpha.wrapping_mul(&dot)
+            } else {
+                beta.wrapping_mul(out)
+                    .wrapping_add(&alpha.wrapping_mul(&dot))
+            };
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::aten::native::cpu;
 use crate::core::Generator;
 use crate::tensor::{Tensor, TensorIterator};
+use crate::util_autograd::increment_version;
 
 pub fn uniform_impl_(self_: &Tensor, from: f64, to: f64, generator: Option<Generator>) {
     // Todo: Pytorch does boundary checking on the from and to values here.
@@ -11,4 +12,16 @@ pub fn uniform_impl_(self_: &Tensor, from: f64, to: f64, generator: Option<Gener
         None => None,
     };
     cpu::uniform_kernel(iter, from, to, gen_);
+    increment_version(self_);
+}
+
+pub fn normal_impl_(self_: &Tensor, mean: f64, std: f64, generator: Option<Generator>) {
+    assert!(
+        std >= 0.0,
+        "normal expects std >= 0.0, but found std {}",
+        std
+    );
+    let gen_ = generator.map(|g| g.as_with_cpu_impl());
+    cpu::normal_kernel(self_, mean, std, gen_);
+    increment_version(self_);
 }
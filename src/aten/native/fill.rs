@@ -1,9 +1,13 @@
 use num::FromPrimitive;
 
 use crate::tensor::{Tensor, TensorIterator};
-use crate::{c10::Scalar, AT_DISPATCH_ALL_TYPES_AND};
+use crate::util_autograd::increment_version;
+use crate::{
+    c10::{Scalar, ScalarType},
+    AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2,
+};
 
-fn fill_fast<T: FromPrimitive>(self_: &Tensor, value: Scalar) {
+fn fill_fast<T: FromPrimitive + 'static>(self_: &Tensor, value: Scalar) {
     let value = value.to::<T>();
     let dptr = self_.data_ptr().as_ptr() as *mut T;
     unsafe {
@@ -14,14 +18,25 @@ fn fill_fast<T: FromPrimitive>(self_: &Tensor, value: Scalar) {
 pub fn fill_out(self_: &Tensor, value: Scalar) {
     //Todo: add complex number condition here
     if self_.device().is_cpu() && self_.numel() == 1 {
-        AT_DISPATCH_ALL_TYPES_AND!(_, self_.scalar_type(), "fill_out", || {
-            fill_fast::<Scalart>(self_, value);
-        });
+        if self_.scalar_type() == ScalarType::Bool {
+            unsafe { *self_.data_ptr_casted::<bool>() = value.to_bool() };
+            return;
+        }
+        AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2!(
+            Half,
+            BFloat16,
+            self_.scalar_type(),
+            "fill_out",
+            || {
+                fill_fast::<Scalart>(self_, value);
+            }
+        );
     } else {
         let iter = TensorIterator::nullary_op(self_);
         super::cpu::fill_kernel(iter, value.into());
     }
 }
 pub fn fill_(self_: &Tensor, value: impl Into<Scalar>) {
-    fill_out(self_, value.into())
+    fill_out(self_, value.into());
+    increment_version(self_);
 }
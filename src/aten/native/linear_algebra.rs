@@ -1,7 +1,7 @@
 use crate::aten::{self, native};
-use crate::c10::Scalar;
+use crate::c10::{isIntegralType, Scalar, ScalarType};
 use crate::tensor::Tensor;
-use crate::AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2;
+use crate::{AT_DISPATCH_FLOATING_AND_COMPLEX_TYPES_AND2, AT_DISPATCH_INTEGRAL_TYPES};
 
 // use crate::ndarry_ext;
 
@@ -88,8 +88,9 @@ pub fn addmm_impl_cpu_(
         transpose_a = true;
         a = m1.clone();
     } else {
+        // Strides BLAS can't describe, e.g. expanded tensors, need a copy.
         transpose_a = !transpose_c;
-        a = m1.clone();
+        a = m1.contiguous();
     }
 
     // Cast m2 as matrix b
@@ -108,7 +109,7 @@ pub fn addmm_impl_cpu_(
         b = m2.clone();
     } else {
         transpose_b = !transpose_c;
-        b = m2.clone();
+        b = m2.contiguous();
     }
     // dbg!(transpose_c, transpose_a, transpose_b);
 
@@ -116,32 +117,59 @@ pub fn addmm_impl_cpu_(
     let ldb = b.strides()[if transpose_b == transpose_c { 1 } else { 0 }];
     let ldc = c.strides()[if transpose_c { 0 } else { 1 }];
 
-    // Apply BLAS routine
-    AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2!(_, _, result.scalar_type(), "addmm_impl_cpu_", || {
-        native::cpublas::gemm(
-            if transpose_a {
-                native::cpublas::TransposeType::Transpose
-            } else {
-                native::cpublas::TransposeType::NoTranspose
-            },
-            if transpose_b {
-                native::cpublas::TransposeType::Transpose
-            } else {
-                native::cpublas::TransposeType::NoTranspose
-            },
-            m,
-            n,
-            k,
-            alpha.to::<Scalart>(),
-            m1.data_ptr().as_ptr(),
-            lda,
-            m2.data_ptr().as_ptr(),
-            ldb,
-            beta.to::<Scalart>(),
-            c.data_ptr().as_ptr(),
-            ldc,
-        );
-    });
+    let trans_a = if transpose_a {
+        native::cpublas::TransposeType::Transpose
+    } else {
+        native::cpublas::TransposeType::NoTranspose
+    };
+    let trans_b = if transpose_b {
+        native::cpublas::TransposeType::Transpose
+    } else {
+        native::cpublas::TransposeType::NoTranspose
+    };
+    let dtype = result.scalar_type();
+    assert!(
+        dtype != ScalarType::Bool,
+        "addmm_impl_cpu_ is not implemented for Bool tensors"
+    );
+    if isIntegralType(dtype, false) {
+        AT_DISPATCH_INTEGRAL_TYPES!(dtype, "addmm_impl_cpu_", || {
+            native::cpublas::gemm_integral(
+                trans_a,
+                trans_b,
+                m,
+                n,
+                k,
+                alpha.to::<Scalart>(),
+                a.data_ptr().as_ptr(),
+                lda,
+                b.data_ptr().as_ptr(),
+                ldb,
+                beta.to::<Scalart>(),
+                c.data_ptr().as_ptr(),
+                ldc,
+            );
+        });
+    } else {
+        // Apply BLAS routine
+        AT_DISPATCH_FLOATING_AND_COMPLEX_TYPES_AND2!(_, _, dtype, "addmm_impl_cpu_", || {
+            native::cpublas::gemm(
+                trans_a,
+                trans_b,
+                m,
+                n,
+                k,
+                alpha.to::<Scalart>(),
+                a.data_ptr().as_ptr(),
+                lda,
+                b.data_ptr().as_ptr(),
+                ldb,
+                beta.to::<Scalart>(),
+                c.data_ptr().as_ptr(),
+                ldc,
+            );
+        });
+    }
     if !c.is_same(result) {
         result.copy(&c, None);
     }
@@ -201,6 +229,12 @@ pub fn matmul(tensor1: &Tensor, tensor2: &Tensor, consume: bool) -> Tensor {
 // }
 
 pub fn mm_cpu(mat1: &Tensor, mat2: &Tensor) -> Tensor {
+    if mat1.is_sparse() {
+        return super::sparse_mm(mat1, mat2);
+    }
+    if mat1.is_sparse_csr() {
+        return super::sparse_csr_mm(mat1, mat2);
+    }
     assert_eq!(mat1.dim(), 2);
     assert_eq!(mat1.dim(), 2);
 
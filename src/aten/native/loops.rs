@@ -5,7 +5,7 @@ use std::ptr::NonNull;
 
 // struct IsContiguous<N,SI,T,S>;
 
-pub fn cpu_serial_kernel<I: Copy, O: num::cast::NumCast, F, const N: usize>(
+pub fn cpu_serial_kernel<I: Copy, O: Copy, F, const N: usize>(
     iter: &mut TensorIterator,
     mut op: Closure<I, O, F, N>,
 ) where
@@ -111,7 +111,7 @@ fn unroll_contiguous_scalar_checks(
             input_type_size,
             output_type_size,
         ) {
-            cb(range_start + 1);
+            cb(range_start);
         } else {
             unroll_contiguous_scalar_checks(
                 strides,
@@ -126,7 +126,7 @@ fn unroll_contiguous_scalar_checks(
         cb(0)
     }
 }
-pub fn cpu_kernel<I: Copy, O: num::cast::NumCast, F, const N: usize>(
+pub fn cpu_kernel<I: Copy, O: Copy, F, const N: usize>(
     iter: &mut TensorIterator,
     mut op: Closure<I, O, F, N>,
 ) where
@@ -142,7 +142,7 @@ pub fn cpu_kernel<I: Copy, O: num::cast::NumCast, F, const N: usize>(
             basic_loop(data, strides, 0, n, &mut op);
         } else {
             let arity = op.arity();
-            let indices = 0..arity;
+            let indices = 1..arity + 1;
             let (in_size, out_size) = (op.input_type_size(), op.output_type_size());
             unroll_contiguous_scalar_checks(
                 strides,
@@ -156,7 +156,7 @@ pub fn cpu_kernel<I: Copy, O: num::cast::NumCast, F, const N: usize>(
     };
     iter.for_each(closure);
 }
-pub fn cpu_kernel_vec<I: Copy, O: num::cast::NumCast, F, const N: usize>(
+pub fn cpu_kernel_vec<I: Copy, O: Copy, F, const N: usize>(
     iter: &mut TensorIterator,
     mut op: Closure<I, O, F, N>,
 ) where
@@ -172,7 +172,7 @@ pub fn cpu_kernel_vec<I: Copy, O: num::cast::NumCast, F, const N: usize>(
             vectorized_loop(data, n, 0, &mut op);
         } else {
             let arity = op.arity();
-            let indices = 0..arity;
+            let indices = 1..arity + 1;
             let (in_size, out_size) = (op.input_type_size(), op.output_type_size());
             unroll_contiguous_scalar_checks(
                 strides,
@@ -192,7 +192,7 @@ pub fn cpu_kernel_vec<I: Copy, O: num::cast::NumCast, F, const N: usize>(
     };
     iter.for_each(closure);
 }
-pub fn vectorized_loop<I: Copy, O: num::cast::NumCast, F, const N: usize>(
+pub fn vectorized_loop<I: Copy, O: Copy, F, const N: usize>(
     data_: &[NonNull<u8>],
     n: usize,
     s: usize,
@@ -220,7 +220,7 @@ pub fn vectorized_loop<I: Copy, O: num::cast::NumCast, F, const N: usize>(
     }
 }
 
-pub fn basic_loop<I: Copy, O: num::cast::NumCast, F, const N: usize>(
+pub fn basic_loop<I: Copy, O: Copy, F, const N: usize>(
     data: &[NonNull<u8>],
     strides_: &[usize],
     i: usize,
@@ -237,7 +237,7 @@ pub fn basic_loop<I: Copy, O: num::cast::NumCast, F, const N: usize>(
     execute_op(data, strides.as_slice(), i, n, op);
 }
 
-pub fn execute_op<I: Copy, O: num::cast::NumCast, F, const N: usize>(
+pub fn execute_op<I: Copy, O: Copy, F, const N: usize>(
     data: &[NonNull<u8>],
     strides: &[usize],
     mut i: usize,
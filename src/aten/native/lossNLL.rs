@@ -183,7 +183,7 @@ fn nll_loss_out_frame<T: Float>(
                     T::one()
                 };
 
-                output_acc[i] = input_acc.index(i)[cur_target as usize] * cur_weight;
+                output_acc[i] = -input_acc.index(i)[cur_target as usize] * cur_weight;
             }
         });
 
@@ -302,7 +302,8 @@ fn nll_loss_backward_out_frame<T: Float>(
                 let mut t = grad_input_acc.index(i);
                 t[cur_target as usize] = -w * grad_output_acc[i];
             }
-        })
+        });
+        return;
     }
     let total_weight_value = unsafe { total_weight.data_ptr_casted::<T>().read() };
     if total_weight_value <= T::zero() {
@@ -56,6 +56,15 @@ pub use softmax::*;
 mod lossNLL;
 pub use lossNLL::*;
 
+mod complex;
+pub use complex::*;
+
+mod sparse;
+pub use sparse::*;
+
+mod sparse_csr;
+pub use sparse_csr::*;
+
 use crate::c10::*;
 use crate::tensor::Tensor;
 #[derive(Default, Clone)]
@@ -75,6 +84,9 @@ pub fn update_result_type_state(tensor: &Tensor, in_state: ResultTypeState) -> R
         let current_default = type_meta_to_scalar_type(&get_default_dtype());
         if isFloatingType(current) {
             current = current_default;
+        } else if is_complex_type(current) {
+            // Wrapped complex numbers use the complex counterpart of the default dtype.
+            current = to_complex_type(current_default);
         }
     }
     if tensor.dim() > 0 {
@@ -98,8 +110,37 @@ fn promote_skip_undefined(a: ScalarType, b: ScalarType) -> ScalarType {
     promote_types(a, b)
 }
 
+// Position of a dtype in the rows and columns of the promote_types lookup table,
+// which differs from the declaration order of ScalarType.
+#[inline(always)]
+fn promote_types_index(t: ScalarType) -> usize {
+    match t {
+        ScalarType::Byte => 0,
+        ScalarType::Char => 1,
+        ScalarType::Short => 2,
+        ScalarType::Int => 3,
+        ScalarType::Long => 4,
+        ScalarType::Half => 5,
+        ScalarType::Float => 6,
+        ScalarType::Double => 7,
+        ScalarType::ComplexHalf => 8,
+        ScalarType::ComplexFloat => 9,
+        ScalarType::ComplexDouble => 10,
+        ScalarType::Bool => 11,
+        ScalarType::QInt8 => 12,
+        ScalarType::QUInt8 => 13,
+        ScalarType::QInt32 => 14,
+        ScalarType::BFloat16 => 15,
+        ScalarType::Undefined | ScalarType::NumOptions => {
+            panic!("{:?} has no type promotion rules", t)
+        }
+    }
+}
+
+// The smallest dtype both `a` and `b` can be safely cast to, e.g. Byte and Char give
+// Short. Panics for quantized dtypes unless both are the same.
 #[inline(always)]
-fn promote_types(a: ScalarType, b: ScalarType) -> ScalarType {
+pub fn promote_types(a: ScalarType, b: ScalarType) -> ScalarType {
     // This is generated according to NumPy's promote_types
     let u1 = ScalarType::Byte;
     let i1 = ScalarType::Char;
@@ -131,58 +172,57 @@ fn promote_types(a: ScalarType, b: ScalarType) -> ScalarType {
           b);
     }
 
-    // this matrix has to be consistent with AT_FORALL_SCALAR_TYPES_WITH_COMPLEX
-    // so that's why we have to add undefined as we are not sure what is the
-    // corrent values for the type promotions in complex type cases.
+    // this matrix has to be consistent with AT_FORALL_SCALAR_TYPES_WITH_COMPLEX.
+    // Half and BFloat16 only meet at Float, as neither can represent the other.
     let promote_types_lookup = [
         /*        u1  i1  i2  i4  i8  f2  f4  f8  c2  c4  c8  b1  q1  q2  q3  bf*/
         /* u1 */
         [
-            u1, i2, i2, i4, i8, f2, f4, f8, ud, c4, c8, u1, ud, ud, ud, ud,
+            u1, i2, i2, i4, i8, f2, f4, f8, c2, c4, c8, u1, ud, ud, ud, bf,
         ],
         /* i1 */
         [
-            i2, i1, i2, i4, i8, f2, f4, f8, ud, c4, c8, i1, ud, ud, ud, ud,
+            i2, i1, i2, i4, i8, f2, f4, f8, c2, c4, c8, i1, ud, ud, ud, bf,
         ],
         /* i2 */
         [
-            i2, i2, i2, i4, i8, f2, f4, f8, ud, c4, c8, i2, ud, ud, ud, ud,
+            i2, i2, i2, i4, i8, f2, f4, f8, c2, c4, c8, i2, ud, ud, ud, bf,
         ],
         /* i4 */
         [
-            i4, i4, i4, i4, i8, f2, f4, f8, ud, c4, c8, i4, ud, ud, ud, ud,
+            i4, i4, i4, i4, i8, f2, f4, f8, c2, c4, c8, i4, ud, ud, ud, bf,
         ],
         /* i8 */
         [
-            i8, i8, i8, i8, i8, f2, f4, f8, ud, c4, c8, i8, ud, ud, ud, ud,
+            i8, i8, i8, i8, i8, f2, f4, f8, c2, c4, c8, i8, ud, ud, ud, bf,
         ],
         /* f2 */
         [
-            f2, f2, f2, f2, f2, f2, f4, f8, ud, c4, c8, f2, ud, ud, ud, ud,
+            f2, f2, f2, f2, f2, f2, f4, f8, c2, c4, c8, f2, ud, ud, ud, f4,
         ],
         /* f4 */
         [
-            f4, f4, f4, f4, f4, f4, f4, f8, ud, c4, c8, f4, ud, ud, ud, ud,
+            f4, f4, f4, f4, f4, f4, f4, f8, c4, c4, c8, f4, ud, ud, ud, f4,
         ],
         /* f8 */
         [
-            f8, f8, f8, f8, f8, f8, f8, f8, ud, c8, c8, f8, ud, ud, ud, ud,
+            f8, f8, f8, f8, f8, f8, f8, f8, c8, c8, c8, f8, ud, ud, ud, f8,
         ],
         /* c2 */
         [
-            ud, ud, ud, ud, ud, ud, ud, ud, c2, c4, c8, ud, ud, ud, ud, ud,
+            c2, c2, c2, c2, c2, c2, c4, c8, c2, c4, c8, c2, ud, ud, ud, c4,
         ],
         /* c4 */
         [
-            c4, c4, c4, c4, c4, c4, c4, c8, c4, c4, c8, c4, ud, ud, ud, ud,
+            c4, c4, c4, c4, c4, c4, c4, c8, c4, c4, c8, c4, ud, ud, ud, c4,
         ],
         /* c8 */
         [
-            c8, c8, c8, c8, c8, c8, c8, c8, c8, c8, c8, c8, ud, ud, ud, ud,
+            c8, c8, c8, c8, c8, c8, c8, c8, c8, c8, c8, c8, ud, ud, ud, c8,
         ],
         /* b1 */
         [
-            u1, i1, i2, i4, i8, f2, f4, f8, ud, c4, c8, b1, ud, ud, ud, ud,
+            u1, i1, i2, i4, i8, f2, f4, f8, c2, c4, c8, b1, ud, ud, ud, bf,
         ],
         /* q1 */
         [
@@ -198,10 +238,10 @@ fn promote_types(a: ScalarType, b: ScalarType) -> ScalarType {
         ],
         /* bf */
         [
-            ud, ud, ud, ud, ud, ud, ud, ud, ud, ud, ud, ud, ud, ud, ud, bf,
+            bf, bf, bf, bf, bf, f4, f4, f8, c4, c4, c8, bf, ud, ud, ud, bf,
         ],
     ];
-    return promote_types_lookup[a as usize][b as usize];
+    return promote_types_lookup[promote_types_index(a)][promote_types_index(b)];
 }
 
 pub fn result_type(in_state: &ResultTypeState) -> ScalarType {
@@ -215,10 +255,17 @@ pub fn result_type(in_state: &ResultTypeState) -> ScalarType {
 fn combine_categories(higher: ScalarType, lower: ScalarType) -> ScalarType {
     if is_complex_type(higher) {
         return higher;
-    } else if !is_complex_type(lower) && isFloatingType(higher) {
+    } else if is_complex_type(lower) {
+        // Preserve the value type of higher if it is floating. There are no kernels for
+        // ComplexHalf, so Half and BFloat16 promote through the lookup table instead.
+        if higher == ScalarType::Float || higher == ScalarType::Double {
+            return to_complex_type(higher);
+        }
+        return promote_skip_undefined(higher, lower);
+    } else if isFloatingType(higher) {
         return higher;
     }
-    if higher == ScalarType::Bool || isFloatingType(lower) || is_complex_type(lower) {
+    if higher == ScalarType::Bool || isFloatingType(lower) {
         return promote_skip_undefined(higher, lower);
     }
     if higher != ScalarType::Undefined {
@@ -122,6 +122,9 @@ pub fn mean_cpu_gpu_(
 }
 
 pub fn sum(self_: &Tensor, dtype: Option<ScalarType>) -> Tensor {
+    if self_.is_sparse() || self_.is_sparse_csr() {
+        return sum(&self_.values(), dtype);
+    }
     return sum_dim_int_list(self_, vec![], false, dtype);
 }
 
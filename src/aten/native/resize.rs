@@ -16,11 +16,19 @@ pub fn storage_size_for(size: &[usize], stride: &[usize]) -> usize {
 pub fn resize<'a>(
     self_: &'a Tensor,
     size: &[usize],
-    _optional_memory_format: Option<crate::c10::MemoryFormat>,
+    optional_memory_format: Option<MemoryFormat>,
 ) -> &'a Tensor {
     let stride = None;
     let impl_ = self_.get_unsafe_tensor_impl();
     resize_impl_cpu(impl_, size, stride);
+    if let Some(memory_format) = optional_memory_format {
+        assert!(
+            memory_format != MemoryFormat::Preserve,
+            "Unsupported memory format {:?}",
+            memory_format
+        );
+        impl_.empty_tensor_restride(memory_format);
+    }
     self_
 }
 
@@ -0,0 +1,654 @@
+use super::{empty_cpu, promote_types, wrapped_scalar_tensor, zeros};
+use crate::c10::{can_cast, isIntegralType, ComplexDouble, Scalar, ScalarType, TensorOptions};
+use crate::tensor::{SparseParts, Tensor, TensorImpl};
+use crate::util_autograd::increment_version;
+use crate::{AT_DISPATCH_FLOATING_AND_COMPLEX_TYPES_AND2, AT_DISPATCH_INTEGRAL_TYPES};
+
+fn sparse_parts(self_: &Tensor) -> SparseParts {
+    assert!(self_.is_sparse(), "expected a sparse tensor");
+    self_.get_unsafe_tensor_impl().sparse_parts().clone()
+}
+
+pub(super) fn new_with_parts(
+    size: &[usize],
+    indices: Tensor,
+    values: Tensor,
+    sparse_dim: usize,
+    coalesced: bool,
+) -> Tensor {
+    Tensor::from_impl(TensorImpl::new_sparse(
+        size,
+        SparseParts {
+            indices,
+            values,
+            sparse_dim,
+            coalesced,
+        },
+    ))
+}
+
+// A sparse tensor with the indices of `self_` and other values for them.
+pub fn sparse_with_values(self_: &Tensor, values: Tensor) -> Tensor {
+    let parts = sparse_parts(self_);
+    new_with_parts(
+        self_.sizes(),
+        parts.indices,
+        values,
+        parts.sparse_dim,
+        parts.coalesced,
+    )
+}
+
+pub(super) fn long_tensor(data: &[i64], size: &[usize]) -> Tensor {
+    let result = empty_cpu(size, TensorOptions::with_dtype(ScalarType::Long), None);
+    if !data.is_empty() {
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                data.as_ptr(),
+                result.data_ptr_casted::<i64>(),
+                data.len(),
+            )
+        };
+    }
+    result
+}
+
+pub(super) fn indices_data(indices: &Tensor) -> Vec<i64> {
+    if indices.numel() == 0 {
+        return vec![];
+    }
+    let indices = indices.contiguous();
+    unsafe {
+        std::slice::from_raw_parts(indices.data_ptr_casted::<i64>(), indices.numel()).to_vec()
+    }
+}
+
+// Row major positions of the indices within the sparse dimensions.
+fn flatten_indices(indices: &[i64], nnz: usize, sparse_sizes: &[usize]) -> Vec<usize> {
+    let mut flat = vec![0; nnz];
+    for (d, size) in sparse_sizes.iter().enumerate() {
+        for (k, f) in flat.iter_mut().enumerate() {
+            *f = *f * size + indices[d * nnz + k] as usize;
+        }
+    }
+    flat
+}
+
+fn unflatten_indices(flat: &[usize], sparse_sizes: &[usize]) -> Tensor {
+    let nnz = flat.len();
+    let mut data = vec![0i64; sparse_sizes.len() * nnz];
+    for (k, f) in flat.iter().enumerate() {
+        let mut f = *f;
+        for (d, size) in sparse_sizes.iter().enumerate().rev() {
+            data[d * nnz + k] = (f % size) as i64;
+            f /= size;
+        }
+    }
+    long_tensor(&data, &[sparse_sizes.len(), nnz])
+}
+
+// Rows `rows` of `src` stacked along the first dimension.
+pub(super) fn index_select_rows(src: &Tensor, rows: &[usize]) -> Tensor {
+    let src = src.contiguous();
+    let mut sizes = src.sizes().to_vec();
+    sizes[0] = rows.len();
+    let result = empty_cpu(&sizes, TensorOptions::with_dtype(src.scalar_type()), None);
+    let row_bytes = sizes[1..].iter().product::<usize>() * src.element_size();
+    if result.numel() == 0 {
+        return result;
+    }
+    let src_data = src.data_ptr_casted::<u8>();
+    let result_data = result.data_ptr_casted::<u8>();
+    for (i, row) in rows.iter().enumerate() {
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                src_data.add(row * row_bytes),
+                result_data.add(i * row_bytes),
+                row_bytes,
+            )
+        };
+    }
+    result
+}
+
+fn add_rows<T: Copy>(dst: &Tensor, rows: &[usize], src: &Tensor, add: impl Fn(T, T) -> T) {
+    let row_numel = dst.sizes()[1..].iter().product::<usize>();
+    if src.numel() == 0 {
+        return;
+    }
+    let dst_data = dst.data_ptr_casted::<T>();
+    let src_data = src.data_ptr_casted::<T>();
+    for (i, row) in rows.iter().enumerate() {
+        for j in 0..row_numel {
+            unsafe {
+                let d = dst_data.add(row * row_numel + j);
+                *d = add(*d, *src_data.add(i * row_numel + j));
+            }
+        }
+    }
+}
+
+// Adds row i of `src` to row rows[i] of the contiguous `dst`, repeated rows accumulate.
+fn index_add_rows(dst: &Tensor, rows: &[usize], src: &Tensor) {
+    assert!(dst.is_contiguous());
+    let dtype = dst.scalar_type();
+    let src = src.to_dtype(dtype).contiguous();
+    if dtype == ScalarType::Bool {
+        add_rows::<bool>(dst, rows, &src, |a, b| a || b);
+    } else if isIntegralType(dtype, false) {
+        AT_DISPATCH_INTEGRAL_TYPES!(dtype, "index_add_rows", || {
+            add_rows::<Scalart>(dst, rows, &src, Scalart::wrapping_add)
+        });
+    } else {
+        AT_DISPATCH_FLOATING_AND_COMPLEX_TYPES_AND2!(
+            Half,
+            BFloat16,
+            dtype,
+            "index_add_rows",
+            || { add_rows::<Scalart>(dst, rows, &src, |a, b| a + b) }
+        );
+    }
+}
+
+// Views `self_` as a matrix with a row per position of the first `dim` dimensions.
+fn rows_view(self_: &Tensor, dim: usize) -> Tensor {
+    let mut sizes = vec![self_.sizes()[..dim].iter().product()];
+    sizes.extend_from_slice(&self_.sizes()[dim..]);
+    super::view(self_, &sizes)
+}
+
+fn scaled(values: &Tensor, alpha: &Scalar) -> Tensor {
+    if alpha.to::<ComplexDouble>() == ComplexDouble::new(1.0, 0.0) {
+        return values.clone();
+    }
+    super::mul(values, &wrapped_scalar_tensor(*alpha)).to_dtype(values.scalar_type())
+}
+
+pub fn sparse_coo_tensor(indices: &Tensor, values: &Tensor, size: &[usize]) -> Tensor {
+    assert_eq!(
+        indices.scalar_type(),
+        ScalarType::Long,
+        "indices must be an int64 tensor"
+    );
+    assert_eq!(
+        indices.dim(),
+        2,
+        "indices must be sparse_dim x nnz, but got: {:?}",
+        indices.sizes()
+    );
+    assert!(values.dim() >= 1, "values must have at least one dimension");
+    let (sparse_dim, nnz) = (indices.size(0), indices.size(1));
+    assert_eq!(
+        values.size(0),
+        nnz,
+        "indices and values must have same nnz, but got nnz from indices: {}, nnz from values: {}",
+        nnz,
+        values.size(0)
+    );
+    let dense_dim = values.dim() as usize - 1;
+    assert_eq!(
+        sparse_dim + dense_dim,
+        size.len(),
+        "number of dimensions must be sparse_dim ({}) + dense_dim ({}), but got {}",
+        sparse_dim,
+        dense_dim,
+        size.len()
+    );
+    assert_eq!(
+        &values.sizes()[1..],
+        &size[sparse_dim..],
+        "values has incorrect size, expected {:?}, got {:?}",
+        &size[sparse_dim..],
+        &values.sizes()[1..]
+    );
+    let data = indices_data(indices);
+    for d in 0..sparse_dim {
+        for index in &data[d * nnz..(d + 1) * nnz] {
+            assert!(
+                *index >= 0 && (*index as usize) < size[d],
+                "found index {} out of bounds for dimension {} of size {}",
+                index,
+                d,
+                size[d]
+            );
+        }
+    }
+    new_with_parts(
+        size,
+        indices.contiguous().tensor_data(),
+        values.contiguous().tensor_data(),
+        sparse_dim,
+        false,
+    )
+}
+
+pub fn sparse_dim(self_: &Tensor) -> usize {
+    if self_.is_sparse() {
+        self_.get_unsafe_tensor_impl().sparse_parts().sparse_dim
+    } else {
+        0
+    }
+}
+
+pub fn dense_dim(self_: &Tensor) -> usize {
+    self_.dim() as usize - sparse_dim(self_)
+}
+
+pub fn nnz(self_: &Tensor) -> usize {
+    self_
+        .get_unsafe_tensor_impl()
+        .sparse_parts()
+        .indices
+        .size(1)
+}
+
+// Sorts the indices and sums the values of repeated ones.
+pub fn coalesce(self_: &Tensor) -> Tensor {
+    let parts = sparse_parts(self_);
+    if parts.coalesced {
+        return self_.clone();
+    }
+    let sparse_sizes = &self_.sizes()[..parts.sparse_dim];
+    let nnz = parts.indices.size(1);
+    let flat = flatten_indices(&indices_data(&parts.indices), nnz, sparse_sizes);
+    let mut order: Vec<usize> = (0..nnz).collect();
+    order.sort_by_key(|k| flat[*k]);
+    let mut unique: Vec<usize> = Vec::new();
+    let mut rows = vec![0; nnz];
+    for k in order {
+        if unique.last() != Some(&flat[k]) {
+            unique.push(flat[k]);
+        }
+        rows[k] = unique.len() - 1;
+    }
+    let mut values_sizes = parts.values.sizes().to_vec();
+    values_sizes[0] = unique.len();
+    let values = zeros(
+        &values_sizes,
+        TensorOptions::with_dtype(parts.values.scalar_type()),
+    );
+    index_add_rows(&values, &rows, &parts.values);
+    new_with_parts(
+        self_.sizes(),
+        unflatten_indices(&unique, sparse_sizes),
+        values,
+        parts.sparse_dim,
+        true,
+    )
+}
+
+pub fn sparse_to_dense(self_: &Tensor) -> Tensor {
+    let result = zeros(
+        self_.sizes(),
+        TensorOptions::with_dtype(self_.scalar_type()),
+    );
+    add_dense_sparse_(&result, self_, &Scalar::from(1));
+    result
+}
+
+// Keeps the positions of the first `sparse_dim` dimensions that have a non zero value.
+pub fn dense_to_sparse(self_: &Tensor, sparse_dim: usize) -> Tensor {
+    assert!(!self_.is_sparse(), "to_sparse expects a strided tensor");
+    assert!(
+        sparse_dim <= self_.dim() as usize,
+        "sparse_dim must be at most the number of dimensions {}, got {}",
+        self_.dim(),
+        sparse_dim
+    );
+    let dense = rows_view(&self_.contiguous(), sparse_dim);
+    let row_numel = dense.sizes()[1..].iter().product::<usize>();
+    let mask = self_.to_dtype(ScalarType::Bool).contiguous();
+    let mask = unsafe { std::slice::from_raw_parts(mask.data_ptr_casted::<bool>(), mask.numel()) };
+    let rows: Vec<usize> = (0..dense.size(0))
+        .filter(|r| mask[r * row_numel..(r + 1) * row_numel].iter().any(|m| *m))
+        .collect();
+    new_with_parts(
+        self_.sizes(),
+        unflatten_indices(&rows, &self_.sizes()[..sparse_dim]),
+        index_select_rows(&dense, &rows),
+        sparse_dim,
+        true,
+    )
+}
+
+// Adds `alpha` times the sparse `other` into the contiguous strided `self_`.
+pub fn add_dense_sparse_(self_: &Tensor, other: &Tensor, alpha: &Scalar) {
+    let parts = sparse_parts(other);
+    assert_eq!(
+        self_.sizes(),
+        other.sizes(),
+        "add expects strided and sparse operands of the same size, but got {:?} and {:?}",
+        self_.sizes(),
+        other.sizes()
+    );
+    let sparse_sizes = &other.sizes()[..parts.sparse_dim];
+    let nnz = parts.indices.size(1);
+    let rows = flatten_indices(&indices_data(&parts.indices), nnz, sparse_sizes);
+    let values = scaled(&parts.values, alpha);
+    index_add_rows(&rows_view(self_, parts.sparse_dim), &rows, &values);
+    increment_version(self_);
+}
+
+fn check_sparse_binary_operands(name: &str, self_: &Tensor, other: &Tensor) {
+    assert!(
+        self_.dim() > 0 && other.dim() > 0 && self_.sizes() == other.sizes(),
+        "{} expects operands of the same size when one is sparse, but got {:?} and {:?}",
+        name,
+        self_.sizes(),
+        other.sizes()
+    );
+}
+
+// The sum of two sparse tensors is sparse and uncoalesced, with a strided operand it is
+// strided.
+pub fn add_sparse(self_: &Tensor, other: &Tensor, alpha: &Scalar) -> Tensor {
+    check_sparse_binary_operands("add", self_, other);
+    let dtype = promote_types(self_.scalar_type(), other.scalar_type());
+    if !self_.is_sparse() || !other.is_sparse() {
+        let (dense, sparse, dense_alpha, sparse_alpha) = if self_.is_sparse() {
+            (other, self_, *alpha, Scalar::from(1))
+        } else {
+            (self_, other, Scalar::from(1), *alpha)
+        };
+        let result = empty_cpu(dense.sizes(), TensorOptions::with_dtype(dtype), None);
+        result.copy(&scaled(dense, &dense_alpha), None);
+        add_dense_sparse_(&result, sparse, &sparse_alpha);
+        return result;
+    }
+    let (a, b) = (sparse_parts(self_), sparse_parts(other));
+    assert_eq!(
+        a.sparse_dim, b.sparse_dim,
+        "add expects sparse tensors with the same number of sparse dimensions"
+    );
+    let a_values = a.values.to_dtype(dtype).contiguous();
+    let b_values = scaled(&b.values.to_dtype(dtype), alpha).contiguous();
+    let (a_nnz, b_nnz) = (a_values.size(0), b_values.size(0));
+    let mut values_sizes = a_values.sizes().to_vec();
+    values_sizes[0] = a_nnz + b_nnz;
+    let values = empty_cpu(&values_sizes, TensorOptions::with_dtype(dtype), None);
+    let a_bytes = a_values.numel() * a_values.element_size();
+    let b_bytes = b_values.numel() * b_values.element_size();
+    if a_bytes > 0 {
+        unsafe {
+            let data = values.data_ptr_casted::<u8>();
+            std::ptr::copy_nonoverlapping(a_values.data_ptr_casted::<u8>(), data, a_bytes)
+        };
+    }
+    if b_bytes > 0 {
+        unsafe {
+            let data = values.data_ptr_casted::<u8>().add(a_bytes);
+            std::ptr::copy_nonoverlapping(b_values.data_ptr_casted::<u8>(), data, b_bytes)
+        };
+    }
+    let (a_indices, b_indices) = (indices_data(&a.indices), indices_data(&b.indices));
+    let mut indices = Vec::with_capacity(a_indices.len() + b_indices.len());
+    for d in 0..a.sparse_dim {
+        indices.extend_from_slice(&a_indices[d * a_nnz..(d + 1) * a_nnz]);
+        indices.extend_from_slice(&b_indices[d * b_nnz..(d + 1) * b_nnz]);
+    }
+    new_with_parts(
+        self_.sizes(),
+        long_tensor(&indices, &[a.sparse_dim, a_nnz + b_nnz]),
+        values,
+        a.sparse_dim,
+        false,
+    )
+}
+
+pub fn add_out_sparse<'a>(
+    result: &'a Tensor,
+    self_: &Tensor,
+    other: &Tensor,
+    alpha: &Scalar,
+) -> &'a Tensor {
+    let out_dtype = result.scalar_type();
+    let dtype = promote_types(self_.scalar_type(), other.scalar_type());
+    assert!(
+        can_cast(dtype, out_dtype),
+        "result type {:?} can't be cast to the desired output type {:?}",
+        dtype,
+        out_dtype
+    );
+    if result.is_sparse() {
+        let mut parts = sparse_parts(&add_sparse(self_, other, alpha));
+        parts.values = parts.values.to_dtype(out_dtype);
+        result.get_unsafe_tensor_impl().set_sparse_parts(parts);
+    } else if result.is_same(self_) && other.is_sparse() && result.is_contiguous() {
+        // Only touches the rows of `other`, e.g. for sparse gradients of embeddings.
+        check_sparse_binary_operands("add", self_, other);
+        add_dense_sparse_(result, other, alpha);
+    } else {
+        result.copy(&add_sparse(self_, other, alpha), None);
+    }
+    result
+}
+
+// Products with a sparse operand are sparse, as they are zero wherever it is.
+pub fn mul_sparse(self_: &Tensor, other: &Tensor) -> Tensor {
+    let (sparse, other) = if self_.is_sparse() {
+        (self_, other)
+    } else {
+        (other, self_)
+    };
+    let parts = sparse_parts(sparse);
+    if other.dim() == 0 {
+        return sparse_with_values(sparse, super::mul(&parts.values, other));
+    }
+    check_sparse_binary_operands("mul", sparse, other);
+    let sparse_sizes = &sparse.sizes()[..parts.sparse_dim];
+    if !other.is_sparse() {
+        let nnz = parts.indices.size(1);
+        let rows = flatten_indices(&indices_data(&parts.indices), nnz, sparse_sizes);
+        let gathered = index_select_rows(&rows_view(&other.contiguous(), parts.sparse_dim), &rows);
+        return sparse_with_values(sparse, super::mul(&parts.values, &gathered));
+    }
+    let (a, b) = (
+        sparse_parts(&coalesce(sparse)),
+        sparse_parts(&coalesce(other)),
+    );
+    assert_eq!(
+        a.sparse_dim, b.sparse_dim,
+        "mul expects sparse tensors with the same number of sparse dimensions"
+    );
+    let (a_nnz, b_nnz) = (a.indices.size(1), b.indices.size(1));
+    let a_flat = flatten_indices(&indices_data(&a.indices), a_nnz, sparse_sizes);
+    let b_flat = flatten_indices(&indices_data(&b.indices), b_nnz, sparse_sizes);
+    // Both are sorted, so the common indices are found in a single pass.
+    let (mut i, mut j) = (0, 0);
+    let (mut a_rows, mut b_rows, mut flat) = (Vec::new(), Vec::new(), Vec::new());
+    while i < a_nnz && j < b_nnz {
+        match a_flat[i].cmp(&b_flat[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                a_rows.push(i);
+                b_rows.push(j);
+                flat.push(a_flat[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    let values = super::mul(
+        &index_select_rows(&a.values, &a_rows),
+        &index_select_rows(&b.values, &b_rows),
+    );
+    new_with_parts(
+        sparse.sizes(),
+        unflatten_indices(&flat, sparse_sizes),
+        values,
+        a.sparse_dim,
+        true,
+    )
+}
+
+pub fn mul_out_sparse<'a>(result: &'a Tensor, self_: &Tensor, other: &Tensor) -> &'a Tensor {
+    assert!(
+        result.is_sparse(),
+        "mul with a sparse operand expects a sparse output"
+    );
+    let mut parts = sparse_parts(&mul_sparse(self_, other));
+    let (dtype, out_dtype) = (parts.values.scalar_type(), result.scalar_type());
+    assert!(
+        can_cast(dtype, out_dtype),
+        "result type {:?} can't be cast to the desired output type {:?}",
+        dtype,
+        out_dtype
+    );
+    parts.values = parts.values.to_dtype(out_dtype);
+    result.get_unsafe_tensor_impl().set_sparse_parts(parts);
+    result
+}
+
+// Product of a sparse matrix with scalar values and a strided matrix, which is strided.
+pub fn sparse_mm(self_: &Tensor, mat2: &Tensor) -> Tensor {
+    assert!(
+        self_.is_sparse() && self_.dim() == 2 && sparse_dim(self_) == 2,
+        "sparse mm expects a sparse matrix with 2 sparse dimensions as first operand"
+    );
+    assert!(
+        !mat2.is_sparse() && mat2.dim() == 2,
+        "sparse mm expects a strided matrix as second operand"
+    );
+    assert_eq!(
+        self_.size(1),
+        mat2.size(0),
+        "mat1 and mat2 shapes cannot be multiplied ({}x{} and {}x{})",
+        self_.size(0),
+        self_.size(1),
+        mat2.size(0),
+        mat2.size(1)
+    );
+    let parts = sparse_parts(self_);
+    let nnz = parts.indices.size(1);
+    let indices = indices_data(&parts.indices);
+    let to_rows = |indices: &[i64]| -> Vec<usize> { indices.iter().map(|i| *i as usize).collect() };
+    let (rows, cols) = (to_rows(&indices[..nnz]), to_rows(&indices[nnz..]));
+    let dtype = promote_types(self_.scalar_type(), mat2.scalar_type());
+    let result = zeros(
+        &[self_.size(0), mat2.size(1)],
+        TensorOptions::with_dtype(dtype),
+    );
+    // Row i of the result gets v * mat2[j] for every entry (i, j, v).
+    let products = super::mul(
+        &index_select_rows(mat2, &cols),
+        &super::view(&parts.values, &[nnz, 1]),
+    );
+    index_add_rows(&result, &rows, &products);
+    result
+}
+
+// Swaps two sparse dimensions by swapping their rows of indices.
+pub fn sparse_transpose(self_: &Tensor, dim0: usize, dim1: usize) -> Tensor {
+    let parts = sparse_parts(self_);
+    assert!(
+        dim0 < parts.sparse_dim && dim1 < parts.sparse_dim,
+        "transpose of sparse tensors is only supported between sparse dimensions"
+    );
+    let nnz = parts.indices.size(1);
+    let mut indices = indices_data(&parts.indices);
+    for k in 0..nnz {
+        indices.swap(dim0 * nnz + k, dim1 * nnz + k);
+    }
+    let mut sizes = self_.sizes().to_vec();
+    sizes.swap(dim0, dim1);
+    new_with_parts(
+        &sizes,
+        long_tensor(&indices, &[parts.sparse_dim, nnz]),
+        parts.values,
+        parts.sparse_dim,
+        false,
+    )
+}
+
+pub fn sparse_to_dtype(self_: &Tensor, dtype: ScalarType, copy: bool) -> Tensor {
+    let values = super::to_dtype(&self_.values(), dtype, false, copy, None);
+    sparse_with_values(self_, values)
+}
+
+pub fn zero_sparse_(self_: &Tensor) {
+    let parts = sparse_parts(self_);
+    let mut values_sizes = parts.values.sizes().to_vec();
+    values_sizes[0] = 0;
+    let values = empty_cpu(
+        &values_sizes,
+        TensorOptions::with_dtype(parts.values.scalar_type()),
+        None,
+    );
+    self_
+        .get_unsafe_tensor_impl()
+        .set_sparse_parts(SparseParts {
+            indices: long_tensor(&[], &[parts.sparse_dim, 0]),
+            values,
+            sparse_dim: parts.sparse_dim,
+            coalesced: true,
+        });
+    increment_version(self_);
+}
+
+// Rows of `weight` at `indices`, with the shape of `indices` followed by the row size.
+pub fn embedding(weight: &Tensor, indices: &Tensor) -> Tensor {
+    assert_eq!(weight.dim(), 2, "'weight' must be 2-D");
+    let rows = embedding_rows(indices, weight.size(0));
+    let mut sizes = indices.sizes().to_vec();
+    sizes.push(weight.size(1));
+    super::view(&index_select_rows(weight, &rows), &sizes)
+}
+
+fn embedding_rows(indices: &Tensor, num_weights: usize) -> Vec<usize> {
+    assert!(
+        isIntegralType(indices.scalar_type(), false),
+        "Expected tensor for argument 'indices' to have an integer dtype, but got {:?}",
+        indices.scalar_type()
+    );
+    indices_data(&indices.to_dtype(ScalarType::Long))
+        .into_iter()
+        .map(|i| {
+            assert!(
+                i >= 0 && (i as usize) < num_weights,
+                "index {} is out of range for an embedding with {} rows",
+                i,
+                num_weights
+            );
+            i as usize
+        })
+        .collect()
+}
+
+// Gradient of `embedding` with respect to the weight, the rows of `padding_idx` get none.
+pub fn embedding_backward(
+    grad: &Tensor,
+    indices: &Tensor,
+    num_weights: usize,
+    padding_idx: Option<usize>,
+    sparse: bool,
+) -> Tensor {
+    let embedding_dim = grad.size(-1);
+    let grad = super::reshape(&grad.contiguous(), &[-1, embedding_dim as isize]);
+    let rows = embedding_rows(indices, num_weights);
+    let kept: Vec<usize> = (0..rows.len())
+        .filter(|k| Some(rows[*k]) != padding_idx)
+        .collect();
+    let (rows, grad) = if kept.len() == rows.len() {
+        (rows, grad)
+    } else {
+        let rows = kept.iter().map(|k| rows[*k]).collect();
+        (rows, index_select_rows(&grad, &kept))
+    };
+    if sparse {
+        let indices: Vec<i64> = rows.iter().map(|r| *r as i64).collect();
+        return new_with_parts(
+            &[num_weights, embedding_dim],
+            long_tensor(&indices, &[1, indices.len()]),
+            grad,
+            1,
+            false,
+        );
+    }
+    let result = zeros(
+        &[num_weights, embedding_dim],
+        TensorOptions::with_dtype(grad.scalar_type()),
+    );
+    index_add_rows(&result, &rows, &grad);
+    result
+}
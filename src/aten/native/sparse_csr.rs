@@ -0,0 +1,318 @@
+use std::ops::{Add, Mul};
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use super::sparse::{index_select_rows, indices_data, long_tensor, new_with_parts};
+use super::{dense_to_sparse, empty_cpu, promote_types, sparse_to_dense, zeros};
+use crate::aten::{parallel_for, GRAIN_SIZE};
+use crate::c10::{isFloatingType, is_complex_type, ScalarType, TensorOptions};
+use crate::tensor::{SparseCsrParts, Tensor, TensorImpl};
+use crate::util_autograd::increment_version;
+use crate::AT_DISPATCH_FLOATING_AND_COMPLEX_TYPES_AND2;
+
+fn sparse_csr_parts(self_: &Tensor) -> SparseCsrParts {
+    assert!(self_.is_sparse_csr(), "expected a sparse CSR tensor");
+    self_.get_unsafe_tensor_impl().sparse_csr_parts().clone()
+}
+
+fn new_csr(size: &[usize], crow_indices: Tensor, col_indices: Tensor, values: Tensor) -> Tensor {
+    Tensor::from_impl(TensorImpl::new_sparse_csr(
+        size,
+        SparseCsrParts {
+            crow_indices,
+            col_indices,
+            values,
+        },
+    ))
+}
+
+// A sparse CSR tensor with the indices of `self_` and other values for them.
+pub fn sparse_csr_with_values(self_: &Tensor, values: Tensor) -> Tensor {
+    let parts = sparse_csr_parts(self_);
+    new_csr(self_.sizes(), parts.crow_indices, parts.col_indices, values)
+}
+
+pub fn sparse_csr_tensor(
+    crow_indices: &Tensor,
+    col_indices: &Tensor,
+    values: &Tensor,
+    size: &[usize],
+) -> Tensor {
+    assert!(
+        crow_indices.scalar_type() == ScalarType::Long
+            && col_indices.scalar_type() == ScalarType::Long,
+        "crow_indices and col_indices must be int64 tensors"
+    );
+    assert!(
+        crow_indices.dim() == 1 && col_indices.dim() == 1 && values.dim() == 1,
+        "crow_indices, col_indices and values must be 1-D tensors"
+    );
+    assert_eq!(
+        size.len(),
+        2,
+        "sparse CSR tensors must be 2-D, but got size {:?}",
+        size
+    );
+    let (rows, cols, nnz) = (size[0], size[1], values.size(0));
+    assert_eq!(
+        crow_indices.size(0),
+        rows + 1,
+        "crow_indices must have {} elements for {} rows, but got {}",
+        rows + 1,
+        rows,
+        crow_indices.size(0)
+    );
+    assert_eq!(
+        col_indices.size(0),
+        nnz,
+        "col_indices and values must have the same number of elements, but got {} and {}",
+        col_indices.size(0),
+        nnz
+    );
+    let crow = indices_data(crow_indices);
+    let col = indices_data(col_indices);
+    assert!(
+        crow[0] == 0 && crow[rows] == nnz as i64,
+        "crow_indices must start at 0 and end at nnz ({}), but got {} and {}",
+        nnz,
+        crow[0],
+        crow[rows]
+    );
+    for i in 0..rows {
+        assert!(
+            crow[i] <= crow[i + 1],
+            "crow_indices must be non decreasing, but got {} after {}",
+            crow[i + 1],
+            crow[i]
+        );
+        let row = &col[crow[i] as usize..crow[i + 1] as usize];
+        for (k, c) in row.iter().enumerate() {
+            assert!(
+                *c >= 0 && (*c as usize) < cols,
+                "found column index {} out of bounds for {} columns",
+                c,
+                cols
+            );
+            assert!(
+                k == 0 || row[k - 1] < *c,
+                "col_indices of row {} must be strictly increasing",
+                i
+            );
+        }
+    }
+    new_csr(
+        size,
+        crow_indices.contiguous().tensor_data(),
+        col_indices.contiguous().tensor_data(),
+        values.contiguous().tensor_data(),
+    )
+}
+
+// Compresses the row indices of a coalesced COO matrix, whose entries are already sorted
+// by row and then by column.
+fn sparse_coo_to_csr(self_: &Tensor) -> Tensor {
+    assert!(
+        self_.dim() == 2 && self_.sparse_dim() == 2,
+        "to_sparse_csr expects a matrix with 2 sparse dimensions, but got size {:?}",
+        self_.sizes()
+    );
+    let coalesced = self_.coalesce();
+    let indices = indices_data(&coalesced.indices());
+    let (rows, nnz) = (self_.size(0), coalesced.nnz());
+    let mut crow = vec![0i64; rows + 1];
+    for row in &indices[..nnz] {
+        crow[*row as usize + 1] += 1;
+    }
+    for i in 0..rows {
+        crow[i + 1] += crow[i];
+    }
+    new_csr(
+        self_.sizes(),
+        long_tensor(&crow, &[rows + 1]),
+        long_tensor(&indices[nnz..], &[nnz]),
+        coalesced.values(),
+    )
+}
+
+pub fn to_sparse_csr(self_: &Tensor) -> Tensor {
+    if self_.is_sparse_csr() {
+        return self_.clone();
+    }
+    if self_.is_sparse() {
+        return sparse_coo_to_csr(self_);
+    }
+    assert_eq!(
+        self_.dim(),
+        2,
+        "to_sparse_csr expects a 2-D tensor, but got size {:?}",
+        self_.sizes()
+    );
+    sparse_coo_to_csr(&dense_to_sparse(self_, 2))
+}
+
+// The coalesced COO tensor with the same entries.
+pub fn sparse_csr_to_sparse(self_: &Tensor) -> Tensor {
+    let parts = sparse_csr_parts(self_);
+    let crow = indices_data(&parts.crow_indices);
+    let nnz = parts.values.size(0);
+    let mut indices = Vec::with_capacity(2 * nnz);
+    for i in 0..self_.size(0) {
+        indices.resize(crow[i + 1] as usize, i as i64);
+    }
+    indices.extend(indices_data(&parts.col_indices));
+    new_with_parts(
+        self_.sizes(),
+        long_tensor(&indices, &[2, nnz]),
+        parts.values,
+        2,
+        true,
+    )
+}
+
+pub fn sparse_csr_to_dense(self_: &Tensor) -> Tensor {
+    sparse_to_dense(&sparse_csr_to_sparse(self_))
+}
+
+// The CSC arrays of a matrix are the CSR arrays of its transpose, so transposing
+// compresses the columns, with a counting sort that keeps the rows of each column sorted.
+pub fn sparse_csr_transpose(self_: &Tensor) -> Tensor {
+    let parts = sparse_csr_parts(self_);
+    let (rows, cols) = (self_.size(0), self_.size(1));
+    let crow = indices_data(&parts.crow_indices);
+    let col = indices_data(&parts.col_indices);
+    let mut ccol = vec![0i64; cols + 1];
+    for c in &col {
+        ccol[*c as usize + 1] += 1;
+    }
+    for j in 0..cols {
+        ccol[j + 1] += ccol[j];
+    }
+    let mut next = ccol.clone();
+    let mut row_indices = vec![0i64; col.len()];
+    let mut order = vec![0; col.len()];
+    for i in 0..rows {
+        for k in crow[i] as usize..crow[i + 1] as usize {
+            let position = &mut next[col[k] as usize];
+            row_indices[*position as usize] = i as i64;
+            order[*position as usize] = k;
+            *position += 1;
+        }
+    }
+    new_csr(
+        &[cols, rows],
+        long_tensor(&ccol, &[cols + 1]),
+        long_tensor(&row_indices, &[col.len()]),
+        index_select_rows(&parts.values, &order),
+    )
+}
+
+pub fn sparse_csr_to_dtype(self_: &Tensor, dtype: ScalarType, copy: bool) -> Tensor {
+    let values = super::to_dtype(&self_.values(), dtype, false, copy, None);
+    sparse_csr_with_values(self_, values)
+}
+
+// An empty sparse CSR matrix, with no specified elements.
+pub fn empty_sparse_csr(size: &[usize], options: &TensorOptions) -> Tensor {
+    assert_eq!(
+        size.len(),
+        2,
+        "sparse CSR tensors must be 2-D, but got size {:?}",
+        size
+    );
+    let crow_indices = zeros(&[size[0] + 1], TensorOptions::with_dtype(ScalarType::Long));
+    let values = empty_cpu(&[0], options.set_layout(None), None);
+    new_csr(size, crow_indices, long_tensor(&[], &[0]), values)
+}
+
+// Row i of the result is the sum of v * mat2[j] over the entries (i, j, v) of row i. Rows
+// are independent, so they are split across threads.
+fn sparse_csr_mm_kernel<T: Copy + Add<Output = T> + Mul<Output = T>>(
+    crow: &[i64],
+    col: &[i64],
+    values: &Tensor,
+    mat2: &Tensor,
+    result: &Tensor,
+) {
+    let (rows, m, nnz) = (result.size(0), result.size(1), col.len());
+    if nnz == 0 || m == 0 {
+        return;
+    }
+    let values_data = AtomicPtr::new(values.data_ptr_casted::<T>());
+    let mat2_data = AtomicPtr::new(mat2.data_ptr_casted::<T>());
+    let result_data = AtomicPtr::new(result.data_ptr_casted::<T>());
+    let grain_size = (GRAIN_SIZE / (m * (nnz / rows).max(1))).max(1);
+    parallel_for(0, rows, grain_size, |begin: usize, end: usize| {
+        let values = values_data.load(Ordering::Relaxed);
+        let mat2 = mat2_data.load(Ordering::Relaxed);
+        let result = result_data.load(Ordering::Relaxed);
+        for i in begin..end {
+            let (lo, hi) = (crow[i] as usize, crow[i + 1] as usize);
+            for (k, &c) in (lo..hi).zip(&col[lo..hi]) {
+                unsafe {
+                    let v = *values.add(k);
+                    let row = mat2.add(c as usize * m);
+                    let out = result.add(i * m);
+                    for j in 0..m {
+                        *out.add(j) = *out.add(j) + v * *row.add(j);
+                    }
+                }
+            }
+        }
+    });
+}
+
+fn sparse_csr_mm_out(result: &Tensor, crow: &[i64], col: &[i64], values: &Tensor, mat2: &Tensor) {
+    AT_DISPATCH_FLOATING_AND_COMPLEX_TYPES_AND2!(
+        Half,
+        BFloat16,
+        result.scalar_type(),
+        "sparse_csr_mm",
+        || { sparse_csr_mm_kernel::<Scalart>(crow, col, values, mat2, result) }
+    );
+}
+
+// Product of a sparse CSR matrix and a strided matrix, which is strided. A matrix vector
+// product is the case of a single column.
+pub fn sparse_csr_mm(self_: &Tensor, mat2: &Tensor) -> Tensor {
+    assert!(
+        self_.is_sparse_csr(),
+        "sparse CSR mm expects a sparse CSR first operand"
+    );
+    assert!(
+        mat2.layout() == crate::c10::K_STRIDED && mat2.dim() == 2,
+        "sparse CSR mm expects a strided matrix as second operand"
+    );
+    assert_eq!(
+        self_.size(1),
+        mat2.size(0),
+        "mat1 and mat2 shapes cannot be multiplied ({}x{} and {}x{})",
+        self_.size(0),
+        self_.size(1),
+        mat2.size(0),
+        mat2.size(1)
+    );
+    let dtype = promote_types(self_.scalar_type(), mat2.scalar_type());
+    assert!(
+        isFloatingType(dtype) || is_complex_type(dtype),
+        "sparse CSR mm is only implemented for floating point and complex tensors, but got {:?}",
+        dtype
+    );
+    let parts = sparse_csr_parts(self_);
+    let values = parts.values.to_dtype(dtype).contiguous();
+    let mat2 = mat2.to_dtype(dtype).contiguous();
+    let result = zeros(
+        &[self_.size(0), mat2.size(1)],
+        TensorOptions::with_dtype(dtype),
+    );
+    let crow = indices_data(&parts.crow_indices);
+    let col = indices_data(&parts.col_indices);
+    sparse_csr_mm_out(&result, &crow, &col, &values, &mat2);
+    result
+}
+
+pub fn zero_sparse_csr_(self_: &Tensor) {
+    let values = self_.values();
+    let empty = empty_sparse_csr(self_.sizes(), &values.options());
+    let parts = sparse_csr_parts(&empty);
+    self_.get_unsafe_tensor_impl().set_sparse_csr_parts(parts);
+    increment_version(self_);
+}
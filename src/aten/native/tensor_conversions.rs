@@ -1,7 +1,7 @@
 use crate::{
     autograd::empty,
     c10::TensorOptions,
-    c10::{Device, MemoryFormat, ScalarType},
+    c10::{type_meta_to_scalar_type, Device, Layout, MemoryFormat, ScalarType},
     tensor::Tensor,
 };
 
@@ -35,6 +35,19 @@ fn to_impl(self_: &Tensor, options: &TensorOptions, non_blocking: bool, copy: bo
         return self_.clone();
     }
 
+    if self_.layout() != options.layout() {
+        let converted = to_layout(self_, options.layout());
+        return to_impl(&converted, options, non_blocking, false);
+    }
+    if self_.is_sparse() {
+        let dtype = type_meta_to_scalar_type(&options.dtype());
+        return super::sparse_to_dtype(self_, dtype, copy);
+    }
+    if self_.is_sparse_csr() {
+        let dtype = type_meta_to_scalar_type(&options.dtype());
+        return super::sparse_csr_to_dtype(self_, dtype, copy);
+    }
+
     if memory_format == MemoryFormat::Preserve {
         if self_.is_non_overlapping_and_dense() {
             // Copy all strides
@@ -55,6 +68,15 @@ fn to_impl(self_: &Tensor, options: &TensorOptions, non_blocking: bool, copy: bo
     r
 }
 
+// Converts between the strided, sparse COO and sparse CSR layouts.
+fn to_layout(self_: &Tensor, layout: Layout) -> Tensor {
+    match layout {
+        Layout::Strided => self_.to_dense(),
+        Layout::Sparse => self_.to_sparse(),
+        Layout::SparseCsr => self_.to_sparse_csr(),
+    }
+}
+
 /// Defaults:
 ///    non_blocking: false
 ///    copy: false
@@ -98,10 +120,6 @@ pub fn to<O: AsRef<TensorOptions>>(
         options.requires_grad()
     );
 
-    assert!(!options.has_layout() || self_.layout() == options.layout(),
-               "to(options) doesn't support converting to a different layout, but got self.layout being {:?} and options.layout set as {:?}", self_.layout(),
-                options.layout());
-
     if options.has_device() {
         options = options.set_device(ensure_has_index(options.device()));
     }
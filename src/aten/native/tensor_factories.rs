@@ -1,20 +1,33 @@
+use std::io;
 use std::ptr::copy_nonoverlapping;
+use std::rc::Rc;
 
 use crate::aten::util::prod_intlist;
-use crate::c10::{MemoryFormat, Scalar, Storage, StorageImpl, TensorOptions, TypeMeta};
+use crate::c10::{
+    Layout, MappedFile, MemoryFormat, Scalar, ScalarType, Storage, StorageImpl, TensorOptions,
+    TypeMeta,
+};
 use crate::core::get_cpu_allocator;
 use crate::tensor::{Tensor, TensorImpl};
-use crate::{aten::native, AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2};
+use crate::{aten::native, AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND3};
 
 pub fn empty_cpu<T: Into<Option<MemoryFormat>>, A: AsRef<TensorOptions>>(
     size: &[usize],
     options: A,
-    _optional_memory_format: T,
+    optional_memory_format: T,
 ) -> Tensor {
+    let options = options.as_ref();
+    let memory_format = optional_memory_format
+        .into()
+        .or_else(|| options.memory_format_opt())
+        .unwrap_or(MemoryFormat::Contiguous);
+    assert!(
+        memory_format != MemoryFormat::Preserve,
+        "Preserve memory format is unsupported by empty"
+    );
     let allocator = unsafe { &mut *get_cpu_allocator() };
 
     let nelements = prod_intlist(size);
-    let options = options.as_ref();
     let dtype = options.dtype();
     let size_bytes = nelements * dtype.itemsize();
 
@@ -24,7 +37,11 @@ pub fn empty_cpu<T: Into<Option<MemoryFormat>>, A: AsRef<TensorOptions>>(
     let tensor = make_tensor(storage_impl, &dtype);
     // Default TensorImpl has size [0]
     if size.len() != 1 || size[0] != 0 {
-        tensor.get_unsafe_tensor_impl().set_sizes_contiguous(size);
+        let impl_ = tensor.get_unsafe_tensor_impl();
+        impl_.set_sizes_contiguous(size);
+        if memory_format != MemoryFormat::Contiguous {
+            impl_.empty_tensor_restride(memory_format);
+        }
     }
     tensor
 }
@@ -35,7 +52,7 @@ pub fn empty_strided_cpu<A: AsRef<TensorOptions>>(
     options: A,
 ) -> Tensor {
     // check_size_nonnegative(size);
-    let t = empty_cpu(&[0], options, None);
+    let t = empty_cpu(&[0], options, MemoryFormat::Contiguous);
     native::resize_impl_cpu(t.get_unsafe_tensor_impl(), size, Some(stride));
     return t;
 }
@@ -45,8 +62,20 @@ pub fn empty<T: Into<Option<MemoryFormat>>, A: AsRef<TensorOptions>>(
     options: A,
     optional_memory_format: T,
 ) -> Tensor {
-    let result = empty_cpu(size, options, optional_memory_format);
-    result
+    let options = options.as_ref();
+    match options.layout() {
+        Layout::Strided => empty_cpu(size, options, optional_memory_format),
+        Layout::Sparse => super::sparse_coo_tensor(
+            &empty_cpu(
+                &[size.len(), 0],
+                TensorOptions::with_dtype(ScalarType::Long),
+                None,
+            ),
+            &empty_cpu(&[0], options.set_layout(None), None),
+            size,
+        ),
+        Layout::SparseCsr => super::empty_sparse_csr(size, options),
+    }
 }
 
 pub fn empty_like<T: Into<Option<MemoryFormat>>, A: AsRef<TensorOptions>>(
@@ -55,9 +84,24 @@ pub fn empty_like<T: Into<Option<MemoryFormat>>, A: AsRef<TensorOptions>>(
     optional_memory_format: T,
 ) -> Tensor {
     let options = self_.options().merge_in(options);
-    let result;
-    result = empty(self_.sizes(), &options, optional_memory_format);
-    result
+    let mut memory_format = optional_memory_format
+        .into()
+        .or_else(|| options.memory_format_opt());
+    if memory_format == Some(MemoryFormat::Preserve) {
+        if options.layout() != Layout::Strided {
+            memory_format = None;
+        } else if self_.is_non_overlapping_and_dense() {
+            // Copy all strides
+            return empty_strided_cpu(self_.sizes(), self_.strides(), &options);
+        } else {
+            memory_format = Some(self_.suggest_memory_format(false));
+        }
+    }
+    empty(
+        self_.sizes(),
+        options.set_memory_format(None),
+        memory_format,
+    )
 }
 
 // --------------------- full --------------------- //
@@ -126,6 +170,45 @@ pub fn make_tensor<T: Into<Storage>>(storage: T, dtype: &TypeMeta) -> Tensor {
     Tensor::from_impl(impl_)
 }
 
+// Contiguous tensor of `size` whose data lives `offset` bytes into `file`, without
+// copying. The tensor keeps the mapping alive. Fails if the data isn't aligned to the
+// dtype or doesn't fit in the file.
+pub fn from_mapped_file(
+    file: &Rc<MappedFile>,
+    offset: usize,
+    size: &[usize],
+    dtype: ScalarType,
+) -> io::Result<Tensor> {
+    let meta: TypeMeta = dtype.into();
+    let nbytes = size
+        .iter()
+        .try_fold(meta.itemsize(), |acc, &s| acc.checked_mul(s))
+        .filter(|&nbytes| {
+            offset
+                .checked_add(nbytes)
+                .is_some_and(|end| end <= file.len())
+        });
+    let nbytes = match nbytes {
+        Some(nbytes) if offset.is_multiple_of(meta.itemsize()) => nbytes,
+        _ => return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "can't map a tensor of shape {:?} and dtype {:?} at offset {} of a {} byte file",
+                size,
+                dtype,
+                offset,
+                file.len()
+            ),
+        )),
+    };
+    let tensor = make_tensor(file.storage(offset, nbytes), &meta);
+    // Default TensorImpl has size [0]
+    if size.len() != 1 || size[0] != 0 {
+        tensor.get_unsafe_tensor_impl().set_sizes_contiguous(size);
+    }
+    Ok(tensor)
+}
+
 /* ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~ Scalar Tensor ~~~~~~~~~~~~~~~~~~~~~~~~~~ */
 pub fn scalar_tensor<A: AsRef<TensorOptions>>(s: Scalar, options: A) -> Tensor {
     let options = options.as_ref();
@@ -180,15 +263,22 @@ fn tensor_cpu<T, A: AsRef<TensorOptions>>(values: &[T], options: A) -> Tensor {
 }
 
 fn tensor_cpu_kernel<T>(result: &Tensor, values: &[T]) {
-    AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND2!(_, _, result.scalar_type(), "tensor_cpu", || {
-        unsafe {
-            copy_nonoverlapping(
-                values.as_ptr() as *mut Scalart,
-                result.data_ptr_casted::<Scalart>(),
-                values.len(),
-            );
+    AT_DISPATCH_ALL_TYPES_AND_COMPLEX_AND3!(
+        Half,
+        BFloat16,
+        Bool,
+        result.scalar_type(),
+        "tensor_cpu",
+        || {
+            unsafe {
+                copy_nonoverlapping(
+                    values.as_ptr() as *mut Scalart,
+                    result.data_ptr_casted::<Scalart>(),
+                    values.len(),
+                );
+            }
         }
-    });
+    );
 }
 
 pub fn tensor<T, A: AsRef<TensorOptions>>(values: &[T], options: A) -> Tensor {
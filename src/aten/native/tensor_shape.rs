@@ -1,7 +1,8 @@
 use aten::{computeStride, infer_size};
 
 use super::make_tensor;
-use crate::tensor::{maybe_wrap_dim, Tensor};
+use crate::tensor::{maybe_wrap_dim, select, Tensor};
+use crate::util_autograd::increment_version;
 use crate::{aten, tensor::TensorImpl};
 
 pub fn expand(self_: &Tensor, size: &[usize], _implicit: bool) -> Tensor {
@@ -29,6 +30,11 @@ pub fn as_strided_tensorimpl<'a>(
     let storage_offset = storage_offset.unwrap_or(self_.storage_offset());
     let result = make_tensor(self_.storage(), self_.dtype());
     setStrided(&result, size, strides, storage_offset);
+    // Views share the version counter of their base, so in-place modifications through
+    // either of them are seen by tensors saved for backward.
+    result
+        .get_unsafe_tensor_impl()
+        .set_version_counter(self_.get_unsafe_tensor_impl().version_counter().clone());
     result
 }
 pub fn as_strided_<'a, 'b, 'c>(
@@ -39,6 +45,7 @@ pub fn as_strided_<'a, 'b, 'c>(
 ) -> &'a Tensor {
     let storage_offset = storage_offset.unwrap_or(self_.storage_offset());
     setStrided(self_, size.into(), strides.into(), storage_offset);
+    increment_version(self_);
     self_
 }
 
@@ -56,6 +63,12 @@ pub fn transpose(self_: &Tensor, dim0: i64, dim1: i64) -> Tensor {
     if dim0 == dim1 {
         return self_.clone();
     }
+    if self_.is_sparse() {
+        return super::sparse_transpose(self_, dim0, dim1);
+    }
+    if self_.is_sparse_csr() {
+        return super::sparse_csr_transpose(self_);
+    }
 
     let mut strides = self_.strides().to_vec();
     let mut sizes = self_.sizes().to_vec();
@@ -129,6 +142,7 @@ fn alias_with_sizes_and_strides(self_: &Tensor, sizes: &[usize], strides: &[usiz
     let mut impl_ = TensorImpl::with_storage_and_dtype(storage, self_.dtype().clone());
     impl_.set_storage_offset(self_.storage_offset());
     impl_.set_sizes_and_strides(sizes, strides);
+    impl_.set_version_counter(self_.get_unsafe_tensor_impl().version_counter().clone());
     new_self = Tensor::from_impl(impl_);
     //   }
     return new_self;
@@ -149,11 +163,10 @@ pub fn view(self_: &Tensor, size: &[usize]) -> Tensor {
 }
 
 pub fn reshape(self_: &Tensor, proposed_shape: &[isize]) -> Tensor {
-    /*
-      if (self.is_sparse()) {
-      AT_ERROR("reshape is not implemented for sparse tensors");
-    }
-    */
+    assert!(
+        !self_.is_sparse() && !self_.is_sparse_csr(),
+        "reshape is not implemented for sparse tensors"
+    );
     let shape = infer_size(proposed_shape, self_.numel());
 
     //   if (self.is_mkldnn()) {
@@ -179,3 +192,24 @@ pub fn reshape(self_: &Tensor, proposed_shape: &[isize]) -> Tensor {
 fn _unsafe_view(self_: &Tensor, size: &[usize]) -> Tensor {
     return self_.view(size);
 }
+
+pub fn stack(tensors: &[Tensor], dim: usize) -> Tensor {
+    assert!(!tensors.is_empty(), "stack expects a non-empty TensorList");
+    let sizes = tensors[0].sizes();
+    for (i, t) in tensors.iter().enumerate() {
+        assert!(
+            t.sizes() == sizes,
+            "stack expects each tensor to be equal size, but got {:?} at entry 0 and {:?} at entry {}",
+            sizes,
+            t.sizes(),
+            i
+        );
+    }
+    let mut result_sizes = sizes.to_vec();
+    result_sizes.insert(dim, tensors.len());
+    let result = super::empty(result_sizes.as_slice(), tensors[0].options(), None);
+    for (i, t) in tensors.iter().enumerate() {
+        super::copy_(&select(&result, dim as i64, i as i64), t, false);
+    }
+    result
+}
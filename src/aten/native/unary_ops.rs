@@ -1,10 +1,12 @@
 use native::{cpu::*, DispatchStub};
 
 use crate::tensor::{Tensor, TensorIterator};
+use crate::util_autograd::increment_version;
 use crate::{aten::native, c10::DeviceType};
 
 struct neg_stub;
 struct sigmoid_stub;
+struct exp_stub;
 
 impl DispatchStub for neg_stub {
     fn call(&self, _device_type: DeviceType, mut iter: TensorIterator) {
@@ -18,6 +20,12 @@ impl DispatchStub for sigmoid_stub {
     }
 }
 
+impl DispatchStub for exp_stub {
+    fn call(&self, _device_type: DeviceType, mut iter: TensorIterator) {
+        exp_kernel(&mut iter);
+    }
+}
+
 #[inline(always)]
 fn unary_op_impl_out<'a>(
     result: &'a Tensor,
@@ -44,7 +52,9 @@ fn unary_op_impl_<'a: 'b, 'b, F>(self_: &'a Tensor, out_impl: F) -> &'a Tensor
 where
     F: Fn(&'a Tensor, &'b Tensor) -> &'a Tensor,
 {
-    out_impl(self_, self_)
+    out_impl(self_, self_);
+    increment_version(self_);
+    self_
 }
 
 pub fn neg_out<'a, 'b>(result: &'a Tensor, self_: &'b Tensor) -> &'a Tensor {
@@ -69,3 +79,14 @@ pub fn sigmoid(self_: &Tensor) -> Tensor {
 pub fn sigmoid_<'a>(self_: &'a Tensor) -> &'a Tensor {
     unary_op_impl_(self_, sigmoid_out)
 }
+
+pub fn exp_out<'a>(result: &'a Tensor, self_: &Tensor) -> &'a Tensor {
+    unary_op_impl_out(result, self_, exp_stub)
+}
+
+pub fn exp(self_: &Tensor) -> Tensor {
+    unary_op_impl(self_, exp_out)
+}
+pub fn exp_(self_: &Tensor) -> &Tensor {
+    unary_op_impl_(self_, exp_out)
+}
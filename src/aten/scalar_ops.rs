@@ -19,6 +19,10 @@ pub fn scalar_to_tensor<D: Into<Option<DeviceType>>>(s: Scalar, device_type: D)
                 native::scalar_tensor(s, device(device_type).set_dtype_(ScalarType::Double))
             } else if s.is_integer() {
                 native::scalar_tensor(s, device(device_type).set_dtype_(ScalarType::Long))
+            } else if s.is_complex() {
+                native::scalar_tensor(s, device(device_type).set_dtype_(ScalarType::ComplexDouble))
+            } else if s.is_boolean() {
+                native::scalar_tensor(s, device(device_type).set_dtype_(ScalarType::Bool))
             } else {
                 todo!()
             }
@@ -27,6 +27,17 @@ impl DataPtr {
             ..Self::default()
         }
     }
+    // `deleter` is called with `context` instead of `data` once the pointer is dropped.
+    pub fn new_with_context(
+        data: Option<NonNull<c_void>>,
+        context: NonNull<c_void>,
+        deleter: fn(*mut c_void),
+    ) -> Self {
+        Self {
+            ptr: UniqueVoidPtr::new_with_context(data, context, deleter),
+            ..Self::default()
+        }
+    }
     pub fn release_context(self) -> Option<*mut c_void> {
         self.ptr.release_context()
     }
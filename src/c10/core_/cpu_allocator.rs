@@ -16,7 +16,7 @@ fn alloc_cpu(nbytes: usize) -> Option<NonNull<c_void>> {
         return None;
     }
     unsafe {
-        let mut data: *mut c_void = std::mem::MaybeUninit::uninit().assume_init();
+        let mut data: *mut c_void = std::ptr::null_mut();
         let err = libc::posix_memalign(&mut data, GALIGNMENT, nbytes);
         if err != 0 {
             panic!(
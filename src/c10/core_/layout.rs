@@ -2,7 +2,9 @@
 pub enum Layout {
     Strided,
     Sparse,
+    SparseCsr,
 }
 
 pub const K_STRIDED: Layout = Layout::Strided;
 pub const K_SPARSE: Layout = Layout::Sparse;
+pub const K_SPARSE_CSR: Layout = Layout::SparseCsr;
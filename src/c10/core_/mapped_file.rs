@@ -0,0 +1,106 @@
+use super::*;
+use std::ffi::c_void;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+use std::ptr::NonNull;
+use std::rc::Rc;
+
+// A file mapped into memory. Files opened with `open` are mapped copy-on-write, so
+// storages pointing into the mapping can be modified without touching the file, while
+// writes to a mapping from `open_writable` end up in the file.
+pub struct MappedFile {
+    ptr: NonNull<c_void>,
+    len: usize,
+}
+
+impl MappedFile {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len() as usize;
+        Self::map(&file, len, libc::MAP_PRIVATE)
+    }
+
+    // Maps `path` shared, creating it or growing it to at least `len` bytes first.
+    pub fn open_writable<P: AsRef<Path>>(path: P, len: usize) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let len = len.max(file.metadata()?.len() as usize);
+        file.set_len(len as u64)?;
+        Self::map(&file, len, libc::MAP_SHARED)
+    }
+
+    fn map(file: &File, len: usize, flags: libc::c_int) -> io::Result<Self> {
+        if len == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "can't map an empty file",
+            ));
+        }
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                flags,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self {
+            ptr: NonNull::new(ptr).unwrap(),
+            len,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr() as *const u8, self.len) }
+    }
+
+    // Storage of `size_bytes` starting `offset` bytes into the mapping, without copying.
+    // The storage keeps the mapping alive until its data pointer is dropped.
+    pub fn storage(self: &Rc<Self>, offset: usize, size_bytes: usize) -> StorageImpl {
+        assert!(
+            offset + size_bytes <= self.len,
+            "Storage of {} bytes at offset {} is out of bounds for a mapping of {} bytes",
+            size_bytes,
+            offset,
+            self.len
+        );
+        let data = if size_bytes == 0 {
+            None
+        } else {
+            NonNull::new(unsafe { (self.ptr.as_ptr() as *mut u8).add(offset) } as *mut c_void)
+        };
+        let context = Box::into_raw(Box::new(self.clone())) as *mut c_void;
+        let data_ptr =
+            DataPtr::new_with_context(data, NonNull::new(context).unwrap(), release_mapping);
+        StorageImpl::new(size_bytes, data_ptr, get_cpu_allocator(), false)
+    }
+}
+
+fn release_mapping(context: *mut c_void) {
+    unsafe { drop(Box::from_raw(context as *mut Rc<MappedFile>)) }
+}
+
+impl Drop for MappedFile {
+    fn drop(&mut self) {
+        unsafe { libc::munmap(self.ptr.as_ptr(), self.len) };
+    }
+}
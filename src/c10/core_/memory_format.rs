@@ -10,3 +10,75 @@ impl MemoryFormat {
         Self::Contiguous
     }
 }
+
+// NHWC strides of an NCHW (or CHW) sized tensor.
+pub fn get_channels_last_strides_2d(sizes: &[usize]) -> Vec<usize> {
+    let mut strides = vec![0; sizes.len()];
+    match sizes.len() {
+        4 => {
+            strides[1] = 1;
+            strides[3] = sizes[1].max(1);
+            strides[2] = strides[3] * sizes[3].max(1);
+            strides[0] = strides[2] * sizes[2].max(1);
+        }
+        3 => {
+            strides[0] = 1;
+            strides[2] = sizes[0].max(1);
+            strides[1] = strides[2] * sizes[2].max(1);
+        }
+        _ => panic!("ChannelsLast2d doesn't support size {:?}", sizes),
+    }
+    strides
+}
+
+// NDHWC strides of an NCDHW (or CDHW) sized tensor.
+pub fn get_channels_last_strides_3d(sizes: &[usize]) -> Vec<usize> {
+    let mut strides = vec![0; sizes.len()];
+    match sizes.len() {
+        5 => {
+            strides[1] = 1;
+            strides[4] = sizes[1].max(1);
+            strides[3] = strides[4] * sizes[4].max(1);
+            strides[2] = strides[3] * sizes[3].max(1);
+            strides[0] = strides[2] * sizes[2].max(1);
+        }
+        4 => {
+            strides[0] = 1;
+            strides[3] = sizes[0].max(1);
+            strides[2] = strides[3] * sizes[3].max(1);
+            strides[1] = strides[2] * sizes[2].max(1);
+        }
+        _ => panic!("ChannelsLast3d doesn't support size {:?}", sizes),
+    }
+    strides
+}
+
+// Whether the strides grow in the channels last order of `order`, allowing gaps. Ambiguous
+// layouts such as N111 fall back to the contiguous format.
+fn is_channels_last_strides(sizes: &[usize], strides: &[usize], order: &[usize]) -> bool {
+    if strides[1] == 0 {
+        return false;
+    }
+    let mut min = 0;
+    for &d in order {
+        if sizes[d] == 0 || strides[d] < min {
+            return false;
+        }
+        if d == 0 && min == strides[1] {
+            return false;
+        }
+        min = strides[d];
+        if sizes[d] > 1 {
+            min *= sizes[d];
+        }
+    }
+    true
+}
+
+pub fn is_channels_last_strides_2d(sizes: &[usize], strides: &[usize]) -> bool {
+    sizes.len() == 4 && is_channels_last_strides(sizes, strides, &[1, 3, 2, 0])
+}
+
+pub fn is_channels_last_strides_3d(sizes: &[usize], strides: &[usize]) -> bool {
+    sizes.len() == 5 && is_channels_last_strides(sizes, strides, &[1, 4, 3, 2, 0])
+}
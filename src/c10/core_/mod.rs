@@ -13,6 +13,9 @@ pub use cpu_allocator::*;
 mod storage;
 pub use storage::*;
 
+mod mapped_file;
+pub use mapped_file::*;
+
 mod layout;
 pub use layout::*;
 
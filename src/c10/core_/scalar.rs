@@ -1,6 +1,6 @@
-use crate::c10::checked_convert;
-use num::{cast, FromPrimitive, NumCast, ToPrimitive};
-use std::any::type_name;
+use crate::c10::{checked_convert, BFloat16, ComplexDouble, ComplexFloat, Half};
+use num::{cast, complex::Complex, FromPrimitive, NumCast, ToPrimitive};
+use std::any::{type_name, Any, TypeId};
 #[repr(u32)]
 #[derive(Copy, Clone)]
 enum Tag {
@@ -13,6 +13,8 @@ enum Tag {
 enum V {
     i(i64),
     f(f64),
+    z(ComplexDouble),
+    b(bool),
 }
 
 #[repr(C)]
@@ -45,29 +47,83 @@ impl Scalar {
         Self { v }
     }
     #[inline(always)]
+    pub fn complex<T>(v: Complex<T>) -> Self
+    where
+        T: NumCast,
+    {
+        let v = V::z(ComplexDouble::new(cast(v.re).unwrap(), cast(v.im).unwrap()));
+        Self { v }
+    }
+    #[inline(always)]
     pub fn new<T>(v: T) -> Self
     where
-        T: ToPrimitive + NumCast,
+        T: ToPrimitive + NumCast + 'static,
     {
-        let is_float = match type_name::<T>() {
-            "f32" | "f64" => true,
-            _ => false,
-        };
-        if is_float {
+        if let Some(&z) = (&v as &dyn Any).downcast_ref::<ComplexFloat>() {
+            return Self::complex(z);
+        }
+        if let Some(&z) = (&v as &dyn Any).downcast_ref::<ComplexDouble>() {
+            return Self::complex(z);
+        }
+        let float_types = [
+            TypeId::of::<f32>(),
+            TypeId::of::<f64>(),
+            TypeId::of::<Half>(),
+            TypeId::of::<BFloat16>(),
+        ];
+        if float_types.contains(&TypeId::of::<T>()) {
             Self::float(v)
         } else {
             Self::int(v)
         }
     }
     #[inline(always)]
-    pub fn to<T: FromPrimitive>(&self) -> T {
-        match type_name::<T>() {
-            "f32" => FromPrimitive::from_f32(self.to_float()).unwrap(),
-            "f64" => FromPrimitive::from_f64(self.to_double()).unwrap(),
-            "i32" => FromPrimitive::from_i32(self.to_int()).unwrap(),
-            "i64" => FromPrimitive::from_i64(self.to_long()).unwrap(),
-            _ => todo!(),
+    pub fn to<T: FromPrimitive + 'static>(&self) -> T {
+        let id = TypeId::of::<T>();
+        if id == TypeId::of::<f32>() || id == TypeId::of::<Half>() || id == TypeId::of::<BFloat16>()
+        {
+            return FromPrimitive::from_f32(self.to_float()).unwrap();
+        }
+        if id == TypeId::of::<f64>() {
+            return FromPrimitive::from_f64(self.to_double()).unwrap();
         }
+        if id == TypeId::of::<i32>() {
+            return FromPrimitive::from_i32(self.to_int()).unwrap();
+        }
+        if id == TypeId::of::<i64>() {
+            return FromPrimitive::from_i64(self.to_long()).unwrap();
+        }
+        // Complex types don't implement FromPrimitive in a useful way, so the value is
+        // built as the concrete type and moved out through `Any`.
+        let mut result: Option<T> = None;
+        let slot = &mut result as &mut dyn Any;
+        if let Some(slot) = slot.downcast_mut::<Option<ComplexFloat>>() {
+            let z = self.to_complex_double();
+            *slot = Some(ComplexFloat::new(z.re as f32, z.im as f32));
+        } else if let Some(slot) = slot.downcast_mut::<Option<ComplexDouble>>() {
+            *slot = Some(self.to_complex_double());
+        }
+        if let Some(result) = result {
+            return result;
+        }
+        let small_ints = [
+            TypeId::of::<u8>(),
+            TypeId::of::<i8>(),
+            TypeId::of::<i16>(),
+            TypeId::of::<usize>(),
+            TypeId::of::<isize>(),
+        ];
+        if small_ints.contains(&id) {
+            let v = self.to_long();
+            return FromPrimitive::from_i64(v).unwrap_or_else(|| {
+                panic!(
+                    "value cannot be converted to type {} without overflow: {}",
+                    type_name::<T>(),
+                    v
+                )
+            });
+        }
+        todo!()
     }
 
     #[inline(always)]
@@ -75,6 +131,8 @@ impl Scalar {
         match self.v {
             V::i(val) => checked_convert(val, "int"),
             V::f(val) => checked_convert(val, "int"),
+            V::z(val) => checked_convert(real_part(val, "int"), "int"),
+            V::b(val) => checked_convert(val as i64, "int"),
         }
     }
 
@@ -83,6 +141,8 @@ impl Scalar {
         match self.v {
             V::i(val) => checked_convert(val, "long"),
             V::f(val) => checked_convert(val, "long"),
+            V::z(val) => checked_convert(real_part(val, "long"), "long"),
+            V::b(val) => checked_convert(val as i64, "long"),
         }
     }
 
@@ -91,6 +151,8 @@ impl Scalar {
         match self.v {
             V::i(val) => checked_convert(val, "float"),
             V::f(val) => checked_convert(val, "float"),
+            V::z(val) => checked_convert(real_part(val, "float"), "float"),
+            V::b(val) => checked_convert(val as i64, "float"),
         }
     }
     #[inline(always)]
@@ -98,6 +160,26 @@ impl Scalar {
         match self.v {
             V::i(val) => checked_convert(val, "double"),
             V::f(val) => checked_convert(val, "double"),
+            V::z(val) => checked_convert(real_part(val, "double"), "double"),
+            V::b(val) => checked_convert(val as i64, "double"),
+        }
+    }
+    #[inline(always)]
+    fn to_complex_double(self) -> ComplexDouble {
+        match self.v {
+            V::i(val) => ComplexDouble::new(val as f64, 0.0),
+            V::f(val) => ComplexDouble::new(val, 0.0),
+            V::z(val) => val,
+            V::b(val) => ComplexDouble::new(val as u8 as f64, 0.0),
+        }
+    }
+    #[inline(always)]
+    pub fn to_bool(&self) -> bool {
+        match self.v {
+            V::i(val) => val != 0,
+            V::f(val) => val != 0.0,
+            V::z(val) => val != ComplexDouble::new(0.0, 0.0),
+            V::b(val) => val,
         }
     }
     #[inline(always)]
@@ -114,6 +196,36 @@ impl Scalar {
             _ => false,
         }
     }
+    // Complex conjugate, real values are returned as is.
+    #[inline(always)]
+    pub fn conj(&self) -> Self {
+        match self.v {
+            V::z(val) => Self {
+                v: V::z(val.conj()),
+            },
+            _ => *self,
+        }
+    }
+    #[inline(always)]
+    pub fn is_complex(&self) -> bool {
+        matches!(self.v, V::z(_))
+    }
+    #[inline(always)]
+    pub fn is_boolean(&self) -> bool {
+        matches!(self.v, V::b(_))
+    }
+}
+
+// Complex values only convert to real types when their imaginary part is zero.
+#[inline(always)]
+fn real_part(v: ComplexDouble, name: &str) -> f64 {
+    assert!(
+        v.im == 0.0,
+        "value {} cannot be converted to type {} without losing its imaginary part",
+        v,
+        name
+    );
+    v.re
 }
 
 // impl From<f32> for Scalar {
@@ -173,5 +285,29 @@ macro_rules! impl_from_scalar {
         )*
     };
 }
-impl_scalar!(f32, f64, i32, i64, usize, isize);
-impl_from_scalar! {f32 f64 i32 i64 usize isize}
+impl From<bool> for Scalar {
+    fn from(s: bool) -> Self {
+        Self { v: V::b(s) }
+    }
+}
+impl From<Scalar> for bool {
+    fn from(s: Scalar) -> Self {
+        s.to_bool()
+    }
+}
+impl_scalar!(
+    u8,
+    i8,
+    i16,
+    f32,
+    f64,
+    i32,
+    i64,
+    usize,
+    isize,
+    Half,
+    BFloat16,
+    ComplexFloat,
+    ComplexDouble
+);
+impl_from_scalar! {u8 i8 i16 f32 f64 i32 i64 usize isize Half BFloat16 ComplexFloat ComplexDouble}
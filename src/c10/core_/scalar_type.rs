@@ -1,4 +1,4 @@
-use crate::c10::TypeMeta;
+use crate::c10::{BFloat16, ComplexDouble, ComplexFloat, Half, TypeMeta};
 use std::mem::size_of;
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum ScalarType {
@@ -31,10 +31,18 @@ impl Default for ScalarType {
 impl From<ScalarType> for TypeMeta {
     fn from(s: ScalarType) -> Self {
         match s {
+            ScalarType::Byte => TypeMeta::make::<u8>(),
+            ScalarType::Char => TypeMeta::make::<i8>(),
+            ScalarType::Short => TypeMeta::make::<i16>(),
+            ScalarType::Bool => TypeMeta::make::<bool>(),
             ScalarType::Int => TypeMeta::make::<i32>(),
             ScalarType::Float => TypeMeta::make::<f32>(),
             ScalarType::Double => TypeMeta::make::<f64>(),
             ScalarType::Long => TypeMeta::make::<i64>(),
+            ScalarType::Half => TypeMeta::make::<Half>(),
+            ScalarType::BFloat16 => TypeMeta::make::<BFloat16>(),
+            ScalarType::ComplexFloat => TypeMeta::make::<ComplexFloat>(),
+            ScalarType::ComplexDouble => TypeMeta::make::<ComplexDouble>(),
             _ => todo!(),
         }
     }
@@ -62,6 +70,30 @@ pub fn try_type_meta_to_scalar_type(dtype: &TypeMeta) -> Option<ScalarType> {
     if dtype == TypeMeta::make::<i64>() {
         return Some(ScalarType::Long);
     }
+    if dtype == TypeMeta::make::<u8>() {
+        return Some(ScalarType::Byte);
+    }
+    if dtype == TypeMeta::make::<i8>() {
+        return Some(ScalarType::Char);
+    }
+    if dtype == TypeMeta::make::<i16>() {
+        return Some(ScalarType::Short);
+    }
+    if dtype == TypeMeta::make::<bool>() {
+        return Some(ScalarType::Bool);
+    }
+    if dtype == TypeMeta::make::<Half>() {
+        return Some(ScalarType::Half);
+    }
+    if dtype == TypeMeta::make::<BFloat16>() {
+        return Some(ScalarType::BFloat16);
+    }
+    if dtype == TypeMeta::make::<ComplexFloat>() {
+        return Some(ScalarType::ComplexFloat);
+    }
+    if dtype == TypeMeta::make::<ComplexDouble>() {
+        return Some(ScalarType::ComplexDouble);
+    }
     return None;
 }
 
@@ -90,6 +122,25 @@ pub fn isFloatingType(t: ScalarType) -> bool {
 pub fn is_complex_type(t: ScalarType) -> bool {
     t == ScalarType::ComplexHalf || t == ScalarType::ComplexFloat || t == ScalarType::ComplexDouble
 }
+// Real type of the components of a complex type, other types are returned as is.
+#[inline(always)]
+pub fn to_value_type(t: ScalarType) -> ScalarType {
+    match t {
+        ScalarType::ComplexHalf => ScalarType::Half,
+        ScalarType::ComplexFloat => ScalarType::Float,
+        ScalarType::ComplexDouble => ScalarType::Double,
+        _ => t,
+    }
+}
+#[inline(always)]
+pub fn to_complex_type(t: ScalarType) -> ScalarType {
+    match t {
+        ScalarType::Half | ScalarType::ComplexHalf => ScalarType::ComplexHalf,
+        ScalarType::Float | ScalarType::ComplexFloat => ScalarType::ComplexFloat,
+        ScalarType::Double | ScalarType::ComplexDouble => ScalarType::ComplexDouble,
+        _ => panic!("Unknown complex ScalarType for {:?}", t),
+    }
+}
 #[inline(always)]
 pub fn isIntegralType(t: ScalarType, included_bool: bool) -> bool {
     let is_integral = t == ScalarType::Byte
@@ -146,10 +197,17 @@ macro_rules! AT_FORALL_SCALAR_TYPES_AND2 {
 pub fn elementSize(t: ScalarType) -> usize {
     match t {
         ScalarType::Byte => size_of::<u8>(),
-        ScalarType::Char => size_of::<char>(),
+        ScalarType::Char => size_of::<i8>(),
+        ScalarType::Short => size_of::<i16>(),
         ScalarType::Bool => size_of::<bool>(),
         ScalarType::Int => size_of::<i32>(),
         ScalarType::Float => size_of::<f32>(),
+        ScalarType::Long => size_of::<i64>(),
+        ScalarType::Double => size_of::<f64>(),
+        ScalarType::Half => size_of::<Half>(),
+        ScalarType::BFloat16 => size_of::<BFloat16>(),
+        ScalarType::ComplexFloat => size_of::<ComplexFloat>(),
+        ScalarType::ComplexDouble => size_of::<ComplexDouble>(),
         _ => todo!(),
     }
 }
@@ -40,6 +40,7 @@ impl TensorOptions {
     pub fn with_layout(layout: Layout) -> Self {
         Self {
             layout,
+            has_layout: true,
             ..Self::default()
         }
     }
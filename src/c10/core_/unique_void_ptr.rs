@@ -13,6 +13,9 @@ fn default_deleter<T>(ptr_: *mut T) {
 
 pub struct Unique<T> {
     inner: Option<NonNull<T>>,
+    // Passed to the deleter instead of `inner` when set, for data owned by something
+    // else than the pointer itself, e.g. a memory mapped file.
+    context: Option<NonNull<T>>,
     deleter: Deleter<T>,
 }
 
@@ -20,6 +23,7 @@ impl Default for Unique<c_void> {
     fn default() -> Self {
         Self {
             inner: None,
+            context: None,
             deleter: delete_nothing,
         }
     }
@@ -27,8 +31,8 @@ impl Default for Unique<c_void> {
 
 impl<T> Drop for Unique<T> {
     fn drop(&mut self) {
-        if self.inner.is_some() {
-            (self.deleter)(self.inner.unwrap().as_ptr())
+        if let Some(ptr) = self.context.or(self.inner) {
+            (self.deleter)(ptr.as_ptr())
         }
     }
 }
@@ -37,11 +41,27 @@ impl<T> Unique<T> {
     pub fn new(inner: Option<NonNull<T>>) -> Self {
         Self {
             inner,
+            context: None,
             deleter: delete_nothing,
         }
     }
     pub fn new_with_deleter(inner: Option<NonNull<T>>, deleter: Deleter<T>) -> Self {
-        Self { inner, deleter }
+        Self {
+            inner,
+            context: None,
+            deleter,
+        }
+    }
+    pub fn new_with_context(
+        inner: Option<NonNull<T>>,
+        context: NonNull<T>,
+        deleter: Deleter<T>,
+    ) -> Self {
+        Self {
+            inner,
+            context: Some(context),
+            deleter,
+        }
     }
     pub fn release_context(self) -> Option<*mut T> {
         let result = self.inner;
@@ -66,11 +86,10 @@ pub type UniqueVoidPtr = Unique<c_void>;
 mod test {
     use super::*;
     use libc::posix_memalign;
-    use std::mem::MaybeUninit;
     #[test]
     fn test_allocation() {
         unsafe {
-            let mut x = MaybeUninit::uninit().assume_init();
+            let mut x = std::ptr::null_mut();
             let _ = posix_memalign(&mut x, 64, 4);
             (x as *mut u32).write(32);
             {
@@ -3,3 +3,16 @@ pub use typeid::*;
 
 mod typecast;
 pub use typecast::*;
+
+// 16-bit floating point types. Kernels compute with them directly or, for
+// reductions, accumulate in f32.
+pub use half::{bf16 as BFloat16, f16 as Half};
+
+// Whether `T` is Half or BFloat16, for kernels that compute those in f32.
+pub fn is_reduced_floating_point<T: 'static>() -> bool {
+    use std::any::TypeId;
+    TypeId::of::<T>() == TypeId::of::<Half>() || TypeId::of::<T>() == TypeId::of::<BFloat16>()
+}
+
+pub type ComplexFloat = num::complex::Complex<f32>;
+pub type ComplexDouble = num::complex::Complex<f64>;
@@ -1,8 +1,59 @@
+use super::{ComplexDouble, ComplexFloat};
+use std::any::{type_name, Any};
+
 #[inline(always)]
 pub fn checked_convert<T: num::NumCast, U: num::NumCast>(v: T, _name: &str) -> U {
     num::cast(v).unwrap()
 }
 
-pub fn cast_with_inter_type<I: num::NumCast, O: num::NumCast>(v: I) -> O {
-    num::cast(v).unwrap()
+#[inline(always)]
+fn as_complex_double<T: 'static>(v: &T) -> Option<ComplexDouble> {
+    let v = v as &dyn Any;
+    if let Some(z) = v.downcast_ref::<ComplexFloat>() {
+        return Some(ComplexDouble::new(z.re as f64, z.im as f64));
+    }
+    v.downcast_ref::<ComplexDouble>().copied()
+}
+
+// Casts to integral types wrap around like a C++ static_cast, through i64 so that
+// e.g. -1.0 becomes 255 as a u8. Values that do not fit in an i64 (and NaN) become
+// i64::MIN, as on x86.
+#[inline(always)]
+fn cast_to_integral<I: num::NumCast, O: num::NumCast>(v: I) -> Option<O> {
+    let wrap = |v: i64| -> i64 {
+        match type_name::<O>() {
+            "u8" => v as u8 as i64,
+            "i8" => v as i8 as i64,
+            "i16" => v as i16 as i64,
+            "i32" => v as i32 as i64,
+            _ => v,
+        }
+    };
+    match type_name::<O>() {
+        "u8" | "i8" | "i16" | "i32" | "i64" => {
+            num::cast(wrap(num::cast::<I, i64>(v).unwrap_or(i64::MIN)))
+        }
+        _ => None,
+    }
+}
+
+// Casting a complex number to a real type keeps the real part, like a C++ static_cast
+// of c10::complex.
+pub fn cast_with_inter_type<I, O>(v: I) -> O
+where
+    I: num::NumCast + Copy + 'static,
+    O: num::NumCast + 'static,
+{
+    let z = match as_complex_double(&v) {
+        Some(z) => z,
+        None => return cast_to_integral(v).unwrap_or_else(|| num::cast(v).unwrap()),
+    };
+    let mut out: Option<O> = None;
+    let slot = &mut out as &mut dyn Any;
+    if let Some(slot) = slot.downcast_mut::<Option<ComplexFloat>>() {
+        *slot = Some(ComplexFloat::new(z.re as f32, z.im as f32));
+    } else if let Some(slot) = slot.downcast_mut::<Option<ComplexDouble>>() {
+        *slot = Some(z);
+    }
+    out.unwrap_or_else(|| cast_to_integral(z.re).unwrap_or_else(|| num::cast(z.re).unwrap()))
 }
@@ -31,31 +31,34 @@ pub trait Trait {
     fn make() -> &'static TypeMetaData;
 }
 
-impl Trait for f32 {
-    fn make() -> &'static TypeMetaData {
-        static SINGLETON: OnceCell<TypeMetaData> = OnceCell::new();
-        SINGLETON.get_or_init(|| make_type_meta_data_instance::<f32>())
-    }
-}
-impl Trait for i32 {
-    fn make() -> &'static TypeMetaData {
-        static SINGLETON: OnceCell<TypeMetaData> = OnceCell::new();
-        SINGLETON.get_or_init(|| make_type_meta_data_instance::<i32>())
-    }
-}
-impl Trait for f64 {
-    fn make() -> &'static TypeMetaData {
-        static SINGLETON: OnceCell<TypeMetaData> = OnceCell::new();
-        SINGLETON.get_or_init(|| make_type_meta_data_instance::<f64>())
-    }
-}
-impl Trait for i64 {
-    fn make() -> &'static TypeMetaData {
-        static SINGLETON: OnceCell<TypeMetaData> = OnceCell::new();
-        SINGLETON.get_or_init(|| make_type_meta_data_instance::<i64>())
-    }
+macro_rules! IMPL_TYPE_META_TRAIT {
+    ($($t:ty),*) => {
+        $(
+            impl Trait for $t {
+                fn make() -> &'static TypeMetaData {
+                    static SINGLETON: OnceCell<TypeMetaData> = OnceCell::new();
+                    SINGLETON.get_or_init(make_type_meta_data_instance::<$t>)
+                }
+            }
+        )*
+    };
 }
 
+IMPL_TYPE_META_TRAIT!(
+    u8,
+    i8,
+    i16,
+    bool,
+    f32,
+    i32,
+    f64,
+    i64,
+    super::Half,
+    super::BFloat16,
+    super::ComplexFloat,
+    super::ComplexDouble
+);
+
 fn make_type_meta_data_instance<T>() -> TypeMetaData {
     let typename = std::any::type_name::<T>();
     let itemsize = std::mem::size_of::<T>();
@@ -129,6 +129,16 @@ impl GeneratorImpl for CPUGeneratorImpl {
         gen.set_next_double_normal_sample(self.next_double_normal_sample);
         Box::new(gen)
     }
+
+    // Copies the engine state into the existing engine so that generators sharing
+    // it observe the restored state too.
+    fn set_state(&mut self, state: &dyn GeneratorImpl) {
+        let state = state.as_cpu_impl();
+        let engine = *state.engine.lock().unwrap();
+        *self.engine.lock().unwrap() = engine;
+        self.next_float_normal_sample = state.next_float_normal_sample;
+        self.next_double_normal_sample = state.next_double_normal_sample;
+    }
 }
 
 static mut DEFAULT_CPU_GEN: Option<Generator> = None;
@@ -216,6 +226,10 @@ mod test {
         fn clone(&self) -> Box<dyn GeneratorImpl> {
             todo!()
         }
+
+        fn set_state(&mut self, _state: &dyn GeneratorImpl) {
+            todo!()
+        }
     }
 
     const MAGIC_NUMBER: u64 = 424242424242424242;
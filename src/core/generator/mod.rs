@@ -30,6 +30,15 @@ impl Generator {
     pub fn as_with_cpu_impl(&self) -> Generator {
         Generator::new(Box::new(self.impl_.as_cpu_impl()))
     }
+
+    // Returns a snapshot of the generator, i.e. a clone with its own engine.
+    pub fn get_state(&self) -> Generator {
+        self.clone()
+    }
+
+    pub fn set_state(&mut self, state: &Generator) {
+        self.impl_.set_state(state.impl_.as_ref())
+    }
 }
 
 const DEFAULT_RNG_SEED_VAL: u64 = 67280421310721;
@@ -45,6 +54,7 @@ pub trait GeneratorImpl: Send {
     fn set_next_float_normal_sample(&mut self, randn: Option<f32>);
     fn next_double_normal_sample(&self) -> Option<f64>;
     fn set_next_double_normal_sample(&mut self, randn: Option<f64>);
+    fn set_state(&mut self, state: &dyn GeneratorImpl);
 }
 
 mod cpu_generator;
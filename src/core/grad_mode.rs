@@ -1,3 +1,5 @@
+use std::cell::Cell;
+
 pub struct AutoGradMode {
     prev_mode: bool,
 }
@@ -18,18 +20,19 @@ impl Drop for AutoGradMode {
     }
 }
 
-static mut GRADMODE_ENABLED: bool = true;
+thread_local! {
+    // Each thread starts with grad mode on, like in PyTorch.
+    static GRADMODE_ENABLED: Cell<bool> = const { Cell::new(true) };
+}
 pub struct GradMode;
 
 impl GradMode {
     pub fn is_enabled() -> bool {
-        let t = unsafe { GRADMODE_ENABLED };
-        // eprintln!("Gradmode_Status = {}", t);
-        t
+        GRADMODE_ENABLED.with(Cell::get)
     }
 
     pub fn set_enabled(enabled: bool) {
-        unsafe { GRADMODE_ENABLED = enabled }
+        GRADMODE_ENABLED.with(|mode| mode.set(enabled))
     }
 }
 
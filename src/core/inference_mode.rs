@@ -0,0 +1,42 @@
+use super::AutoGradMode;
+use std::cell::Cell;
+
+thread_local! {
+    // Per thread, like GradMode, which the guard switches together with it.
+    static INFERENCE_MODE_ENABLED: Cell<bool> = const { Cell::new(false) };
+}
+
+// Like NoGradGuard, but tensors created while it is alive are inference tensors: they
+// carry no version counter and can't be saved for backward or require grad once the
+// guard is dropped.
+pub struct InferenceMode {
+    prev_mode: bool,
+    _grad_mode: AutoGradMode,
+}
+
+impl InferenceMode {
+    pub fn new(enabled: bool) -> Self {
+        let self_ = Self {
+            prev_mode: Self::is_enabled(),
+            _grad_mode: AutoGradMode::new(!enabled),
+        };
+        INFERENCE_MODE_ENABLED.with(|mode| mode.set(enabled));
+        self_
+    }
+
+    pub fn is_enabled() -> bool {
+        INFERENCE_MODE_ENABLED.with(Cell::get)
+    }
+}
+
+impl Default for InferenceMode {
+    fn default() -> Self {
+        Self::new(true)
+    }
+}
+
+impl Drop for InferenceMode {
+    fn drop(&mut self) {
+        INFERENCE_MODE_ENABLED.with(|mode| mode.set(self.prev_mode))
+    }
+}
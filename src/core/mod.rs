@@ -1,6 +1,9 @@
 mod grad_mode;
 pub use grad_mode::*;
 
+mod inference_mode;
+pub use inference_mode::*;
+
 mod generator;
 pub use generator::*;
 
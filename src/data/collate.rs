@@ -0,0 +1,81 @@
+use crate::autograd::tensor;
+use crate::tensor::{stack, Tensor};
+
+// Merges the samples of a batch into the value a DataLoader yields for it.
+pub trait Collate: Sized {
+    type Output;
+
+    fn collate(batch: Vec<Self>) -> Self::Output;
+}
+
+pub fn default_collate<T: Collate>(batch: Vec<T>) -> T::Output {
+    T::collate(batch)
+}
+
+// Tensors are stacked along a new first dimension.
+impl Collate for Tensor {
+    type Output = Tensor;
+
+    fn collate(batch: Vec<Self>) -> Self::Output {
+        stack(&batch, 0)
+    }
+}
+
+// Scalars become a 1-d tensor and vectors a 2-d tensor of shape [batch, len].
+macro_rules! IMPL_COLLATE_SCALAR {
+    ($t:ty) => {
+        impl Collate for $t {
+            type Output = Tensor;
+
+            fn collate(batch: Vec<Self>) -> Self::Output {
+                tensor(batch.as_slice(), None)
+            }
+        }
+
+        impl Collate for Vec<$t> {
+            type Output = Tensor;
+
+            fn collate(batch: Vec<Self>) -> Self::Output {
+                let len = batch.first().map_or(0, |sample| sample.len());
+                assert!(
+                    batch.iter().all(|sample| sample.len() == len),
+                    "collate expects each sample to be of equal length"
+                );
+                let data: Vec<$t> = batch.into_iter().flatten().collect();
+                let size = data.len() / len.max(1);
+                tensor(data.as_slice(), None).view(&[size, len])
+            }
+        }
+    };
+}
+
+IMPL_COLLATE_SCALAR!(f32);
+IMPL_COLLATE_SCALAR!(f64);
+IMPL_COLLATE_SCALAR!(i32);
+IMPL_COLLATE_SCALAR!(i64);
+
+// Each field of a tuple is collated separately.
+impl<A: Collate, B: Collate> Collate for (A, B) {
+    type Output = (A::Output, B::Output);
+
+    fn collate(batch: Vec<Self>) -> Self::Output {
+        let (a, b): (Vec<A>, Vec<B>) = batch.into_iter().unzip();
+        (A::collate(a), B::collate(b))
+    }
+}
+
+impl<A: Collate, B: Collate, C: Collate> Collate for (A, B, C) {
+    type Output = (A::Output, B::Output, C::Output);
+
+    fn collate(batch: Vec<Self>) -> Self::Output {
+        let mut a = Vec::with_capacity(batch.len());
+        let mut b = Vec::with_capacity(batch.len());
+        let mut c = Vec::with_capacity(batch.len());
+        for (x, y, z) in batch {
+            a.push(x);
+            b.push(y);
+            c.push(z);
+        }
+        (A::collate(a), B::collate(b), C::collate(c))
+    }
+}
@@ -0,0 +1,599 @@
+use super::{Collate, Dataset, IterableDataset};
+use crate::autograd::tensor;
+use crate::c10::ScalarType;
+use crate::tensor::Tensor;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// How missing values, i.e. empty fields or one of the `na_values`, are handled in
+// feature columns. Rows with a missing target are always skipped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fill {
+    // Numeric features get the value, categorical ones a category of their own.
+    Constant(f32),
+    // Numeric features get the mean of the column, categorical ones a category of
+    // their own. The means take an extra pass over the file.
+    Mean,
+    // Rows with missing features are skipped.
+    Skip,
+}
+
+#[derive(Debug, Clone)]
+pub struct CsvOptionsBuilder {
+    features: Option<Vec<String>>,
+    target: Option<String>,
+    target_dtype: ScalarType,
+    categorical: Vec<String>,
+    fill: Fill,
+    na_values: Vec<String>,
+    delimiter: u8,
+}
+
+impl Default for CsvOptionsBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CsvOptionsBuilder {
+    pub fn new() -> Self {
+        Self {
+            features: None,
+            target: None,
+            target_dtype: ScalarType::Float,
+            categorical: Vec::new(),
+            fill: Fill::Constant(0.0),
+            na_values: ["", "NA", "N/A", "NaN", "null"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            delimiter: b',',
+        }
+    }
+    // Feature columns in the order they appear in a sample. Defaults to every column
+    // except the target, which can't be listed as a feature.
+    pub fn features(&mut self, features: &[&str]) -> &mut Self {
+        self.features = Some(features.iter().map(|s| s.to_string()).collect());
+        self
+    }
+    pub fn target(&mut self, target: &str) -> &mut Self {
+        self.target = Some(target.to_string());
+        self
+    }
+    // Float for regression targets, Long for class indices. Categorical targets are
+    // always Long.
+    pub fn target_dtype(&mut self, dtype: ScalarType) -> &mut Self {
+        assert!(
+            dtype == ScalarType::Float || dtype == ScalarType::Long,
+            "CSV targets can be Float or Long but got {:?}",
+            dtype
+        );
+        self.target_dtype = dtype;
+        self
+    }
+    // Columns whose values are mapped to Long indices, in order of first appearance.
+    pub fn categorical(&mut self, categorical: &[&str]) -> &mut Self {
+        self.categorical = categorical.iter().map(|s| s.to_string()).collect();
+        self
+    }
+    pub fn fill(&mut self, fill: Fill) -> &mut Self {
+        self.fill = fill;
+        self
+    }
+    pub fn na_values(&mut self, na_values: &[&str]) -> &mut Self {
+        self.na_values = na_values.iter().map(|s| s.to_string()).collect();
+        self
+    }
+    pub fn delimiter(&mut self, delimiter: u8) -> &mut Self {
+        self.delimiter = delimiter;
+        self
+    }
+    pub fn build(&self) -> CsvOptions {
+        CsvOptions {
+            features: self.features.clone(),
+            target: self.target.clone(),
+            target_dtype: self.target_dtype,
+            categorical: self.categorical.clone(),
+            fill: self.fill,
+            na_values: self.na_values.clone(),
+            delimiter: self.delimiter,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    features: Option<Vec<String>>,
+    target: Option<String>,
+    target_dtype: ScalarType,
+    categorical: Vec<String>,
+    fill: Fill,
+    na_values: Vec<String>,
+    delimiter: u8,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptionsBuilder::new().build()
+    }
+}
+
+impl CsvOptions {
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+    pub fn fill(&self) -> Fill {
+        self.fill
+    }
+    pub fn delimiter(&self) -> u8 {
+        self.delimiter
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CsvTarget {
+    Float(f32),
+    Long(i64),
+}
+
+// One row of a CSV file. Numeric features and the indices of categorical features are
+// kept apart since they end up in tensors of different dtypes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvRow {
+    pub features: Vec<f32>,
+    pub categorical: Vec<i64>,
+    pub target: Option<CsvTarget>,
+}
+
+impl CsvRow {
+    // Tensors of a single row, shaped as a batch of one.
+    pub fn to_tensors(&self) -> CsvBatch {
+        CsvRow::collate(vec![self.clone()])
+    }
+}
+
+// A batch of rows: `features` is a Float tensor of shape [batch, numeric features],
+// `categorical` a Long tensor of shape [batch, categorical features] and `target` a
+// Float or Long tensor of shape [batch]. Empty groups of columns are None.
+pub struct CsvBatch {
+    pub features: Option<Tensor>,
+    pub categorical: Option<Tensor>,
+    pub target: Option<Tensor>,
+}
+
+impl Collate for CsvRow {
+    type Output = CsvBatch;
+
+    fn collate(batch: Vec<Self>) -> Self::Output {
+        let size = batch.len();
+        let features: Vec<f32> = batch.iter().flat_map(|row| row.features.clone()).collect();
+        let categorical: Vec<i64> = batch
+            .iter()
+            .flat_map(|row| row.categorical.clone())
+            .collect();
+        let target = match batch.first().and_then(|row| row.target) {
+            Some(CsvTarget::Float(_)) => Some(tensor(
+                batch
+                    .iter()
+                    .map(|row| match row.target {
+                        Some(CsvTarget::Float(t)) => t,
+                        _ => panic!("expected every row to have a Float target"),
+                    })
+                    .collect::<Vec<_>>()
+                    .as_slice(),
+                None,
+            )),
+            Some(CsvTarget::Long(_)) => Some(tensor(
+                batch
+                    .iter()
+                    .map(|row| match row.target {
+                        Some(CsvTarget::Long(t)) => t,
+                        _ => panic!("expected every row to have a Long target"),
+                    })
+                    .collect::<Vec<_>>()
+                    .as_slice(),
+                None,
+            )),
+            None => None,
+        };
+        let matrix = |numel: usize| (size > 0 && numel > 0).then(|| [size, numel / size]);
+        CsvBatch {
+            features: matrix(features.len())
+                .map(|sizes| tensor(features.as_slice(), None).view(&sizes)),
+            categorical: matrix(categorical.len())
+                .map(|sizes| tensor(categorical.as_slice(), None).view(&sizes)),
+            target,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TargetKind {
+    Float,
+    Long,
+    Categorical,
+}
+
+// Column indices picked from the header.
+#[derive(Debug, Clone)]
+struct Schema {
+    numeric: Vec<usize>,
+    categorical: Vec<usize>,
+    target: Option<(usize, TargetKind)>,
+    headers: Vec<String>,
+}
+
+impl Schema {
+    fn new(headers: Vec<String>, options: &CsvOptions) -> io::Result<Self> {
+        let column = |name: &str| {
+            headers.iter().position(|h| h == name).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("column {:?} is not in the header {:?}", name, headers),
+                )
+            })
+        };
+        for name in &options.categorical {
+            column(name)?;
+        }
+        let target = match &options.target {
+            Some(name) => {
+                let kind = if options.categorical.contains(name) {
+                    TargetKind::Categorical
+                } else if options.target_dtype == ScalarType::Long {
+                    TargetKind::Long
+                } else {
+                    TargetKind::Float
+                };
+                Some((column(name)?, kind))
+            }
+            None => None,
+        };
+        let features = match &options.features {
+            Some(features) => {
+                if let Some(target) = options.target.as_ref().filter(|t| features.contains(t)) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("target column {:?} is also a feature", target),
+                    ));
+                }
+                features
+                    .iter()
+                    .map(|name| column(name))
+                    .collect::<io::Result<Vec<_>>>()?
+            }
+            None => (0..headers.len())
+                .filter(|&i| Some(i) != target.map(|(t, _)| t))
+                .collect(),
+        };
+        let (categorical, numeric) = features
+            .into_iter()
+            .partition(|&i| options.categorical.contains(&headers[i]));
+        Ok(Self {
+            numeric,
+            categorical,
+            target,
+            headers,
+        })
+    }
+}
+
+// Values of a categorical column, indexed in order of first appearance.
+#[derive(Debug, Clone, Default)]
+struct Categories {
+    indices: HashMap<String, i64>,
+    values: Vec<String>,
+}
+
+impl Categories {
+    fn index(&mut self, value: &str) -> i64 {
+        if let Some(&index) = self.indices.get(value) {
+            return index;
+        }
+        let index = self.values.len() as i64;
+        self.indices.insert(value.to_string(), index);
+        self.values.push(value.to_string());
+        index
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn open_reader(path: &Path, options: &CsvOptions) -> io::Result<::csv::Reader<File>> {
+    Ok(::csv::ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .trim(::csv::Trim::All)
+        .from_reader(File::open(path)?))
+}
+
+fn read_record(
+    reader: &mut ::csv::Reader<File>,
+    record: &mut ::csv::StringRecord,
+) -> io::Result<bool> {
+    reader
+        .read_record(record)
+        .map_err(|e| invalid_data(e.to_string()))
+}
+
+// Parses the rows of a CSV file one at a time.
+struct RowParser<'a> {
+    schema: &'a Schema,
+    options: &'a CsvOptions,
+    means: &'a HashMap<usize, f32>,
+}
+
+impl<'a> RowParser<'a> {
+    fn field<'r>(
+        &self,
+        record: &'r ::csv::StringRecord,
+        column: usize,
+    ) -> io::Result<Option<&'r str>> {
+        let value = record.get(column).ok_or_else(|| {
+            invalid_data(format!(
+                "line {} has no value for column {:?}",
+                record.position().map_or(0, |p| p.line()),
+                self.schema.headers[column]
+            ))
+        })?;
+        Ok(if self.options.na_values.iter().any(|na| na == value) {
+            None
+        } else {
+            Some(value)
+        })
+    }
+
+    fn number<T: std::str::FromStr>(
+        &self,
+        record: &::csv::StringRecord,
+        column: usize,
+        value: &str,
+    ) -> io::Result<T> {
+        value.parse().map_err(|_| {
+            invalid_data(format!(
+                "can't parse {:?} in column {:?} on line {}",
+                value,
+                self.schema.headers[column],
+                record.position().map_or(0, |p| p.line())
+            ))
+        })
+    }
+
+    // None for rows that are skipped because of missing values.
+    fn parse(
+        &self,
+        record: &::csv::StringRecord,
+        categories: &mut HashMap<usize, Categories>,
+    ) -> io::Result<Option<CsvRow>> {
+        let target = match self.schema.target {
+            Some((column, kind)) => match self.field(record, column)? {
+                None => return Ok(None),
+                Some(value) => Some(match kind {
+                    TargetKind::Float => CsvTarget::Float(self.number(record, column, value)?),
+                    TargetKind::Long => CsvTarget::Long(self.number(record, column, value)?),
+                    TargetKind::Categorical => {
+                        CsvTarget::Long(categories.entry(column).or_default().index(value))
+                    }
+                }),
+            },
+            None => None,
+        };
+        let mut features = Vec::with_capacity(self.schema.numeric.len());
+        for &column in &self.schema.numeric {
+            features.push(match (self.field(record, column)?, self.options.fill) {
+                (Some(value), _) => self.number(record, column, value)?,
+                (None, Fill::Constant(fill)) => fill,
+                (None, Fill::Mean) => self.means[&column],
+                (None, Fill::Skip) => return Ok(None),
+            });
+        }
+        let mut categorical = Vec::with_capacity(self.schema.categorical.len());
+        for &column in &self.schema.categorical {
+            let value = match self.field(record, column)? {
+                Some(value) => value,
+                None if self.options.fill == Fill::Skip => return Ok(None),
+                None => "",
+            };
+            categorical.push(categories.entry(column).or_default().index(value));
+        }
+        Ok(Some(CsvRow {
+            features,
+            categorical,
+            target,
+        }))
+    }
+}
+
+// Reads the header and, for Fill::Mean, the means of the numeric feature columns.
+fn prepare(path: &Path, options: &CsvOptions) -> io::Result<(Schema, HashMap<usize, f32>)> {
+    let mut reader = open_reader(path, options)?;
+    let headers = reader
+        .headers()
+        .map_err(|e| invalid_data(e.to_string()))?
+        .iter()
+        .map(|h| h.to_string())
+        .collect();
+    let schema = Schema::new(headers, options)?;
+    let mut means = HashMap::new();
+    if options.fill == Fill::Mean {
+        let parser = RowParser {
+            schema: &schema,
+            options,
+            means: &means,
+        };
+        let mut sums = vec![(0.0f64, 0usize); schema.numeric.len()];
+        let mut record = ::csv::StringRecord::new();
+        while read_record(&mut reader, &mut record)? {
+            for (sum, &column) in sums.iter_mut().zip(&schema.numeric) {
+                if let Some(value) = parser.field(&record, column)? {
+                    sum.0 += parser.number::<f64>(&record, column, value)?;
+                    sum.1 += 1;
+                }
+            }
+        }
+        means = schema
+            .numeric
+            .iter()
+            .zip(sums)
+            .map(|(&column, (sum, count))| (column, (sum / count.max(1) as f64) as f32))
+            .collect();
+    }
+    Ok((schema, means))
+}
+
+fn category_values<'a>(
+    schema: &Schema,
+    categories: &'a HashMap<usize, Categories>,
+    column: &str,
+) -> Option<&'a [String]> {
+    let index = schema.headers.iter().position(|h| h == column)?;
+    categories.get(&index).map(|c| c.values.as_slice())
+}
+
+// A CSV file with a header row, read into memory. Samples are CsvRows, which the
+// DataLoader collates into a CsvBatch.
+#[derive(Debug, Clone)]
+pub struct CsvDataset {
+    rows: Vec<CsvRow>,
+    schema: Schema,
+    categories: HashMap<usize, Categories>,
+}
+
+impl CsvDataset {
+    pub fn new<P: AsRef<Path>>(path: P, options: &CsvOptions) -> io::Result<Self> {
+        let path = path.as_ref();
+        let (schema, means) = prepare(path, options)?;
+        let parser = RowParser {
+            schema: &schema,
+            options,
+            means: &means,
+        };
+        let mut categories = HashMap::new();
+        let mut rows = Vec::new();
+        let mut reader = open_reader(path, options)?;
+        let mut record = ::csv::StringRecord::new();
+        while read_record(&mut reader, &mut record)? {
+            if let Some(row) = parser.parse(&record, &mut categories)? {
+                rows.push(row);
+            }
+        }
+        Ok(Self {
+            rows,
+            schema,
+            categories,
+        })
+    }
+
+    pub fn rows(&self) -> &[CsvRow] {
+        &self.rows
+    }
+
+    // Names of the numeric feature columns, in the order of CsvRow::features.
+    pub fn feature_names(&self) -> Vec<&str> {
+        self.schema
+            .numeric
+            .iter()
+            .map(|&i| self.schema.headers[i].as_str())
+            .collect()
+    }
+
+    // Names of the categorical feature columns, in the order of CsvRow::categorical.
+    pub fn categorical_names(&self) -> Vec<&str> {
+        self.schema
+            .categorical
+            .iter()
+            .map(|&i| self.schema.headers[i].as_str())
+            .collect()
+    }
+
+    // Values of a categorical column, the position of each being its index.
+    pub fn categories(&self, column: &str) -> Option<&[String]> {
+        category_values(&self.schema, &self.categories, column)
+    }
+}
+
+impl Dataset for CsvDataset {
+    type Item = CsvRow;
+
+    fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn get(&self, index: usize) -> Self::Item {
+        assert!(
+            index < self.len(),
+            "Index {} is out of range for a dataset of {} samples",
+            index,
+            self.len()
+        );
+        self.rows[index].clone()
+    }
+}
+
+// A CSV file read one row at a time on every pass, for files that don't fit in
+// memory. Categorical indices are assigned as values show up, so they match those of
+// a CsvDataset over the same file. Malformed rows panic while iterating.
+#[derive(Debug)]
+pub struct CsvStream {
+    path: PathBuf,
+    options: CsvOptions,
+    schema: Schema,
+    means: HashMap<usize, f32>,
+    categories: RefCell<HashMap<usize, Categories>>,
+}
+
+impl CsvStream {
+    pub fn new<P: AsRef<Path>>(path: P, options: &CsvOptions) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let (schema, means) = prepare(&path, options)?;
+        Ok(Self {
+            path,
+            options: options.clone(),
+            schema,
+            means,
+            categories: RefCell::new(HashMap::new()),
+        })
+    }
+
+    // Values of a categorical column seen so far.
+    pub fn categories(&self, column: &str) -> Option<Vec<String>> {
+        category_values(&self.schema, &self.categories.borrow(), column).map(|v| v.to_vec())
+    }
+}
+
+impl IterableDataset for CsvStream {
+    type Item = CsvRow;
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Self::Item> + '_> {
+        let mut reader = open_reader(&self.path, &self.options)
+            .unwrap_or_else(|e| panic!("failed to open {:?}: {}", self.path, e));
+        let parser = RowParser {
+            schema: &self.schema,
+            options: &self.options,
+            means: &self.means,
+        };
+        let mut record = ::csv::StringRecord::new();
+        Box::new(std::iter::from_fn(move || loop {
+            let row = read_record(&mut reader, &mut record)
+                .and_then(|more| {
+                    if more {
+                        parser
+                            .parse(&record, &mut self.categories.borrow_mut())
+                            .map(Some)
+                    } else {
+                        Ok(None)
+                    }
+                })
+                .unwrap_or_else(|e| panic!("failed to read {:?}: {}", self.path, e));
+            match row {
+                Some(Some(row)) => return Some(row),
+                Some(None) => continue,
+                None => return None,
+            }
+        }))
+    }
+}
@@ -0,0 +1,293 @@
+use super::{
+    default_collate, BatchSampler, Collate, Dataset, IterableDataset, RandomSampler, Sampler,
+    SequentialSampler,
+};
+use crate::core::Generator;
+use std::sync::mpsc::{sync_channel, Receiver};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+// Batches each worker may load ahead of the one being consumed.
+const PREFETCH_FACTOR: usize = 2;
+
+#[derive(Default)]
+pub struct DataLoaderOptionsBuilder {
+    batch_size: usize,
+    shuffle: bool,
+    drop_last: bool,
+    generator: Option<Generator>,
+}
+
+impl DataLoaderOptionsBuilder {
+    pub fn new(batch_size: usize) -> Self {
+        Self {
+            batch_size,
+            ..Default::default()
+        }
+    }
+    pub fn shuffle(&mut self, shuffle: bool) -> &mut Self {
+        self.shuffle = shuffle;
+        self
+    }
+    pub fn drop_last(&mut self, drop_last: bool) -> &mut Self {
+        self.drop_last = drop_last;
+        self
+    }
+    // Generator used for shuffling instead of the default CPU generator.
+    pub fn generator(&mut self, generator: Generator) -> &mut Self {
+        self.generator = Some(generator);
+        self
+    }
+    pub fn build(&self) -> DataLoaderOptions {
+        DataLoaderOptions {
+            batch_size: self.batch_size,
+            shuffle: self.shuffle,
+            drop_last: self.drop_last,
+            generator: self.generator.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct DataLoaderOptions {
+    batch_size: usize,
+    shuffle: bool,
+    drop_last: bool,
+    generator: Option<Generator>,
+}
+
+impl DataLoaderOptions {
+    pub fn new(batch_size: usize) -> Self {
+        DataLoaderOptionsBuilder::new(batch_size).build()
+    }
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+    pub fn shuffle(&self) -> bool {
+        self.shuffle
+    }
+    pub fn drop_last(&self) -> bool {
+        self.drop_last
+    }
+}
+
+// Worker threads loading the samples of every `workers.len()`-th batch, starting at
+// their own position. Batches are received in order by cycling over the workers.
+struct Workers<T> {
+    receivers: Vec<Receiver<Vec<T>>>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl<T> Drop for Workers<T> {
+    fn drop(&mut self) {
+        // Workers blocked on a full channel stop once their receiver is gone.
+        self.receivers.clear();
+        for handle in self.handles.drain(..) {
+            let _ = handle.join();
+        }
+    }
+}
+
+type SpawnWorkers<T> = Box<dyn Fn(Vec<Vec<usize>>) -> Workers<T>>;
+
+// Loads batches of samples from a dataset and collates them, e.g. into tensors.
+// Samples are loaded on the calling thread unless `num_workers` is set.
+pub struct DataLoader<D: Dataset, B> {
+    dataset: Arc<D>,
+    options: DataLoaderOptions,
+    batch_sampler: BatchSampler,
+    collate: Box<dyn Fn(Vec<D::Item>) -> B>,
+    spawn_workers: Option<SpawnWorkers<D::Item>>,
+}
+
+impl<D> DataLoader<D, <D::Item as Collate>::Output>
+where
+    D: Dataset,
+    D::Item: Collate + 'static,
+{
+    pub fn new(dataset: D, options: DataLoaderOptions) -> Self {
+        Self::new_with_collate(dataset, options, default_collate)
+    }
+}
+
+impl<D: Dataset, B> DataLoader<D, B> {
+    pub fn new_with_collate<F>(dataset: D, options: DataLoaderOptions, collate: F) -> Self
+    where
+        F: Fn(Vec<D::Item>) -> B + 'static,
+    {
+        let len = dataset.len();
+        let batch_sampler = if options.shuffle {
+            BatchSampler::new(
+                RandomSampler::new(len, options.generator.clone()),
+                options.batch_size,
+                options.drop_last,
+            )
+        } else {
+            BatchSampler::new(
+                SequentialSampler::new(len),
+                options.batch_size,
+                options.drop_last,
+            )
+        };
+        Self {
+            dataset: Arc::new(dataset),
+            options,
+            batch_sampler,
+            collate: Box::new(collate),
+            spawn_workers: None,
+        }
+    }
+
+    // Replaces the sampler picked from the `shuffle` option.
+    pub fn with_sampler<S: Sampler + 'static>(mut self, sampler: S) -> Self {
+        self.batch_sampler =
+            BatchSampler::new(sampler, self.options.batch_size, self.options.drop_last);
+        self
+    }
+
+    pub fn with_batch_sampler(mut self, batch_sampler: BatchSampler) -> Self {
+        self.batch_sampler = batch_sampler;
+        self
+    }
+
+    pub fn options(&self) -> &DataLoaderOptions {
+        &self.options
+    }
+
+    pub fn dataset(&self) -> &D {
+        &self.dataset
+    }
+
+    // Number of batches in one pass.
+    pub fn len(&self) -> usize {
+        self.batch_sampler.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.batch_sampler.is_empty()
+    }
+
+    // One pass over the dataset.
+    pub fn iter(&mut self) -> DataLoaderIter<'_, D, B> {
+        let batches = self.batch_sampler.batches();
+        let workers = self
+            .spawn_workers
+            .as_ref()
+            .map(|spawn| spawn(batches.clone()));
+        DataLoaderIter {
+            dataset: &self.dataset,
+            collate: &self.collate,
+            batches: batches.into_iter(),
+            workers,
+            position: 0,
+        }
+    }
+}
+
+impl<D, B> DataLoader<D, B>
+where
+    D: Dataset + Send + Sync + 'static,
+    D::Item: Send + 'static,
+{
+    // Loads samples on `num_workers` background threads, which prefetch up to
+    // PREFETCH_FACTOR batches each. Only loading runs there: `Tensor` is `Rc` based and
+    // not `Send`, so samples have to be plain data and are collated into tensors on the
+    // calling thread.
+    pub fn num_workers(mut self, num_workers: usize) -> Self {
+        if num_workers == 0 {
+            self.spawn_workers = None;
+            return self;
+        }
+        let dataset = self.dataset.clone();
+        self.spawn_workers = Some(Box::new(move |batches: Vec<Vec<usize>>| {
+            let batches = Arc::new(batches);
+            let mut receivers = Vec::with_capacity(num_workers);
+            let mut handles = Vec::with_capacity(num_workers);
+            for worker in 0..num_workers {
+                let (sender, receiver) = sync_channel(PREFETCH_FACTOR);
+                let dataset = dataset.clone();
+                let batches = batches.clone();
+                handles.push(thread::spawn(move || {
+                    for batch in batches.iter().skip(worker).step_by(num_workers) {
+                        let samples = batch.iter().map(|&i| dataset.get(i)).collect();
+                        if sender.send(samples).is_err() {
+                            break;
+                        }
+                    }
+                }));
+                receivers.push(receiver);
+            }
+            Workers { receivers, handles }
+        }));
+        self
+    }
+}
+
+pub struct DataLoaderIter<'a, D: Dataset, B> {
+    dataset: &'a D,
+    collate: &'a dyn Fn(Vec<D::Item>) -> B,
+    batches: std::vec::IntoIter<Vec<usize>>,
+    workers: Option<Workers<D::Item>>,
+    position: usize,
+}
+
+impl<'a, D: Dataset, B> Iterator for DataLoaderIter<'a, D, B> {
+    type Item = B;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch = self.batches.next()?;
+        let samples = match self.workers.as_ref() {
+            Some(workers) => workers.receivers[self.position % workers.receivers.len()]
+                .recv()
+                .expect("DataLoader worker exited unexpectedly"),
+            None => batch.iter().map(|&i| self.dataset.get(i)).collect(),
+        };
+        self.position += 1;
+        Some((self.collate)(samples))
+    }
+}
+
+// Batches the samples of an IterableDataset in the order it yields them.
+pub struct IterableDataLoader<I: IterableDataset, B> {
+    dataset: I,
+    batch_size: usize,
+    drop_last: bool,
+    collate: Box<dyn Fn(Vec<I::Item>) -> B>,
+}
+
+impl<I> IterableDataLoader<I, <I::Item as Collate>::Output>
+where
+    I: IterableDataset,
+    I::Item: Collate + 'static,
+{
+    pub fn new(dataset: I, batch_size: usize, drop_last: bool) -> Self {
+        Self::new_with_collate(dataset, batch_size, drop_last, default_collate)
+    }
+}
+
+impl<I: IterableDataset, B> IterableDataLoader<I, B> {
+    pub fn new_with_collate<F>(dataset: I, batch_size: usize, drop_last: bool, collate: F) -> Self
+    where
+        F: Fn(Vec<I::Item>) -> B + 'static,
+    {
+        assert!(batch_size > 0, "batch_size should be a positive integer");
+        Self {
+            dataset,
+            batch_size,
+            drop_last,
+            collate: Box::new(collate),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = B> + '_ {
+        let mut samples = self.dataset.iter();
+        std::iter::from_fn(move || {
+            let batch: Vec<_> = samples.by_ref().take(self.batch_size).collect();
+            if batch.is_empty() || (self.drop_last && batch.len() < self.batch_size) {
+                None
+            } else {
+                Some((self.collate)(batch))
+            }
+        })
+    }
+}
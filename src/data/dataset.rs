@@ -0,0 +1,19 @@
+// A map-style dataset: samples are accessed by index.
+pub trait Dataset {
+    type Item;
+
+    fn len(&self) -> usize;
+
+    fn get(&self, index: usize) -> Self::Item;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// A dataset which can only be read in order, e.g. a stream of records.
+pub trait IterableDataset {
+    type Item;
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Self::Item> + '_>;
+}
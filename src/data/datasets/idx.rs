@@ -0,0 +1,156 @@
+use flate2::read::GzDecoder;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+// IDX layout: magic number (two zero bytes, a dtype code and the number of
+// dimensions), each dimension as a big endian u32, then the big endian data.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdxData {
+    U8(Vec<u8>),
+    I8(Vec<i8>),
+    I16(Vec<i16>),
+    I32(Vec<i32>),
+    F32(Vec<f32>),
+    F64(Vec<f64>),
+}
+
+macro_rules! read_values {
+    ($bytes:expr, $t:ty) => {
+        $bytes
+            .chunks_exact(std::mem::size_of::<$t>())
+            .map(|chunk| {
+                let mut value = [0u8; std::mem::size_of::<$t>()];
+                value.copy_from_slice(chunk);
+                <$t>::from_be_bytes(value)
+            })
+            .collect()
+    };
+}
+
+impl IdxData {
+    fn element_size(code: u8) -> io::Result<usize> {
+        match code {
+            0x08 | 0x09 => Ok(1),
+            0x0B => Ok(2),
+            0x0C | 0x0D => Ok(4),
+            0x0E => Ok(8),
+            _ => Err(invalid_data(format!(
+                "unknown IDX dtype code {:#04x}",
+                code
+            ))),
+        }
+    }
+
+    fn from_be_bytes(code: u8, bytes: &[u8]) -> Self {
+        match code {
+            0x08 => IdxData::U8(bytes.to_vec()),
+            0x09 => IdxData::I8(bytes.iter().map(|&b| b as i8).collect()),
+            0x0B => IdxData::I16(read_values!(bytes, i16)),
+            0x0C => IdxData::I32(read_values!(bytes, i32)),
+            0x0D => IdxData::F32(read_values!(bytes, f32)),
+            0x0E => IdxData::F64(read_values!(bytes, f64)),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            IdxData::U8(v) => v.len(),
+            IdxData::I8(v) => v.len(),
+            IdxData::I16(v) => v.len(),
+            IdxData::I32(v) => v.len(),
+            IdxData::F32(v) => v.len(),
+            IdxData::F64(v) => v.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Integers are scaled by the maximum of their type, so u8 pixels end up in [0, 1].
+    // Floating point values are kept as is.
+    pub fn to_normalized_f32(&self) -> Vec<f32> {
+        match self {
+            IdxData::U8(v) => v.iter().map(|&x| x as f32 / u8::MAX as f32).collect(),
+            IdxData::I8(v) => v.iter().map(|&x| x as f32 / i8::MAX as f32).collect(),
+            IdxData::I16(v) => v.iter().map(|&x| x as f32 / i16::MAX as f32).collect(),
+            IdxData::I32(v) => v.iter().map(|&x| x as f32 / i32::MAX as f32).collect(),
+            IdxData::F32(v) => v.clone(),
+            IdxData::F64(v) => v.iter().map(|&x| x as f32).collect(),
+        }
+    }
+
+    pub fn to_i64(&self) -> Vec<i64> {
+        match self {
+            IdxData::U8(v) => v.iter().map(|&x| x as i64).collect(),
+            IdxData::I8(v) => v.iter().map(|&x| x as i64).collect(),
+            IdxData::I16(v) => v.iter().map(|&x| x as i64).collect(),
+            IdxData::I32(v) => v.iter().map(|&x| x as i64).collect(),
+            IdxData::F32(v) => v.iter().map(|&x| x as i64).collect(),
+            IdxData::F64(v) => v.iter().map(|&x| x as i64).collect(),
+        }
+    }
+}
+
+// An array read from an IDX file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdxArray {
+    pub sizes: Vec<usize>,
+    pub data: IdxData,
+}
+
+pub fn read_idx<R: Read>(reader: &mut R) -> io::Result<IdxArray> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if magic[0] != 0 || magic[1] != 0 {
+        return Err(invalid_data("not an IDX file".to_string()));
+    }
+    let code = magic[2];
+    let element_size = IdxData::element_size(code)?;
+    let mut sizes = Vec::with_capacity(magic[3] as usize);
+    for _ in 0..magic[3] {
+        let mut size = [0u8; 4];
+        reader.read_exact(&mut size)?;
+        sizes.push(u32::from_be_bytes(size) as usize);
+    }
+    let nbytes = sizes
+        .iter()
+        .try_fold(element_size, |acc, &size| acc.checked_mul(size))
+        .ok_or_else(|| invalid_data(format!("IDX array of size {:?} is too large", sizes)))?;
+    // The header isn't trusted to size the buffer, it grows with the data actually read.
+    let mut bytes = Vec::new();
+    reader.take(nbytes as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != nbytes {
+        return Err(invalid_data(format!(
+            "IDX array of size {:?} needs {} bytes of data but only {} are left",
+            sizes,
+            nbytes,
+            bytes.len()
+        )));
+    }
+    Ok(IdxArray {
+        data: IdxData::from_be_bytes(code, &bytes),
+        sizes,
+    })
+}
+
+// Reads an IDX file, decompressing it first if it's gzipped.
+pub fn load_idx<P: AsRef<Path>>(path: P) -> io::Result<IdxArray> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut magic = [0u8; 2];
+    reader.read_exact(&mut magic)?;
+    let mut reader = io::Cursor::new(magic).chain(reader);
+    if magic == GZIP_MAGIC {
+        read_idx(&mut GzDecoder::new(reader))
+    } else {
+        read_idx(&mut reader)
+    }
+}
@@ -0,0 +1,103 @@
+use super::load_idx;
+use crate::autograd::tensor;
+use crate::data::Dataset;
+use crate::tensor::Tensor;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// MNIST style dataset read from IDX files, e.g. MNIST or Fashion-MNIST. Samples are
+// a Float image tensor, normalized to [0, 1] for integer pixels, and a Long label.
+#[derive(Debug, Clone)]
+pub struct Mnist {
+    images: Vec<f32>,
+    labels: Vec<i64>,
+    image_sizes: Vec<usize>,
+}
+
+// Picks the uncompressed file if it exists, otherwise the gzipped one.
+fn find_file(root: &Path, name: &str) -> io::Result<PathBuf> {
+    let path = root.join(name);
+    let gz_path = root.join(format!("{}.gz", name));
+    if path.exists() {
+        Ok(path)
+    } else if gz_path.exists() {
+        Ok(gz_path)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("neither {:?} nor {:?} exist", path, gz_path),
+        ))
+    }
+}
+
+impl Mnist {
+    // Reads the training or test split from `root`, which holds the files with their
+    // original names, e.g. train-images-idx3-ubyte.gz.
+    pub fn new<P: AsRef<Path>>(root: P, train: bool) -> io::Result<Self> {
+        let root = root.as_ref();
+        let prefix = if train { "train" } else { "t10k" };
+        Self::from_files(
+            find_file(root, &format!("{}-images-idx3-ubyte", prefix))?,
+            find_file(root, &format!("{}-labels-idx1-ubyte", prefix))?,
+        )
+    }
+
+    pub fn from_files<P: AsRef<Path>, Q: AsRef<Path>>(images: P, labels: Q) -> io::Result<Self> {
+        let images = load_idx(images)?;
+        let labels = load_idx(labels)?;
+        // Any rank works, as long as the samples are stacked along the first dimension.
+        let count = match images.sizes.first() {
+            Some(&count) => count,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "expected images in an array of at least 1 dimension but got a scalar",
+                ))
+            }
+        };
+        if labels.sizes.len() != 1 || labels.sizes[0] != count {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "expected {} labels in a 1-d array but got an array of size {:?}",
+                    count, labels.sizes
+                ),
+            ));
+        }
+        Ok(Self {
+            images: images.data.to_normalized_f32(),
+            labels: labels.data.to_i64(),
+            image_sizes: images.sizes[1..].to_vec(),
+        })
+    }
+
+    // Size of a single image, e.g. [28, 28].
+    pub fn image_sizes(&self) -> &[usize] {
+        &self.image_sizes
+    }
+
+    pub fn labels(&self) -> &[i64] {
+        &self.labels
+    }
+}
+
+impl Dataset for Mnist {
+    type Item = (Tensor, i64);
+
+    fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    fn get(&self, index: usize) -> Self::Item {
+        assert!(
+            index < self.len(),
+            "Index {} is out of range for a dataset of {} samples",
+            index,
+            self.len()
+        );
+        let numel = self.image_sizes.iter().product::<usize>();
+        let pixels = &self.images[index * numel..(index + 1) * numel];
+        let image = tensor(pixels, None).view(&self.image_sizes);
+        (image, self.labels[index])
+    }
+}
@@ -0,0 +1,5 @@
+mod idx;
+pub use idx::*;
+
+mod mnist;
+pub use mnist::*;
@@ -0,0 +1,15 @@
+mod dataset;
+pub use dataset::*;
+
+mod sampler;
+pub use sampler::*;
+
+mod collate;
+pub use collate::*;
+
+mod dataloader;
+pub use dataloader::*;
+
+pub mod datasets;
+
+pub mod csv;
@@ -0,0 +1,256 @@
+use crate::core::{check_generator, get_default_cpu_generator, Generator, UniformRealDistribution};
+
+// Decides the order in which the samples of a dataset are visited.
+pub trait Sampler {
+    // Indices for one pass over the dataset. Random samplers draw new indices on
+    // every call.
+    fn sample(&mut self) -> Vec<usize>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// Samplers without a generator of their own use the default CPU generator, so
+// `manual_seed` makes them deterministic.
+fn generator(generator: &mut Option<Generator>) -> &mut Generator {
+    match generator {
+        Some(generator) => generator,
+        None => get_default_cpu_generator(),
+    }
+}
+
+// Uniformly distributed integer in [0, n).
+fn random_index(gen: &mut Generator, n: usize) -> usize {
+    (check_generator(gen).random64() % n as u64) as usize
+}
+
+fn random_unit(gen: &mut Generator) -> f64 {
+    UniformRealDistribution::<f64>::new(0.0, 1.0).call(check_generator(gen))
+}
+
+// Fisher-Yates shuffle.
+fn shuffle(gen: &mut Generator, indices: &mut [usize]) {
+    for i in (1..indices.len()).rev() {
+        indices.swap(i, random_index(gen, i + 1));
+    }
+}
+
+// Visits the samples in order.
+#[derive(Debug, Clone)]
+pub struct SequentialSampler {
+    len: usize,
+}
+
+impl SequentialSampler {
+    pub fn new(len: usize) -> Self {
+        Self { len }
+    }
+}
+
+impl Sampler for SequentialSampler {
+    fn sample(&mut self) -> Vec<usize> {
+        (0..self.len).collect()
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+// Visits the samples in a random order. With replacement, `num_samples` indices are
+// drawn independently instead.
+#[derive(Clone)]
+pub struct RandomSampler {
+    dataset_len: usize,
+    replacement: bool,
+    num_samples: usize,
+    generator: Option<Generator>,
+}
+
+impl RandomSampler {
+    pub fn new(len: usize, generator: Option<Generator>) -> Self {
+        Self {
+            dataset_len: len,
+            replacement: false,
+            num_samples: len,
+            generator,
+        }
+    }
+
+    pub fn with_replacement(len: usize, num_samples: usize, generator: Option<Generator>) -> Self {
+        assert!(
+            len > 0 || num_samples == 0,
+            "Cannot draw samples with replacement from an empty dataset"
+        );
+        Self {
+            dataset_len: len,
+            replacement: true,
+            num_samples,
+            generator,
+        }
+    }
+}
+
+impl Sampler for RandomSampler {
+    fn sample(&mut self) -> Vec<usize> {
+        let len = self.dataset_len;
+        let gen = generator(&mut self.generator);
+        if self.replacement {
+            (0..self.num_samples)
+                .map(|_| random_index(gen, len))
+                .collect()
+        } else {
+            let mut indices: Vec<usize> = (0..len).collect();
+            shuffle(gen, &mut indices);
+            indices
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.num_samples
+    }
+}
+
+// Visits the given indices in a random order, e.g. to split a dataset into training
+// and validation samples.
+#[derive(Clone)]
+pub struct SubsetRandomSampler {
+    indices: Vec<usize>,
+    generator: Option<Generator>,
+}
+
+impl SubsetRandomSampler {
+    pub fn new(indices: Vec<usize>, generator: Option<Generator>) -> Self {
+        Self { indices, generator }
+    }
+}
+
+impl Sampler for SubsetRandomSampler {
+    fn sample(&mut self) -> Vec<usize> {
+        let mut indices = self.indices.clone();
+        shuffle(generator(&mut self.generator), &mut indices);
+        indices
+    }
+
+    fn len(&self) -> usize {
+        self.indices.len()
+    }
+}
+
+// Draws `num_samples` indices, each with a probability proportional to its weight.
+#[derive(Clone)]
+pub struct WeightedRandomSampler {
+    weights: Vec<f64>,
+    num_samples: usize,
+    replacement: bool,
+    generator: Option<Generator>,
+}
+
+impl WeightedRandomSampler {
+    pub fn new(
+        weights: Vec<f64>,
+        num_samples: usize,
+        replacement: bool,
+        generator: Option<Generator>,
+    ) -> Self {
+        assert!(
+            weights.iter().all(|w| w.is_finite() && *w >= 0.0),
+            "weights must be finite and non-negative, got {:?}",
+            weights
+        );
+        let non_zero = weights.iter().filter(|w| **w > 0.0).count();
+        assert!(
+            non_zero > 0 || num_samples == 0,
+            "WeightedRandomSampler needs at least one non-zero weight"
+        );
+        assert!(
+            replacement || num_samples <= non_zero,
+            "Cannot draw {} samples without replacement from {} non-zero weights",
+            num_samples,
+            non_zero
+        );
+        Self {
+            weights,
+            num_samples,
+            replacement,
+            generator,
+        }
+    }
+}
+
+impl Sampler for WeightedRandomSampler {
+    fn sample(&mut self) -> Vec<usize> {
+        let gen = generator(&mut self.generator);
+        let mut weights = self.weights.clone();
+        let mut result = Vec::with_capacity(self.num_samples);
+        for _ in 0..self.num_samples {
+            let total: f64 = weights.iter().sum();
+            let target = random_unit(gen) * total;
+            let mut cumulative = 0.0;
+            // Falls back to the last non-zero weight if rounding leaves `target` past
+            // the end.
+            let mut index = weights.iter().rposition(|w| *w > 0.0).unwrap();
+            for (i, w) in weights.iter().enumerate() {
+                cumulative += w;
+                if *w > 0.0 && target < cumulative {
+                    index = i;
+                    break;
+                }
+            }
+            if !self.replacement {
+                weights[index] = 0.0;
+            }
+            result.push(index);
+        }
+        result
+    }
+
+    fn len(&self) -> usize {
+        self.num_samples
+    }
+}
+
+// Groups the indices of another sampler into batches.
+pub struct BatchSampler {
+    sampler: Box<dyn Sampler>,
+    batch_size: usize,
+    drop_last: bool,
+}
+
+impl BatchSampler {
+    pub fn new<S: Sampler + 'static>(sampler: S, batch_size: usize, drop_last: bool) -> Self {
+        assert!(batch_size > 0, "batch_size should be a positive integer");
+        Self {
+            sampler: Box::new(sampler),
+            batch_size,
+            drop_last,
+        }
+    }
+
+    // Batches for one pass over the dataset. Unless `drop_last` is set, the last batch
+    // may be smaller than `batch_size`.
+    pub fn batches(&mut self) -> Vec<Vec<usize>> {
+        let indices = self.sampler.sample();
+        indices
+            .chunks(self.batch_size)
+            .filter(|batch| !self.drop_last || batch.len() == self.batch_size)
+            .map(|batch| batch.to_vec())
+            .collect()
+    }
+
+    // Number of batches in one pass.
+    pub fn len(&self) -> usize {
+        if self.drop_last {
+            self.sampler.len() / self.batch_size
+        } else {
+            self.sampler.len().div_ceil(self.batch_size)
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -12,9 +12,12 @@ extern crate blas_src;
 pub mod aten;
 pub mod c10;
 pub mod core;
+pub mod data;
 pub mod nn;
 mod ops;
 pub mod optim;
+pub mod serialize;
+pub mod sparse;
 
 mod rsrc;
 pub use rsrc::*;
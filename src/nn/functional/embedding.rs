@@ -0,0 +1,12 @@
+use crate::tensor::{self, Tensor};
+
+// Looks up the rows of `weight` at the integer `input`. Rows at `padding_idx` get no
+// gradient, and a sparse gradient only holds the rows that were looked up.
+pub fn embedding(
+    input: &Tensor,
+    weight: &Tensor,
+    padding_idx: Option<usize>,
+    sparse: bool,
+) -> Tensor {
+    tensor::embedding(weight, input, padding_idx, sparse)
+}
@@ -4,5 +4,8 @@ pub use activation::*;
 mod linear;
 pub use linear::*;
 
+mod embedding;
+pub use embedding::*;
+
 mod loss;
 pub use loss::*;
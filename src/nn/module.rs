@@ -1,8 +1,186 @@
+use crate::core::NoGradGuard;
 use crate::tensor::*;
+use crate::util::OrderedDict;
+use std::rc::Rc;
 
-pub trait Module: std::fmt::Debug {
+// Keys which didn't match when loading a state_dict.
+#[derive(Debug, Default, PartialEq)]
+pub struct IncompatibleKeys {
+    pub missing_keys: Vec<String>,
+    pub unexpected_keys: Vec<String>,
+}
+
+// Lets default methods of Module pass `self` on as a `&dyn Module`, also when they
+// are called on a `dyn Module`.
+pub trait AsModule {
+    fn as_module(&self) -> &dyn Module;
+}
+
+impl<T: Module> AsModule for T {
+    fn as_module(&self) -> &dyn Module {
+        self
+    }
+}
+
+pub trait Module: AsModule + std::fmt::Debug {
     fn forward(&self, xs: &[&Tensor]) -> Tensor;
-    fn parameters(&self) -> Option<Vec<Tensor>>;
+
+    fn is_training(&self) -> bool;
+
+    // Only changes this module, use `train` to also change the submodules.
+    fn set_training(&self, on: bool);
+
+    // Parameters registered directly on this module.
+    fn local_parameters(&self) -> OrderedDict<String, Tensor> {
+        OrderedDict::new_with_key_description("Parameter".to_string())
+    }
+
+    // Buffers registered directly on this module.
+    fn local_buffers(&self) -> OrderedDict<String, Tensor> {
+        OrderedDict::new_with_key_description("Buffer".to_string())
+    }
+
+    // Direct submodules of this module.
+    fn named_children(&self) -> OrderedDict<String, Rc<dyn Module>> {
+        OrderedDict::new_with_key_description("Submodule".to_string())
+    }
+
+    fn children(&self) -> Vec<Rc<dyn Module>> {
+        self.named_children().values().cloned().collect()
+    }
+
+    // All submodules, depth first, keyed by their dotted path. The module itself is not
+    // included.
+    fn named_modules(&self) -> OrderedDict<String, Rc<dyn Module>> {
+        let mut result = OrderedDict::new_with_key_description("Submodule".to_string());
+        for (name, child) in self.named_children().iter() {
+            result.insert(name.clone(), child.clone());
+            for (child_name, module) in child.named_modules().iter() {
+                result.insert(format!("{}.{}", name, child_name), module.clone());
+            }
+        }
+        result
+    }
+
+    fn modules(&self) -> Vec<Rc<dyn Module>> {
+        self.named_modules().values().cloned().collect()
+    }
+
+    // Parameters of this module and all of its submodules, keyed by their dotted path,
+    // e.g. `0.ws` for the weight of the first layer of a Sequential.
+    fn named_parameters(&self) -> OrderedDict<String, Tensor> {
+        let mut result = self.local_parameters();
+        for (name, child) in self.named_children().iter() {
+            for (param_name, param) in child.named_parameters().iter() {
+                result.insert(format!("{}.{}", name, param_name), param.clone());
+            }
+        }
+        result
+    }
+
+    fn parameters(&self) -> Option<Vec<Tensor>> {
+        Some(self.named_parameters().values().cloned().collect())
+    }
+
+    fn named_buffers(&self) -> OrderedDict<String, Tensor> {
+        let mut result = self.local_buffers();
+        for (name, child) in self.named_children().iter() {
+            for (buffer_name, buffer) in child.named_buffers().iter() {
+                result.insert(format!("{}.{}", name, buffer_name), buffer.clone());
+            }
+        }
+        result
+    }
+
+    fn buffers(&self) -> Vec<Tensor> {
+        self.named_buffers().values().cloned().collect()
+    }
+
+    // Parameters followed by buffers, detached from the graph but sharing their storage.
+    fn state_dict(&self) -> OrderedDict<String, Tensor> {
+        let mut result = OrderedDict::new_with_key_description("Tensor".to_string());
+        for (name, tensor) in self
+            .named_parameters()
+            .iter()
+            .chain(self.named_buffers().iter())
+        {
+            result.insert(name.clone(), tensor.tensor_data());
+        }
+        result
+    }
+
+    // Copies the values of `state_dict` into the parameters and buffers with the same
+    // name. With `strict`, missing or unexpected keys are an error.
+    fn load_state_dict(
+        &self,
+        state_dict: &OrderedDict<String, Tensor>,
+        strict: bool,
+    ) -> IncompatibleKeys {
+        let own_state = self.state_dict();
+        let mut result = IncompatibleKeys::default();
+        let _guard = NoGradGuard::default();
+        for (name, tensor) in own_state.iter() {
+            match state_dict.find(name) {
+                Some(value) => {
+                    assert!(
+                        value.sizes() == tensor.sizes(),
+                        "size mismatch for {}: copying a param with shape {:?} from checkpoint, the shape in current model is {:?}.",
+                        name,
+                        value.sizes(),
+                        tensor.sizes()
+                    );
+                    tensor.copy(value, None);
+                }
+                None => result.missing_keys.push(name.clone()),
+            }
+        }
+        result.unexpected_keys = state_dict
+            .keys()
+            .filter(|key| !own_state.contains(key))
+            .cloned()
+            .collect();
+        assert!(
+            !strict || (result.missing_keys.is_empty() && result.unexpected_keys.is_empty()),
+            "Error(s) in loading state_dict: Missing key(s): {:?}. Unexpected key(s): {:?}.",
+            result.missing_keys,
+            result.unexpected_keys
+        );
+        result
+    }
+
+    fn train(&self, on: bool) {
+        self.set_training(on);
+        for child in self.children() {
+            child.train(on);
+        }
+    }
+
+    fn eval(&self) {
+        self.train(false)
+    }
+
+    fn zero_grad(&self) {
+        for p in self.parameters().unwrap() {
+            if let Some(grad) = p.grad().as_mut() {
+                grad.detach_();
+                grad.zero_();
+            }
+        }
+    }
+
+    fn requires_grad_(&self, requires_grad: bool) {
+        for p in self.parameters().unwrap() {
+            p.set_requires_grad(requires_grad);
+        }
+    }
+
+    // Calls `f` on this module and then on every submodule, depth first.
+    fn apply(&self, f: &mut dyn FnMut(&dyn Module)) {
+        f(self.as_module());
+        for child in self.children() {
+            child.apply(f);
+        }
+    }
 }
 
 pub trait ModuleT: std::fmt::Debug {
@@ -0,0 +1,88 @@
+use crate::autograd;
+use crate::nn::{functional as F, module};
+use crate::tensor::{select, Tensor};
+use crate::util::OrderedDict;
+use std::cell::Cell;
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmbeddingConfig {
+    pub num_embeddings: usize,
+    pub embedding_dim: usize,
+    pub padding_idx: Option<usize>,
+    pub sparse: bool,
+}
+
+#[derive(Debug)]
+pub struct Embedding {
+    pub weight: Option<Tensor>,
+    options: EmbeddingConfig,
+    training: Cell<bool>,
+}
+
+impl Embedding {
+    pub fn new(num_embeddings: usize, embedding_dim: usize) -> Self {
+        Self::new_with_config(EmbeddingConfig {
+            num_embeddings,
+            embedding_dim,
+            ..EmbeddingConfig::default()
+        })
+    }
+
+    pub fn new_with_config(options: EmbeddingConfig) -> Self {
+        if let Some(padding_idx) = options.padding_idx {
+            assert!(
+                padding_idx < options.num_embeddings,
+                "padding_idx must be within num_embeddings"
+            );
+        }
+        let mut self_ = Self {
+            weight: None,
+            options,
+            training: Cell::new(true),
+        };
+        self_.reset();
+        self_
+    }
+
+    fn reset(&mut self) {
+        let weight = autograd::empty(
+            &[self.options.num_embeddings, self.options.embedding_dim],
+            None,
+            None,
+        );
+        module::register_parameter(&weight, true);
+        self.weight = Some(weight);
+        self.reset_parameters();
+    }
+    fn reset_parameters(&mut self) {
+        let weight = self.weight.as_ref().unwrap();
+        weight.normal(0.0, 1.0);
+        if let Some(padding_idx) = self.options.padding_idx {
+            select(&weight.tensor_data(), 0, padding_idx as i64).fill_(0.0);
+        }
+    }
+}
+
+impl module::Module for Embedding {
+    fn forward(&self, xs: &[&Tensor]) -> Tensor {
+        F::embedding(
+            xs[0],
+            self.weight.as_ref().unwrap(),
+            self.options.padding_idx,
+            self.options.sparse,
+        )
+    }
+
+    fn is_training(&self) -> bool {
+        self.training.get()
+    }
+
+    fn set_training(&self, on: bool) {
+        self.training.set(on)
+    }
+
+    fn local_parameters(&self) -> OrderedDict<String, Tensor> {
+        let mut result = OrderedDict::new_with_key_description("Parameter".to_string());
+        result.insert("weight".to_string(), self.weight.as_ref().unwrap().clone());
+        result
+    }
+}
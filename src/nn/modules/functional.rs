@@ -1,8 +1,10 @@
 use crate::nn::module::Module;
 use crate::tensor::{sigmoid, Tensor};
+use std::cell::Cell;
 
 pub struct Functional {
     fn_: Box<dyn Fn(&[&Tensor]) -> Tensor>,
+    training: Cell<bool>,
 }
 
 impl std::fmt::Debug for Functional {
@@ -13,7 +15,10 @@ impl std::fmt::Debug for Functional {
 
 impl Functional {
     pub fn new(func: Box<dyn Fn(&[&Tensor]) -> Tensor>) -> Self {
-        Self { fn_: func }
+        Self {
+            fn_: func,
+            training: Cell::new(true),
+        }
     }
 
     pub fn sigmoid() -> Box<dyn Fn(&[&Tensor]) -> Tensor> {
@@ -26,8 +31,12 @@ impl Module for Functional {
         (self.fn_)(xs)
     }
 
-    fn parameters(&self) -> Option<Vec<Tensor>> {
-        None
+    fn is_training(&self) -> bool {
+        self.training.get()
+    }
+
+    fn set_training(&self, on: bool) {
+        self.training.set(on)
     }
 }
 
@@ -1,6 +1,8 @@
 use crate::autograd;
 use crate::nn::{functional as F, module};
 use crate::tensor::Tensor;
+use crate::util::OrderedDict;
+use std::cell::Cell;
 #[derive(Debug, Clone, Copy)]
 pub struct LinearConfig {
     pub bias: bool,
@@ -22,6 +24,7 @@ pub struct Linear {
     pub ws: Option<Tensor>,
     pub bs: Option<Tensor>,
     options: LinearConfig,
+    training: Cell<bool>,
 }
 
 impl Linear {
@@ -35,6 +38,7 @@ impl Linear {
             ws: None,
             bs: None,
             options,
+            training: Cell::new(true),
         };
         self_.reset();
         self_
@@ -76,14 +80,20 @@ impl module::Module for Linear {
         F::linear(xs[0], self.ws.as_ref().unwrap(), self.bs.as_ref().unwrap())
     }
 
-    fn parameters(&self) -> Option<Vec<Tensor>> {
-        if self.bs.is_some() {
-            Some(vec![
-                self.ws.as_ref().unwrap().clone(),
-                self.bs.as_ref().unwrap().clone(),
-            ])
-        } else {
-            Some(vec![self.ws.as_ref().unwrap().clone()])
+    fn is_training(&self) -> bool {
+        self.training.get()
+    }
+
+    fn set_training(&self, on: bool) {
+        self.training.set(on)
+    }
+
+    fn local_parameters(&self) -> OrderedDict<String, Tensor> {
+        let mut result = OrderedDict::new_with_key_description("Parameter".to_string());
+        result.insert("ws".to_string(), self.ws.as_ref().unwrap().clone());
+        if let Some(bs) = self.bs.as_ref() {
+            result.insert("bs".to_string(), bs.clone());
         }
+        result
     }
 }
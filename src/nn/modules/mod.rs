@@ -1,8 +1,23 @@
 mod linear;
 pub use linear::*;
 
+mod embedding;
+pub use embedding::*;
+
 mod functional;
 pub use functional::*;
 
 mod sequential;
-pub use sequential::*;
\ No newline at end of file
+pub use sequential::*;
+
+mod module_list;
+pub use module_list::*;
+
+mod module_dict;
+pub use module_dict::*;
+
+mod parameter_list;
+pub use parameter_list::*;
+
+mod parameter_dict;
+pub use parameter_dict::*;
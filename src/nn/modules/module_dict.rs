@@ -0,0 +1,90 @@
+use crate::{nn::Module, tensor::Tensor, util::OrderedDict};
+use std::cell::Cell;
+use std::ops::Index;
+use std::rc::Rc;
+
+// Holds submodules by name, in insertion order. Like ModuleList it has no forward.
+#[derive(Debug)]
+pub struct ModuleDict {
+    modules: OrderedDict<String, Rc<dyn Module>>,
+    training: Cell<bool>,
+}
+
+impl ModuleDict {
+    pub fn new() -> Self {
+        Self {
+            modules: OrderedDict::new_with_key_description("Submodule".to_string()),
+            training: Cell::new(true),
+        }
+    }
+
+    pub fn insert<M>(&mut self, key: &str, module: M) -> &mut Self
+    where
+        M: Module + 'static,
+    {
+        assert!(!self.contains(key), "Submodule '{}' already defined", key);
+        self.modules.insert(key.to_string(), Rc::new(module));
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Rc<dyn Module>> {
+        self.modules.find(&key.to_string())
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.modules.contains(&key.to_string())
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.modules.keys()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Rc<dyn Module>)> {
+        self.modules.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.modules.size()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+}
+
+impl Default for ModuleDict {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Index<&str> for ModuleDict {
+    type Output = Rc<dyn Module>;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        self.get(key)
+            .unwrap_or_else(|| panic!("Submodule '{}' is not defined", key))
+    }
+}
+
+impl Module for ModuleDict {
+    fn forward(&self, _xs: &[&Tensor]) -> Tensor {
+        panic!("ModuleDict has no forward, call its modules instead")
+    }
+
+    fn is_training(&self) -> bool {
+        self.training.get()
+    }
+
+    fn set_training(&self, on: bool) {
+        self.training.set(on)
+    }
+
+    fn named_children(&self) -> OrderedDict<String, Rc<dyn Module>> {
+        let mut result = OrderedDict::new_with_key_description("Submodule".to_string());
+        for (key, module) in self.modules.iter() {
+            result.insert(key.clone(), module.clone());
+        }
+        result
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{nn::Module, tensor::Tensor, util::OrderedDict};
+use std::cell::Cell;
+use std::ops::Index;
+use std::rc::Rc;
+
+// Holds submodules in a list. Unlike Sequential it has no forward, the owner decides
+// how to call the modules.
+#[derive(Debug)]
+pub struct ModuleList {
+    modules: Vec<Rc<dyn Module>>,
+    training: Cell<bool>,
+}
+
+impl ModuleList {
+    pub fn new() -> Self {
+        Self {
+            modules: Vec::default(),
+            training: Cell::new(true),
+        }
+    }
+
+    pub fn push<M>(&mut self, module: M) -> &mut Self
+    where
+        M: Module + 'static,
+    {
+        self.modules.push(Rc::new(module));
+        self
+    }
+
+    // Inserts `module` before the module at `index`, shifting the later ones.
+    pub fn insert<M>(&mut self, index: usize, module: M) -> &mut Self
+    where
+        M: Module + 'static,
+    {
+        assert!(
+            index <= self.len(),
+            "Index {} is out of range for ModuleList of length {}",
+            index,
+            self.len()
+        );
+        self.modules.insert(index, Rc::new(module));
+        self
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Rc<dyn Module>> {
+        self.modules.get(index)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Rc<dyn Module>> {
+        self.modules.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+}
+
+impl Default for ModuleList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Index<usize> for ModuleList {
+    type Output = Rc<dyn Module>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.modules[index]
+    }
+}
+
+impl<'a> IntoIterator for &'a ModuleList {
+    type Item = &'a Rc<dyn Module>;
+    type IntoIter = std::slice::Iter<'a, Rc<dyn Module>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Module for ModuleList {
+    fn forward(&self, _xs: &[&Tensor]) -> Tensor {
+        panic!("ModuleList has no forward, call its modules instead")
+    }
+
+    fn is_training(&self) -> bool {
+        self.training.get()
+    }
+
+    fn set_training(&self, on: bool) {
+        self.training.set(on)
+    }
+
+    // Submodules are named after their position.
+    fn named_children(&self) -> OrderedDict<String, Rc<dyn Module>> {
+        let mut result = OrderedDict::new_with_key_description("Submodule".to_string());
+        for (i, module) in self.modules.iter().enumerate() {
+            result.insert(i.to_string(), module.clone());
+        }
+        result
+    }
+}
@@ -0,0 +1,90 @@
+use crate::nn::{module, Module};
+use crate::tensor::Tensor;
+use crate::util::OrderedDict;
+use std::cell::Cell;
+use std::ops::Index;
+
+// Holds parameters by name, in insertion order.
+#[derive(Debug)]
+pub struct ParameterDict {
+    parameters: OrderedDict<String, Tensor>,
+    training: Cell<bool>,
+}
+
+impl ParameterDict {
+    pub fn new() -> Self {
+        Self {
+            parameters: OrderedDict::new_with_key_description("Parameter".to_string()),
+            training: Cell::new(true),
+        }
+    }
+
+    // Registers `parameter` under `key`, making it require grad.
+    pub fn insert(&mut self, key: &str, parameter: Tensor) -> &mut Self {
+        assert!(!self.contains(key), "Parameter '{}' already defined", key);
+        module::register_parameter(&parameter, true);
+        self.parameters.insert(key.to_string(), parameter);
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Tensor> {
+        self.parameters.find(&key.to_string())
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.parameters.contains(&key.to_string())
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.parameters.keys()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Tensor)> {
+        self.parameters.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.parameters.size()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parameters.is_empty()
+    }
+}
+
+impl Default for ParameterDict {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Index<&str> for ParameterDict {
+    type Output = Tensor;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        self.get(key)
+            .unwrap_or_else(|| panic!("Parameter '{}' is not defined", key))
+    }
+}
+
+impl Module for ParameterDict {
+    fn forward(&self, _xs: &[&Tensor]) -> Tensor {
+        panic!("ParameterDict has no forward")
+    }
+
+    fn is_training(&self) -> bool {
+        self.training.get()
+    }
+
+    fn set_training(&self, on: bool) {
+        self.training.set(on)
+    }
+
+    fn local_parameters(&self) -> OrderedDict<String, Tensor> {
+        let mut result = OrderedDict::new_with_key_description("Parameter".to_string());
+        for (key, parameter) in self.parameters.iter() {
+            result.insert(key.clone(), parameter.clone());
+        }
+        result
+    }
+}
@@ -0,0 +1,89 @@
+use crate::nn::{module, Module};
+use crate::tensor::Tensor;
+use crate::util::OrderedDict;
+use std::cell::Cell;
+use std::ops::Index;
+
+// Holds parameters in a list, named after their position in the module tree.
+#[derive(Debug)]
+pub struct ParameterList {
+    parameters: Vec<Tensor>,
+    training: Cell<bool>,
+}
+
+impl ParameterList {
+    pub fn new() -> Self {
+        Self {
+            parameters: Vec::default(),
+            training: Cell::new(true),
+        }
+    }
+
+    // Registers `parameter`, making it require grad.
+    pub fn push(&mut self, parameter: Tensor) -> &mut Self {
+        module::register_parameter(&parameter, true);
+        self.parameters.push(parameter);
+        self
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Tensor> {
+        self.parameters.get(index)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Tensor> {
+        self.parameters.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.parameters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parameters.is_empty()
+    }
+}
+
+impl Default for ParameterList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Index<usize> for ParameterList {
+    type Output = Tensor;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.parameters[index]
+    }
+}
+
+impl<'a> IntoIterator for &'a ParameterList {
+    type Item = &'a Tensor;
+    type IntoIter = std::slice::Iter<'a, Tensor>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Module for ParameterList {
+    fn forward(&self, _xs: &[&Tensor]) -> Tensor {
+        panic!("ParameterList has no forward")
+    }
+
+    fn is_training(&self) -> bool {
+        self.training.get()
+    }
+
+    fn set_training(&self, on: bool) {
+        self.training.set(on)
+    }
+
+    fn local_parameters(&self) -> OrderedDict<String, Tensor> {
+        let mut result = OrderedDict::new_with_key_description("Parameter".to_string());
+        for (i, parameter) in self.parameters.iter().enumerate() {
+            result.insert(i.to_string(), parameter.clone());
+        }
+        result
+    }
+}
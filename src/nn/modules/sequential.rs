@@ -1,13 +1,18 @@
-use crate::{nn::Module, tensor::Tensor};
+use crate::{nn::Module, tensor::Tensor, util::OrderedDict};
+use std::cell::Cell;
+use std::rc::Rc;
 #[derive(Debug)]
 pub struct Sequential {
-    modules: Vec<Box<dyn Module>>,
+    // Modules added without a name are named after their position.
+    modules: Vec<(Option<String>, Rc<dyn Module>)>,
+    training: Cell<bool>,
 }
 
 impl Sequential {
     pub fn new() -> Self {
         Self {
             modules: Vec::default(),
+            training: Cell::new(true),
         }
     }
 
@@ -15,27 +20,87 @@ impl Sequential {
     where
         M: Module + 'static,
     {
-        self.modules.push(Box::new(module));
+        self.modules.push((None, Rc::new(module)));
         self
     }
+
+    pub fn add_named<M>(&mut self, name: &str, module: M) -> &mut Self
+    where
+        M: Module + 'static,
+    {
+        // Unnamed modules are named after their position, which changes on `insert`, so
+        // numeric names are reserved for them.
+        assert!(
+            name.parse::<usize>().is_err(),
+            "Submodule name '{}' is reserved for positional submodules",
+            name
+        );
+        assert!(
+            self.modules
+                .iter()
+                .all(|(other, _)| other.as_deref() != Some(name)),
+            "Submodule '{}' already defined",
+            name
+        );
+        self.modules.push((Some(name.to_string()), Rc::new(module)));
+        self
+    }
+
+    // Inserts `module` before the module at `index`, shifting the later ones.
+    pub fn insert<M>(&mut self, index: usize, module: M) -> &mut Self
+    where
+        M: Module + 'static,
+    {
+        assert!(
+            index <= self.len(),
+            "Index {} is out of range for Sequential of length {}",
+            index,
+            self.len()
+        );
+        self.modules.insert(index, (None, Rc::new(module)));
+        self
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Rc<dyn Module>> {
+        self.modules.get(index).map(|(_, module)| module)
+    }
+
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
 }
 
 impl Module for Sequential {
     fn forward(&self, input: &[&Tensor]) -> Tensor {
-        let mut output = self.modules.first().unwrap().forward(input);
-        for m in self.modules[1..].iter() {
+        let mut modules = self.modules.iter().map(|(_, module)| module);
+        let mut output = modules
+            .next()
+            .expect("Cannot call forward on an empty Sequential")
+            .forward(input);
+        for m in modules {
             output = m.forward(&[&output]);
         }
         output
     }
 
-    fn parameters(&self) -> Option<Vec<Tensor>> {
-        let mut result = vec![];
-        for module in self.modules.iter() {
-            if let Some(mut param_vec) = module.parameters() {
-                param_vec.drain(..).for_each(|p| result.push(p))
-            }
+    fn is_training(&self) -> bool {
+        self.training.get()
+    }
+
+    fn set_training(&self, on: bool) {
+        self.training.set(on)
+    }
+
+    fn named_children(&self) -> OrderedDict<String, Rc<dyn Module>> {
+        let mut result = OrderedDict::new_with_key_description("Submodule".to_string());
+        for (i, (name, module)) in self.modules.iter().enumerate() {
+            let name = name.clone().unwrap_or_else(|| i.to_string());
+            result.insert(name, module.clone());
         }
-        Some(result)
+        result
     }
 }
@@ -1,13 +1,21 @@
 use crate::{
-    aten::native::{self, log_softmax_backward_cpu, sigmoid_backward},
+    aten::{
+        self,
+        native::{self, log_softmax_backward_cpu, sigmoid_backward},
+    },
     c10::{Scalar, ScalarType},
     ops::NodeTrait,
     tensor::*,
     util::index_generator::IndexGenerator,
 };
-use crate::{autograd::*, util::BitSet};
+use crate::{
+    autograd::*,
+    core::{get_default_cpu_generator, AutoGradMode, Generator, GradMode},
+    util::BitSet,
+};
 use loss::Reduction;
 use smallvec::*;
+use std::rc::Rc;
 
 pub struct AccumulateGrad {
     tensor: Tensor,
@@ -60,7 +68,7 @@ impl NodeTrait for AccumulateGrad {
         self.input_metadata_.len()
     }
     fn num_outputs(&self) -> usize {
-        todo!()
+        0
     }
 
     fn input_metadata(&self, index: usize) -> &InputMetaData {
@@ -70,6 +78,10 @@ impl NodeTrait for AccumulateGrad {
     fn debug_print(&self) -> String {
         "Accumulate_Grad".to_string()
     }
+
+    fn variable(&self) -> Option<&Tensor> {
+        Some(&self.tensor)
+    }
 }
 
 #[derive(Debug)]
@@ -79,6 +91,7 @@ pub struct Add;
 pub struct InputMetaData {
     pub size: SmallVec<[usize; 5]>,
     pub device: usize,
+    pub dtype: ScalarType,
 }
 
 impl InputMetaData {
@@ -86,12 +99,17 @@ impl InputMetaData {
         InputMetaData {
             size: SmallVec::from_slice(t.sizes()),
             device: 0,
+            dtype: t.scalar_type(),
         }
     }
 
     pub fn shape(&self) -> &[usize] {
         self.size.as_slice()
     }
+
+    pub fn dtype(&self) -> ScalarType {
+        self.dtype
+    }
 }
 
 pub struct AddBackwardTensors {
@@ -102,10 +120,18 @@ pub struct AddBackwardTensors {
 impl NodeTrait for AddBackwardTensors {
     fn call(&mut self, mut grads: Vec<Tensor>) -> Vec<Tensor> {
         let _tmp: Vec<_> = grads.drain(1..).collect();
+        let mut gen = IndexGenerator::new();
+        let self_idx = gen.next();
+        let other_idx = gen.next();
+        let mut grad_inputs = Vec::with_capacity(gen.len());
         let grad = grads.get(0).unwrap().clone();
-        let grad_1 = grad.clone();
-        let grad_2 = grad.clone();
-        vec![grad_1, grad_2]
+        if self.should_compute_output(self_idx) {
+            grad_inputs.push(grad.clone())
+        }
+        if self.should_compute_output(other_idx) {
+            grad_inputs.push(grad)
+        }
+        grad_inputs
     }
 
     fn set_next_edges(&mut self, edges: Vec<Edge>) {
@@ -157,10 +183,17 @@ impl NodeTrait for MulBackwardTensors {
         let self_ = self._self.as_ref().unwrap().unpack();
         let other = self.other.as_ref().unwrap().unpack();
 
-        let first = &other * grad;
-        let second = &self_ * grad;
-
-        vec![first, second]
+        let mut gen = IndexGenerator::new();
+        let self_idx = gen.next();
+        let other_idx = gen.next();
+        let mut grad_inputs = Vec::with_capacity(gen.len());
+        if self.should_compute_output(self_idx) {
+            grad_inputs.push(grad * &other.conj());
+        }
+        if self.should_compute_output(other_idx) {
+            grad_inputs.push(grad * &self_.conj());
+        }
+        grad_inputs
     }
 
     fn set_next_edges(&mut self, edges: Vec<Edge>) {
@@ -259,7 +292,7 @@ pub struct MulBackwardScalar {
 impl NodeTrait for MulBackwardScalar {
     fn call(&mut self, grads: Vec<Tensor>) -> Vec<Tensor> {
         let grad = grads.first().unwrap();
-        let other = self.other;
+        let other = self.other.conj();
         let first = grad * other;
         vec![first]
     }
@@ -375,10 +408,17 @@ impl NodeTrait for DivBackwardTensors {
         let self_ = self._self.as_ref().unwrap().unpack();
         let other = self.other.as_ref().unwrap().unpack();
 
-        let first = grad / &other;
-        let second = -grad * &self_ / (&other * &other);
-
-        vec![first, second]
+        let mut gen = IndexGenerator::new();
+        let self_idx = gen.next();
+        let other_idx = gen.next();
+        let mut grad_inputs = Vec::with_capacity(gen.len());
+        if self.should_compute_output(self_idx) {
+            grad_inputs.push(grad / &other.conj());
+        }
+        if self.should_compute_output(other_idx) {
+            grad_inputs.push(-grad * &(&self_ / &(&other * &other)).conj());
+        }
+        grad_inputs
     }
 
     fn set_next_edges(&mut self, edges: Vec<Edge>) {
@@ -429,8 +469,8 @@ pub struct DivBackwardScalar {
 impl NodeTrait for DivBackwardScalar {
     fn call(&mut self, grads: Vec<Tensor>) -> Vec<Tensor> {
         let grad = grads.first().unwrap();
-        let other = self.other;
-        let first = grad * other;
+        let other = self.other.conj();
+        let first = grad / other;
         vec![first]
     }
 
@@ -610,10 +650,12 @@ fn mm_mat1_backward(
 ) -> Tensor {
     let sizes = mat1.sizes();
     let strides = mat1.strides();
+    let mat2 = mat2.conj();
+    let alpha = alpha.into().conj();
     if strides[0] == 1 && strides[1] == sizes[0] {
-        return maybe_mutliply(mat2.mm(grad.t(), true), alpha.into());
+        return maybe_mutliply(mat2.mm(grad.t(), true).t(), alpha);
     }
-    maybe_mutliply(grad.mm(mat2.t(), true), alpha.into())
+    maybe_mutliply(grad.mm(mat2.t(), true), alpha)
 }
 
 fn mm_mat2_backward(
@@ -623,10 +665,12 @@ fn mm_mat2_backward(
     strides: &[usize],
     alpha: impl Into<Scalar>,
 ) -> Tensor {
+    let mat1 = mat1.conj();
+    let alpha = alpha.into().conj();
     if strides[0] == 1 && strides[1] == sizes[0] {
-        return maybe_mutliply(grad.t().mm(mat1, false).t(), alpha.into());
+        return maybe_mutliply(grad.t().mm(&mat1, false).t(), alpha);
     }
-    maybe_mutliply(mat1.t().mm(grad, false), alpha.into())
+    maybe_mutliply(mat1.t().mm(grad, false), alpha)
 }
 
 impl NodeTrait for MmBackward {
@@ -634,13 +678,23 @@ impl NodeTrait for MmBackward {
         let grad = grads.first().unwrap();
         let mat1 = self.self_.as_ref().unwrap().unpack();
         let mat2 = self.mat2_.as_ref().unwrap().unpack();
-        //Todo: imlement and use mm_mat2_backward and mm_mat1_backward
-        let mat2_grad =
-            mm_mat2_backward(grad, &mat1, self.mat2_sizes.as_slice(), mat2.strides(), 1);
-        // mat1.t().mm(grad, false);
-        let mat1_grad = mm_mat1_backward(grad, &mat2, &mat1, 1);
-        // grad.mm(&mat2.t(), false);
-        vec![mat1_grad, mat2_grad]
+        let mut gen = IndexGenerator::new();
+        let mat1_idx = gen.next();
+        let mat2_idx = gen.next();
+        let mut grad_inputs = Vec::with_capacity(gen.len());
+        if self.should_compute_output(mat1_idx) {
+            grad_inputs.push(mm_mat1_backward(grad, &mat2, &mat1, 1));
+        }
+        if self.should_compute_output(mat2_idx) {
+            grad_inputs.push(mm_mat2_backward(
+                grad,
+                &mat1,
+                self.mat2_sizes.as_slice(),
+                mat2.strides(),
+                1,
+            ));
+        }
+        grad_inputs
     }
 
     fn set_next_edges(&mut self, edges: Vec<Edge>) {
@@ -703,7 +757,7 @@ impl NodeTrait for AddmmBackward {
         let mat2_idx = gen.next();
         let mut grad_inputs = Vec::with_capacity(gen.len());
         if self.should_compute_output(self_idx) {
-            let self_grad = maybe_mutliply(grad.clone(), self.beta);
+            let self_grad = maybe_mutliply(grad.clone(), self.beta.conj());
             grad_inputs.push(self_grad);
         }
         if self.should_compute_output(mat1_idx) {
@@ -771,8 +825,13 @@ impl NodeTrait for SigmoidBackward {
     fn call(&mut self, input: Vec<Tensor>) -> Vec<Tensor> {
         let grad_input = input.first().unwrap();
         let result = self.result_.as_ref().unwrap().unpack();
-        let grad_result = sigmoid_backward(grad_input, &result);
-        // dbg!(&grad_result);
+        // With create_graph the kernel is replaced by differentiable ops so
+        // that higher order gradients can flow through this node.
+        let grad_result = if GradMode::is_enabled() {
+            grad_input * &(&result * &(&(-&result) + 1.0))
+        } else {
+            sigmoid_backward(grad_input, &result)
+        };
         vec![grad_result]
     }
 
@@ -860,7 +919,7 @@ impl NodeTrait for SumBackward0 {
     }
 
     fn debug_print(&self) -> String {
-        "SigmoidBackward".to_string()
+        "SumBackward0".to_string()
     }
 }
 const dim_bitset_size: usize = 64;
@@ -962,7 +1021,7 @@ impl NodeTrait for SumBackward1 {
     }
 
     fn debug_print(&self) -> String {
-        "SigmoidBackward".to_string()
+        "SumBackward1".to_string()
     }
 }
 
@@ -994,6 +1053,17 @@ impl NodeTrait for BinaryCrossEntropyBackward {
         let self_ = self.self_.as_ref().unwrap().unpack();
         let target_ = self.target_.as_ref().unwrap().unpack();
         let weight = self.weight_.as_ref().unwrap().unpack();
+        if GradMode::is_enabled() {
+            let denom = &(&(-&self_) + 1.0) * &self_;
+            let mut grad_result = grad_input * &(&(&self_ - &target_) / &denom);
+            if weight.defined() {
+                grad_result = &grad_result * &weight;
+            }
+            if self.reduction == Reduction::Mean {
+                grad_result = &grad_result / self_.numel();
+            }
+            return vec![grad_result];
+        }
         let grad_result = loss::binary_cross_entropy_backward(
             grad_input,
             &self_,
@@ -1053,8 +1123,7 @@ pub struct MeanBackward {
 impl NodeTrait for MeanBackward {
     fn call(&mut self, input: Vec<Tensor>) -> Vec<Tensor> {
         let grad_input = input.first().unwrap();
-        let mut result = grad_input.expand(self.self_sizes.as_slice(), false);
-        result.div_(&full(result.sizes(), self.self_numel as f32, None));
+        let result = grad_input.expand(self.self_sizes.as_slice(), false) / self.self_numel;
         vec![result]
     }
 
@@ -1120,7 +1189,12 @@ impl NodeTrait for LogSoftmaxBackward {
     fn call(&mut self, input: Vec<Tensor>) -> Vec<Tensor> {
         let grad_input = input.first().unwrap();
         let result = self.result.as_ref().unwrap().unpack();
-        let grad_result = log_softmax_backward_cpu(grad_input, &result, self.dim);
+        let grad_result = if GradMode::is_enabled() {
+            let dim = maybe_wrap_dim(self.dim, result.dim(), true);
+            grad_input - &(&result.exp() * &grad_input.sum_dim(&[dim], true))
+        } else {
+            log_softmax_backward_cpu(grad_input, &result, self.dim)
+        };
         vec![grad_result]
     }
 
@@ -1193,6 +1267,25 @@ impl NodeTrait for NllLossBackward {
         let weight = self.weight.as_ref().unwrap().unpack();
         let target = self.target.as_ref().unwrap().unpack();
         let total_weight = self.total_weight.as_ref().unwrap().unpack();
+        if GradMode::is_enabled() {
+            // nll_loss_backward is linear in grad, so scale the constant
+            // coefficients computed for a unit grad.
+            let coefficients = native::nll_loss_backward_cpu(
+                &native::ones_like(grad_input, grad_input.options(), None),
+                &self_,
+                &target,
+                &weight,
+                self.reduction,
+                self.ignore_index,
+                &total_weight,
+            );
+            let grad = if grad_input.dim() > 0 {
+                grad_input.unsqueeze(1)
+            } else {
+                grad_input.clone()
+            };
+            return vec![&coefficients * &grad];
+        }
         let grad_result = native::nll_loss_backward_cpu(
             grad_input,
             &self_,
@@ -1242,3 +1335,850 @@ impl NodeTrait for NllLossBackward {
         "NllLossBackward".to_string()
     }
 }
+
+pub struct ExpBackward {
+    pub input_metadata_: SmallVec<[InputMetaData; 2]>,
+    pub next_edges: Option<EdgeList>,
+    pub result_: Option<SavedTensor>,
+}
+
+impl NodeTrait for ExpBackward {
+    fn call(&mut self, input: Vec<Tensor>) -> Vec<Tensor> {
+        let grad_input = input.first().unwrap();
+        let result = self.result_.as_ref().unwrap().unpack();
+        vec![grad_input * &result]
+    }
+
+    fn set_next_edges(&mut self, edges: Vec<Edge>) {
+        self.next_edges = Some(edges)
+    }
+
+    fn add_input_metadata(&mut self, tensor: &Tensor) -> usize {
+        let input_nr = self.input_metadata_.len();
+        self.input_metadata_
+            .push(InputMetaData::from_tensor(tensor));
+        input_nr
+    }
+
+    fn next_edges(&self) -> Option<&EdgeList> {
+        self.next_edges.as_ref()
+    }
+
+    fn next_edge(&self, i: usize) -> Option<Edge> {
+        let edges = self.next_edges.as_ref().unwrap();
+        let e = edges.get(i).cloned();
+        e
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.input_metadata_.len()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.next_edges.as_ref().unwrap().len()
+    }
+
+    fn input_metadata(&self, index: usize) -> &InputMetaData {
+        self.input_metadata_.get(index).unwrap()
+    }
+
+    fn debug_print(&self) -> String {
+        "ExpBackward".to_string()
+    }
+}
+
+pub struct ExpandBackward {
+    pub input_metadata_: SmallVec<[InputMetaData; 2]>,
+    pub next_edges: Option<EdgeList>,
+    pub self_sizes: Vec<usize>,
+}
+
+impl NodeTrait for ExpandBackward {
+    fn call(&mut self, input: Vec<Tensor>) -> Vec<Tensor> {
+        let grad_input = input.first().unwrap();
+        vec![aten::sum_to(grad_input.clone(), self.self_sizes.as_slice())]
+    }
+
+    fn set_next_edges(&mut self, edges: Vec<Edge>) {
+        self.next_edges = Some(edges)
+    }
+
+    fn add_input_metadata(&mut self, tensor: &Tensor) -> usize {
+        let input_nr = self.input_metadata_.len();
+        self.input_metadata_
+            .push(InputMetaData::from_tensor(tensor));
+        input_nr
+    }
+
+    fn next_edges(&self) -> Option<&EdgeList> {
+        self.next_edges.as_ref()
+    }
+
+    fn next_edge(&self, i: usize) -> Option<Edge> {
+        let edges = self.next_edges.as_ref().unwrap();
+        let e = edges.get(i).cloned();
+        e
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.input_metadata_.len()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.next_edges.as_ref().unwrap().len()
+    }
+
+    fn input_metadata(&self, index: usize) -> &InputMetaData {
+        self.input_metadata_.get(index).unwrap()
+    }
+
+    fn debug_print(&self) -> String {
+        "ExpandBackward".to_string()
+    }
+}
+
+pub struct ViewBackward {
+    pub input_metadata_: SmallVec<[InputMetaData; 2]>,
+    pub next_edges: Option<EdgeList>,
+    pub self_sizes: Vec<usize>,
+}
+
+impl NodeTrait for ViewBackward {
+    fn call(&mut self, input: Vec<Tensor>) -> Vec<Tensor> {
+        let grad_input = input.first().unwrap();
+        let sizes = self
+            .self_sizes
+            .iter()
+            .map(|s| *s as isize)
+            .collect::<Vec<_>>();
+        vec![grad_input.reshape(sizes.as_slice())]
+    }
+
+    fn set_next_edges(&mut self, edges: Vec<Edge>) {
+        self.next_edges = Some(edges)
+    }
+
+    fn add_input_metadata(&mut self, tensor: &Tensor) -> usize {
+        let input_nr = self.input_metadata_.len();
+        self.input_metadata_
+            .push(InputMetaData::from_tensor(tensor));
+        input_nr
+    }
+
+    fn next_edges(&self) -> Option<&EdgeList> {
+        self.next_edges.as_ref()
+    }
+
+    fn next_edge(&self, i: usize) -> Option<Edge> {
+        let edges = self.next_edges.as_ref().unwrap();
+        let e = edges.get(i).cloned();
+        e
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.input_metadata_.len()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.next_edges.as_ref().unwrap().len()
+    }
+
+    fn input_metadata(&self, index: usize) -> &InputMetaData {
+        self.input_metadata_.get(index).unwrap()
+    }
+
+    fn debug_print(&self) -> String {
+        "ViewBackward".to_string()
+    }
+}
+
+pub struct StackBackward {
+    pub input_metadata_: SmallVec<[InputMetaData; 2]>,
+    pub next_edges: Option<EdgeList>,
+    pub dim: usize,
+}
+
+impl NodeTrait for StackBackward {
+    fn call(&mut self, input: Vec<Tensor>) -> Vec<Tensor> {
+        let grad_input = input.first().unwrap();
+        (0..self.num_outputs())
+            .filter(|i| self.should_compute_output(*i))
+            .map(|i| select(grad_input, self.dim as i64, i as i64))
+            .collect()
+    }
+
+    fn set_next_edges(&mut self, edges: Vec<Edge>) {
+        self.next_edges = Some(edges)
+    }
+
+    fn add_input_metadata(&mut self, tensor: &Tensor) -> usize {
+        let input_nr = self.input_metadata_.len();
+        self.input_metadata_
+            .push(InputMetaData::from_tensor(tensor));
+        input_nr
+    }
+
+    fn next_edges(&self) -> Option<&EdgeList> {
+        self.next_edges.as_ref()
+    }
+
+    fn next_edge(&self, i: usize) -> Option<Edge> {
+        let edges = self.next_edges.as_ref().unwrap();
+        let e = edges.get(i).cloned();
+        e
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.input_metadata_.len()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.next_edges.as_ref().unwrap().len()
+    }
+
+    fn input_metadata(&self, index: usize) -> &InputMetaData {
+        self.input_metadata_.get(index).unwrap()
+    }
+
+    fn debug_print(&self) -> String {
+        "StackBackward".to_string()
+    }
+}
+
+pub type CheckpointFunction = Rc<dyn Fn(&[Tensor]) -> Tensor>;
+
+pub struct CheckpointBackward {
+    pub input_metadata_: SmallVec<[InputMetaData; 2]>,
+    pub next_edges: Option<EdgeList>,
+    pub function: CheckpointFunction,
+    // Inputs and parameters of the function, followed by the RNG state of the forward
+    // pass, enough to recompute it. The edges of the node are in the same order.
+    pub inputs: VariableList,
+    pub parameters: VariableList,
+    pub rng_state: Generator,
+}
+
+impl NodeTrait for CheckpointBackward {
+    fn call(&mut self, grads: Vec<Tensor>) -> Vec<Tensor> {
+        let grad_output = grads.first().unwrap();
+
+        // Rebuild the graph with the forward RNG state so random ops such as dropout
+        // produce the same values, then restore the state backward started with.
+        let generator = get_default_cpu_generator();
+        let current_state = generator.get_state();
+        generator.set_state(&self.rng_state);
+        let output = {
+            let _mode = AutoGradMode::new(true);
+            (self.function)(&self.inputs)
+        };
+        generator.set_state(&current_state);
+
+        // Only the gradients the engine asked for are computed, and none of them is
+        // accumulated into a .grad. The engine enables grad mode for create_graph.
+        let targets: VariableList = self
+            .inputs
+            .iter()
+            .chain(self.parameters.iter())
+            .enumerate()
+            .filter(|(i, _)| self.should_compute_output(*i))
+            .map(|(_, t)| t.clone())
+            .collect();
+        let result = if output.requires_grad() {
+            grad(
+                &vec![output],
+                &targets,
+                &vec![grad_output.clone()],
+                GradMode::is_enabled(),
+            )
+        } else {
+            vec![Tensor::default(); targets.len()]
+        };
+        targets
+            .iter()
+            .zip(result)
+            .map(|(target, result)| {
+                if result.defined() {
+                    result
+                } else {
+                    native::full_like(target, 0.0, target.options(), None)
+                }
+            })
+            .collect()
+    }
+
+    fn set_next_edges(&mut self, edges: Vec<Edge>) {
+        self.next_edges = Some(edges)
+    }
+
+    fn add_input_metadata(&mut self, tensor: &Tensor) -> usize {
+        let input_nr = self.input_metadata_.len();
+        self.input_metadata_
+            .push(InputMetaData::from_tensor(tensor));
+        input_nr
+    }
+
+    fn next_edges(&self) -> Option<&EdgeList> {
+        self.next_edges.as_ref()
+    }
+
+    fn next_edge(&self, i: usize) -> Option<Edge> {
+        let edges = self.next_edges.as_ref().unwrap();
+        let e = edges.get(i).cloned();
+        e
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.input_metadata_.len()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.next_edges.as_ref().unwrap().len()
+    }
+
+    fn input_metadata(&self, index: usize) -> &InputMetaData {
+        self.input_metadata_.get(index).unwrap()
+    }
+
+    fn debug_print(&self) -> String {
+        "CheckpointBackward".to_string()
+    }
+}
+
+// Gradients of complex tensors follow PyTorch's convention: for a real loss L of
+// z = x + iy, the gradient is dL/dx + i dL/dy, the conjugate Wirtinger derivative
+// scaled by 2. Real inputs of complex ops get the real part of it.
+fn imaginary_unit() -> Scalar {
+    Scalar::complex(num::complex::Complex::new(0.0, 1.0))
+}
+
+pub struct RealBackward {
+    pub input_metadata_: SmallVec<[InputMetaData; 2]>,
+    pub next_edges: Option<EdgeList>,
+    pub self_scalar_type: ScalarType,
+}
+
+impl NodeTrait for RealBackward {
+    fn call(&mut self, grads: Vec<Tensor>) -> Vec<Tensor> {
+        let grad = grads.first().unwrap();
+        vec![grad.to_dtype(self.self_scalar_type)]
+    }
+
+    fn set_next_edges(&mut self, edges: Vec<Edge>) {
+        self.next_edges = Some(edges)
+    }
+
+    fn add_input_metadata(&mut self, tensor: &Tensor) -> usize {
+        let input_nr = self.input_metadata_.len();
+        self.input_metadata_
+            .push(InputMetaData::from_tensor(tensor));
+        input_nr
+    }
+
+    fn next_edges(&self) -> Option<&EdgeList> {
+        self.next_edges.as_ref()
+    }
+
+    fn next_edge(&self, i: usize) -> Option<Edge> {
+        let edges = self.next_edges.as_ref().unwrap();
+        let e = edges.get(i).cloned();
+        e
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.input_metadata_.len()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.next_edges.as_ref().unwrap().len()
+    }
+
+    fn input_metadata(&self, index: usize) -> &InputMetaData {
+        self.input_metadata_.get(index).unwrap()
+    }
+
+    fn debug_print(&self) -> String {
+        "RealBackward".to_string()
+    }
+}
+
+pub struct ImagBackward {
+    pub input_metadata_: SmallVec<[InputMetaData; 2]>,
+    pub next_edges: Option<EdgeList>,
+    pub self_scalar_type: ScalarType,
+}
+
+impl NodeTrait for ImagBackward {
+    fn call(&mut self, grads: Vec<Tensor>) -> Vec<Tensor> {
+        let grad = grads.first().unwrap();
+        vec![grad.to_dtype(self.self_scalar_type) * imaginary_unit()]
+    }
+
+    fn set_next_edges(&mut self, edges: Vec<Edge>) {
+        self.next_edges = Some(edges)
+    }
+
+    fn add_input_metadata(&mut self, tensor: &Tensor) -> usize {
+        let input_nr = self.input_metadata_.len();
+        self.input_metadata_
+            .push(InputMetaData::from_tensor(tensor));
+        input_nr
+    }
+
+    fn next_edges(&self) -> Option<&EdgeList> {
+        self.next_edges.as_ref()
+    }
+
+    fn next_edge(&self, i: usize) -> Option<Edge> {
+        let edges = self.next_edges.as_ref().unwrap();
+        let e = edges.get(i).cloned();
+        e
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.input_metadata_.len()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.next_edges.as_ref().unwrap().len()
+    }
+
+    fn input_metadata(&self, index: usize) -> &InputMetaData {
+        self.input_metadata_.get(index).unwrap()
+    }
+
+    fn debug_print(&self) -> String {
+        "ImagBackward".to_string()
+    }
+}
+
+pub struct ConjBackward {
+    pub input_metadata_: SmallVec<[InputMetaData; 2]>,
+    pub next_edges: Option<EdgeList>,
+}
+
+impl NodeTrait for ConjBackward {
+    fn call(&mut self, grads: Vec<Tensor>) -> Vec<Tensor> {
+        let grad = grads.first().unwrap();
+        vec![grad.conj()]
+    }
+
+    fn set_next_edges(&mut self, edges: Vec<Edge>) {
+        self.next_edges = Some(edges)
+    }
+
+    fn add_input_metadata(&mut self, tensor: &Tensor) -> usize {
+        let input_nr = self.input_metadata_.len();
+        self.input_metadata_
+            .push(InputMetaData::from_tensor(tensor));
+        input_nr
+    }
+
+    fn next_edges(&self) -> Option<&EdgeList> {
+        self.next_edges.as_ref()
+    }
+
+    fn next_edge(&self, i: usize) -> Option<Edge> {
+        let edges = self.next_edges.as_ref().unwrap();
+        let e = edges.get(i).cloned();
+        e
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.input_metadata_.len()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.next_edges.as_ref().unwrap().len()
+    }
+
+    fn input_metadata(&self, index: usize) -> &InputMetaData {
+        self.input_metadata_.get(index).unwrap()
+    }
+
+    fn debug_print(&self) -> String {
+        "ConjBackward".to_string()
+    }
+}
+
+pub struct ViewAsRealBackward {
+    pub input_metadata_: SmallVec<[InputMetaData; 2]>,
+    pub next_edges: Option<EdgeList>,
+}
+
+impl NodeTrait for ViewAsRealBackward {
+    fn call(&mut self, grads: Vec<Tensor>) -> Vec<Tensor> {
+        let grad = grads.first().unwrap();
+        vec![native::view_as_complex(&grad.contiguous())]
+    }
+
+    fn set_next_edges(&mut self, edges: Vec<Edge>) {
+        self.next_edges = Some(edges)
+    }
+
+    fn add_input_metadata(&mut self, tensor: &Tensor) -> usize {
+        let input_nr = self.input_metadata_.len();
+        self.input_metadata_
+            .push(InputMetaData::from_tensor(tensor));
+        input_nr
+    }
+
+    fn next_edges(&self) -> Option<&EdgeList> {
+        self.next_edges.as_ref()
+    }
+
+    fn next_edge(&self, i: usize) -> Option<Edge> {
+        let edges = self.next_edges.as_ref().unwrap();
+        let e = edges.get(i).cloned();
+        e
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.input_metadata_.len()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.next_edges.as_ref().unwrap().len()
+    }
+
+    fn input_metadata(&self, index: usize) -> &InputMetaData {
+        self.input_metadata_.get(index).unwrap()
+    }
+
+    fn debug_print(&self) -> String {
+        "ViewAsRealBackward".to_string()
+    }
+}
+
+pub struct ViewAsComplexBackward {
+    pub input_metadata_: SmallVec<[InputMetaData; 2]>,
+    pub next_edges: Option<EdgeList>,
+}
+
+impl NodeTrait for ViewAsComplexBackward {
+    fn call(&mut self, grads: Vec<Tensor>) -> Vec<Tensor> {
+        let grad = grads.first().unwrap();
+        vec![native::view_as_real(grad)]
+    }
+
+    fn set_next_edges(&mut self, edges: Vec<Edge>) {
+        self.next_edges = Some(edges)
+    }
+
+    fn add_input_metadata(&mut self, tensor: &Tensor) -> usize {
+        let input_nr = self.input_metadata_.len();
+        self.input_metadata_
+            .push(InputMetaData::from_tensor(tensor));
+        input_nr
+    }
+
+    fn next_edges(&self) -> Option<&EdgeList> {
+        self.next_edges.as_ref()
+    }
+
+    fn next_edge(&self, i: usize) -> Option<Edge> {
+        let edges = self.next_edges.as_ref().unwrap();
+        let e = edges.get(i).cloned();
+        e
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.input_metadata_.len()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.next_edges.as_ref().unwrap().len()
+    }
+
+    fn input_metadata(&self, index: usize) -> &InputMetaData {
+        self.input_metadata_.get(index).unwrap()
+    }
+
+    fn debug_print(&self) -> String {
+        "ViewAsComplexBackward".to_string()
+    }
+}
+
+pub struct AbsBackward {
+    pub input_metadata_: SmallVec<[InputMetaData; 2]>,
+    pub next_edges: Option<EdgeList>,
+    pub self_: Option<SavedTensor>,
+}
+
+impl NodeTrait for AbsBackward {
+    fn call(&mut self, grads: Vec<Tensor>) -> Vec<Tensor> {
+        let grad = grads.first().unwrap();
+        let self_ = self.self_.as_ref().unwrap().unpack();
+        vec![grad * &self_.sgn()]
+    }
+
+    fn set_next_edges(&mut self, edges: Vec<Edge>) {
+        self.next_edges = Some(edges)
+    }
+
+    fn add_input_metadata(&mut self, tensor: &Tensor) -> usize {
+        let input_nr = self.input_metadata_.len();
+        self.input_metadata_
+            .push(InputMetaData::from_tensor(tensor));
+        input_nr
+    }
+
+    fn next_edges(&self) -> Option<&EdgeList> {
+        self.next_edges.as_ref()
+    }
+
+    fn next_edge(&self, i: usize) -> Option<Edge> {
+        let edges = self.next_edges.as_ref().unwrap();
+        let e = edges.get(i).cloned();
+        e
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.input_metadata_.len()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.next_edges.as_ref().unwrap().len()
+    }
+
+    fn input_metadata(&self, index: usize) -> &InputMetaData {
+        self.input_metadata_.get(index).unwrap()
+    }
+
+    fn debug_print(&self) -> String {
+        "AbsBackward".to_string()
+    }
+}
+
+pub struct AngleBackward {
+    pub input_metadata_: SmallVec<[InputMetaData; 2]>,
+    pub next_edges: Option<EdgeList>,
+    pub self_: Option<SavedTensor>,
+}
+
+impl NodeTrait for AngleBackward {
+    fn call(&mut self, grads: Vec<Tensor>) -> Vec<Tensor> {
+        let grad = grads.first().unwrap();
+        let self_ = self.self_.as_ref().unwrap().unpack();
+        vec![native::angle_backward(grad, &self_)]
+    }
+
+    fn set_next_edges(&mut self, edges: Vec<Edge>) {
+        self.next_edges = Some(edges)
+    }
+
+    fn add_input_metadata(&mut self, tensor: &Tensor) -> usize {
+        let input_nr = self.input_metadata_.len();
+        self.input_metadata_
+            .push(InputMetaData::from_tensor(tensor));
+        input_nr
+    }
+
+    fn next_edges(&self) -> Option<&EdgeList> {
+        self.next_edges.as_ref()
+    }
+
+    fn next_edge(&self, i: usize) -> Option<Edge> {
+        let edges = self.next_edges.as_ref().unwrap();
+        let e = edges.get(i).cloned();
+        e
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.input_metadata_.len()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.next_edges.as_ref().unwrap().len()
+    }
+
+    fn input_metadata(&self, index: usize) -> &InputMetaData {
+        self.input_metadata_.get(index).unwrap()
+    }
+
+    fn debug_print(&self) -> String {
+        "AngleBackward".to_string()
+    }
+}
+
+pub struct PolarBackward {
+    pub input_metadata_: SmallVec<[InputMetaData; 2]>,
+    pub next_edges: Option<EdgeList>,
+    pub result_: Option<SavedTensor>,
+}
+
+impl NodeTrait for PolarBackward {
+    fn call(&mut self, grads: Vec<Tensor>) -> Vec<Tensor> {
+        let grad = grads.first().unwrap();
+        let result = self.result_.as_ref().unwrap().unpack();
+        let grad_conj = grad.conj();
+
+        let mut gen = IndexGenerator::new();
+        let abs_idx = gen.next();
+        let angle_idx = gen.next();
+        let mut grad_inputs = Vec::with_capacity(gen.len());
+        if self.should_compute_output(abs_idx) {
+            grad_inputs.push((&grad_conj * &result.sgn()).real().contiguous());
+        }
+        if self.should_compute_output(angle_idx) {
+            let result_mul_i = &result * imaginary_unit();
+            grad_inputs.push((&grad_conj * &result_mul_i).real().contiguous());
+        }
+        grad_inputs
+    }
+
+    fn set_next_edges(&mut self, edges: Vec<Edge>) {
+        self.next_edges = Some(edges)
+    }
+
+    fn add_input_metadata(&mut self, tensor: &Tensor) -> usize {
+        let input_nr = self.input_metadata_.len();
+        self.input_metadata_
+            .push(InputMetaData::from_tensor(tensor));
+        input_nr
+    }
+
+    fn next_edges(&self) -> Option<&EdgeList> {
+        self.next_edges.as_ref()
+    }
+
+    fn next_edge(&self, i: usize) -> Option<Edge> {
+        let edges = self.next_edges.as_ref().unwrap();
+        let e = edges.get(i).cloned();
+        e
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.input_metadata_.len()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.next_edges.as_ref().unwrap().len()
+    }
+
+    fn input_metadata(&self, index: usize) -> &InputMetaData {
+        self.input_metadata_.get(index).unwrap()
+    }
+
+    fn debug_print(&self) -> String {
+        "PolarBackward".to_string()
+    }
+}
+
+pub struct ComplexBackward {
+    pub input_metadata_: SmallVec<[InputMetaData; 2]>,
+    pub next_edges: Option<EdgeList>,
+}
+
+impl NodeTrait for ComplexBackward {
+    fn call(&mut self, grads: Vec<Tensor>) -> Vec<Tensor> {
+        let grad = grads.first().unwrap();
+        let mut gen = IndexGenerator::new();
+        let real_idx = gen.next();
+        let imag_idx = gen.next();
+        let mut grad_inputs = Vec::with_capacity(gen.len());
+        if self.should_compute_output(real_idx) {
+            grad_inputs.push(grad.real().contiguous());
+        }
+        if self.should_compute_output(imag_idx) {
+            grad_inputs.push(grad.imag().contiguous());
+        }
+        grad_inputs
+    }
+
+    fn set_next_edges(&mut self, edges: Vec<Edge>) {
+        self.next_edges = Some(edges)
+    }
+
+    fn add_input_metadata(&mut self, tensor: &Tensor) -> usize {
+        let input_nr = self.input_metadata_.len();
+        self.input_metadata_
+            .push(InputMetaData::from_tensor(tensor));
+        input_nr
+    }
+
+    fn next_edges(&self) -> Option<&EdgeList> {
+        self.next_edges.as_ref()
+    }
+
+    fn next_edge(&self, i: usize) -> Option<Edge> {
+        let edges = self.next_edges.as_ref().unwrap();
+        let e = edges.get(i).cloned();
+        e
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.input_metadata_.len()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.next_edges.as_ref().unwrap().len()
+    }
+
+    fn input_metadata(&self, index: usize) -> &InputMetaData {
+        self.input_metadata_.get(index).unwrap()
+    }
+
+    fn debug_print(&self) -> String {
+        "ComplexBackward".to_string()
+    }
+}
+
+pub struct EmbeddingBackward {
+    pub input_metadata_: SmallVec<[InputMetaData; 2]>,
+    pub next_edges: Option<EdgeList>,
+    pub indices_: Option<SavedTensor>,
+    pub num_weights: usize,
+    pub padding_idx: Option<usize>,
+    pub sparse: bool,
+}
+
+impl NodeTrait for EmbeddingBackward {
+    fn call(&mut self, grads: Vec<Tensor>) -> Vec<Tensor> {
+        let grad = grads.first().unwrap();
+        let indices = self.indices_.as_ref().unwrap().unpack();
+        vec![native::embedding_backward(
+            grad,
+            &indices,
+            self.num_weights,
+            self.padding_idx,
+            self.sparse,
+        )]
+    }
+
+    fn set_next_edges(&mut self, edges: Vec<Edge>) {
+        self.next_edges = Some(edges)
+    }
+
+    fn add_input_metadata(&mut self, tensor: &Tensor) -> usize {
+        let input_nr = self.input_metadata_.len();
+        self.input_metadata_
+            .push(InputMetaData::from_tensor(tensor));
+        input_nr
+    }
+
+    fn next_edges(&self) -> Option<&EdgeList> {
+        self.next_edges.as_ref()
+    }
+
+    fn next_edge(&self, i: usize) -> Option<Edge> {
+        let edges = self.next_edges.as_ref().unwrap();
+        let e = edges.get(i).cloned();
+        e
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.input_metadata_.len()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.next_edges.as_ref().unwrap().len()
+    }
+
+    fn input_metadata(&self, index: usize) -> &InputMetaData {
+        self.input_metadata_.get(index).unwrap()
+    }
+
+    fn debug_print(&self) -> String {
+        "EmbeddingBackward".to_string()
+    }
+}
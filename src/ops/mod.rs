@@ -1,5 +1,6 @@
 mod graph_ops;
 mod graphroot;
+use crate::autograd::{AnomalyMetadata, AnomalyMode};
 use crate::tensor::*;
 pub use graph_ops::*;
 pub use graphroot::*;
@@ -14,6 +15,10 @@ pub trait NodeTrait {
     fn num_outputs(&self) -> usize;
     fn input_metadata(&self, index: usize) -> &InputMetaData;
     fn debug_print(&self) -> String;
+    // The leaf tensor a node accumulates into, only set for AccumulateGrad.
+    fn variable(&self) -> Option<&Tensor> {
+        None
+    }
     fn should_compute_output(&self, output_edge_index: usize) -> bool {
         assert!(output_edge_index < self.num_outputs(), "Index out of range");
         self.next_edges()
@@ -23,6 +28,7 @@ pub trait NodeTrait {
 #[derive(Debug)]
 pub struct Node {
     pub _impl: Box<dyn NodeTrait>,
+    metadata: Option<AnomalyMetadata>,
 }
 
 impl Node {
@@ -31,7 +37,16 @@ impl Node {
         T: NodeTrait + 'static,
     {
         let t: Box<dyn NodeTrait> = Box::new(node_type);
-        Self { _impl: t }
+        let metadata = if AnomalyMode::is_enabled() {
+            Some(AnomalyMetadata::store_stack())
+        } else {
+            None
+        };
+        Self { _impl: t, metadata }
+    }
+
+    pub fn metadata(&self) -> Option<&AnomalyMetadata> {
+        self.metadata.as_ref()
     }
 }
 
@@ -70,6 +85,10 @@ impl Node {
         self._impl.debug_print()
     }
 
+    pub fn variable(&self) -> Option<&Tensor> {
+        self._impl.variable()
+    }
+
     pub fn should_compute_output(&self, output_edge_index: usize) -> bool {
         assert!(output_edge_index < self.num_outputs(), "Index out of range");
         self.next_edges()
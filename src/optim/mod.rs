@@ -1,5 +1,7 @@
+use crate::aten::native;
 use crate::core::{AutoGradMode, NoGradGuard};
 use crate::tensor::*;
+use crate::util::OrderedDict;
 
 struct OptimizerOptions {}
 pub struct OptimizerParamGroup {
@@ -22,6 +24,10 @@ pub trait Optimizer {
     where
         F: FnMut() -> Tensor;
     fn param_groups(&self) -> &Vec<OptimizerParamGroup>;
+    // Per-parameter state such as momentum buffers, keyed as `state.{index}.{name}` with
+    // the parameters numbered across all param groups.
+    fn state_dict(&self) -> OrderedDict<String, Tensor>;
+    fn load_state_dict(&mut self, state_dict: &OrderedDict<String, Tensor>);
     fn zero_grad(&self) {
         for group in self.param_groups() {
             for p in group.params() {
@@ -141,6 +147,14 @@ impl SGDOptions {
 pub struct Sgd {
     param_groups: Vec<OptimizerParamGroup>,
     options: SGDOptions,
+    // Indexed like the keys of the state_dict.
+    momentum_buffers: Vec<Option<Tensor>>,
+}
+
+fn detached_clone(tensor: &Tensor) -> Tensor {
+    let result = native::empty_like(tensor, tensor.options(), None);
+    result.copy(tensor, None);
+    result
 }
 
 impl Sgd {
@@ -149,9 +163,11 @@ impl Sgd {
     }
 
     fn new_from_param_group(param_groups: Vec<OptimizerParamGroup>, options: SGDOptions) -> Self {
+        let num_params = param_groups.iter().map(|g| g.params().len()).sum();
         Self {
             param_groups,
             options,
+            momentum_buffers: vec![None; num_params],
         }
     }
 }
@@ -169,32 +185,44 @@ impl Optimizer for Sgd {
         }
         let weight_decay = self.options.weight_decay();
         let learning_rate = self.options.lr();
-        let _momentum = self.options.momentum();
-        let _dampening = self.options.dampening();
-        let _nesterov = self.options.nesterov();
+        let momentum = self.options.momentum();
+        let dampening = self.options.dampening();
+        let nesterov = self.options.nesterov();
 
-        for group in self.param_groups.iter_mut() {
-            for p in group.params() {
-                match p.grad().as_mut() {
-                    Some(d_p) => {
-                        if weight_decay != 0.0 {
-                            // eprintln!("Weight Grad Before: {:?}", borrow_);
-                            d_p.add_scalar(weight_decay);
+        let params = self.param_groups.iter().flat_map(|group| group.params());
+        for (p, momentum_buffer) in params.zip(self.momentum_buffers.iter_mut()) {
+            match p.grad().as_mut() {
+                Some(d_p) => {
+                    // Sparse gradients only update the rows they have.
+                    assert!(
+                        !d_p.is_sparse() || (weight_decay == 0.0 && momentum == 0.0),
+                        "SGD does not support momentum or weight decay with sparse gradients"
+                    );
+                    if weight_decay != 0.0 {
+                        d_p.add_with_alpha_(p, weight_decay);
+                    }
+                    if momentum != 0.0 {
+                        let buf = match momentum_buffer.take() {
+                            Some(mut buf) => {
+                                buf.mul_scalar(momentum);
+                                buf.add_with_alpha_(d_p, 1.0 - dampening);
+                                buf
+                            }
+                            None => detached_clone(d_p),
+                        };
+                        if nesterov {
+                            let update = detached_clone(d_p);
+                            update.add_with_alpha_(&buf, momentum);
+                            p.add_with_alpha_(&update, -learning_rate);
+                        } else {
+                            p.add_with_alpha_(&buf, -learning_rate);
                         }
-                        // if momentum != 0.0 {
-                        //     let buf;
-
-                        //     if nesterov {
-                        //         d_p = d_p.add(buf, momentum);
-                        //     } else {
-                        //         d_p = buf;
-                        //     }
-                        // }
-
+                        *momentum_buffer = Some(buf);
+                    } else {
                         p.add_with_alpha_(d_p, -1.0 * learning_rate);
                     }
-                    None => continue,
                 }
+                None => continue,
             }
         }
         loss
@@ -203,6 +231,47 @@ impl Optimizer for Sgd {
     fn param_groups(&self) -> &Vec<OptimizerParamGroup> {
         self.param_groups.as_ref()
     }
+
+    fn state_dict(&self) -> OrderedDict<String, Tensor> {
+        let mut result = OrderedDict::new_with_key_description("Tensor".to_string());
+        for (i, buf) in self.momentum_buffers.iter().enumerate() {
+            if let Some(buf) = buf {
+                result.insert(format!("state.{}.momentum_buffer", i), buf.tensor_data());
+            }
+        }
+        result
+    }
+
+    fn load_state_dict(&mut self, state_dict: &OrderedDict<String, Tensor>) {
+        let mut momentum_buffers = vec![None; self.momentum_buffers.len()];
+        for (key, value) in state_dict.iter() {
+            let index = key
+                .strip_prefix("state.")
+                .and_then(|k| k.strip_suffix(".momentum_buffer"))
+                .and_then(|i| i.parse::<usize>().ok())
+                .unwrap_or_else(|| panic!("Unexpected key in Sgd state_dict: {}", key));
+            assert!(
+                index < momentum_buffers.len(),
+                "Sgd state_dict has state for parameter {} but the optimizer only has {} parameters",
+                index,
+                momentum_buffers.len()
+            );
+            momentum_buffers[index] = Some(detached_clone(value));
+        }
+        let params = self.param_groups.iter().flat_map(|group| group.params());
+        for (i, (p, buf)) in params.zip(momentum_buffers.iter()).enumerate() {
+            if let Some(buf) = buf {
+                assert!(
+                    buf.sizes() == p.sizes(),
+                    "size mismatch for state.{}.momentum_buffer: copying a buffer with shape {:?} from checkpoint, the shape of the parameter is {:?}.",
+                    i,
+                    buf.sizes(),
+                    p.sizes()
+                );
+            }
+        }
+        self.momentum_buffers = momentum_buffers;
+    }
 }
 
 #[cfg(test)]
@@ -3,7 +3,8 @@ use aten::native::{scalar_tensor, to};
 use crate::{
     aten,
     c10::{
-        get_default_dtype, kDouble, kFloat, kInt, kLong, type_meta_to_scalar_type, Device, Scalar,
+        get_default_dtype, is_complex_type, kDouble, kFloat, kInt, kLong, to_complex_type,
+        type_meta_to_scalar_type, BFloat16, ComplexDouble, ComplexFloat, Device, Half, Scalar,
         ScalarType, TensorOptions, KCPU,
     },
     core::NoGradGuard,
@@ -22,13 +23,16 @@ fn compute_desired_dtype(scalar_type: ScalarType) -> ScalarType {
         // `tensor` with an integer type or a ` (nested) slice` / `vector`
         //  of integer types always produces a tensor of dtype `kLong`
         // (aka. i64).
-        return kLong;
+        kLong
     } else if scalar_type == kFloat || scalar_type == kDouble {
         // `tensor` with a floating-point type or a `(nested) slice` / `vector` of
         //  floating-point types always produces a tensor of dtype `get_default_dtype()`
-        return type_meta_to_scalar_type(&get_default_dtype());
+        type_meta_to_scalar_type(&get_default_dtype())
+    } else if is_complex_type(scalar_type) {
+        // Complex values produce the complex counterpart of `get_default_dtype()`.
+        to_complex_type(type_meta_to_scalar_type(&get_default_dtype()))
     } else {
-        return scalar_type;
+        scalar_type
     }
 }
 #[derive(Debug)]
@@ -80,15 +84,10 @@ impl TensorDataContainer {
 
     */
     fn from_slice<T>(values: &[T], scalar_type: ScalarType) -> Self {
-        let tensor;
-        if scalar_type == ScalarType::Bool {
-            todo!()
-        } else {
-            tensor = aten::native::tensor(values, {
-                let device: Device = KCPU.into();
-                TensorOptions::with_dtype(scalar_type).set_device(device)
-            });
-        }
+        let tensor = aten::native::tensor(values, {
+            let device: Device = KCPU.into();
+            TensorOptions::with_dtype(scalar_type).set_device(device)
+        });
         Self {
             sizes: vec![values.len()],
             scalar_type,
@@ -158,7 +157,15 @@ macro_rules! IMPL_TENSORDATECONTAINER {
     };
 }
 
+IMPL_TENSORDATECONTAINER!(ScalarType::Byte, u8);
+IMPL_TENSORDATECONTAINER!(ScalarType::Char, i8);
+IMPL_TENSORDATECONTAINER!(ScalarType::Short, i16);
+IMPL_TENSORDATECONTAINER!(ScalarType::Bool, bool);
 IMPL_TENSORDATECONTAINER!(ScalarType::Float, f32);
 IMPL_TENSORDATECONTAINER!(ScalarType::Int, i32);
 IMPL_TENSORDATECONTAINER!(ScalarType::Double, f64);
 IMPL_TENSORDATECONTAINER!(ScalarType::Long, i64);
+IMPL_TENSORDATECONTAINER!(ScalarType::Half, Half);
+IMPL_TENSORDATECONTAINER!(ScalarType::BFloat16, BFloat16);
+IMPL_TENSORDATECONTAINER!(ScalarType::ComplexFloat, ComplexFloat);
+IMPL_TENSORDATECONTAINER!(ScalarType::ComplexDouble, ComplexDouble);
@@ -0,0 +1,86 @@
+use crate::tensor::Tensor;
+use crate::AT_DISPATCH_FLOATING_AND_COMPLEX_TYPES_AND2;
+use std::backtrace::Backtrace;
+use std::cell::Cell;
+
+thread_local! {
+    static ANOMALY_ENABLED: Cell<bool> = const { Cell::new(false) };
+}
+pub struct AnomalyMode;
+
+impl AnomalyMode {
+    pub fn is_enabled() -> bool {
+        ANOMALY_ENABLED.with(Cell::get)
+    }
+
+    pub fn set_enabled(enabled: bool) {
+        ANOMALY_ENABLED.with(|mode| mode.set(enabled))
+    }
+}
+
+pub struct DetectAnomalyGuard {
+    prev_mode: bool,
+}
+
+impl DetectAnomalyGuard {
+    pub fn new(enabled: bool) -> Self {
+        let self_ = Self {
+            prev_mode: AnomalyMode::is_enabled(),
+        };
+        AnomalyMode::set_enabled(enabled);
+        self_
+    }
+}
+
+impl Drop for DetectAnomalyGuard {
+    fn drop(&mut self) {
+        AnomalyMode::set_enabled(self.prev_mode)
+    }
+}
+
+// Enables anomaly detection until the returned guard is dropped. While enabled, nodes
+// record the forward call site and the engine checks every node output for NaN/Inf.
+pub fn detect_anomaly() -> DetectAnomalyGuard {
+    DetectAnomalyGuard::new(true)
+}
+
+// Forward-time information stored on a node created while anomaly mode is on.
+#[derive(Debug)]
+pub struct AnomalyMetadata {
+    traceback: Backtrace,
+}
+
+impl AnomalyMetadata {
+    pub fn store_stack() -> Self {
+        Self {
+            traceback: Backtrace::force_capture(),
+        }
+    }
+
+    pub fn traceback(&self) -> String {
+        self.traceback.to_string()
+    }
+}
+
+pub fn has_non_finite(tensor: &Tensor) -> bool {
+    if !tensor.defined() {
+        return false;
+    }
+    if tensor.is_sparse() || tensor.is_sparse_csr() {
+        return has_non_finite(&tensor.values());
+    }
+    let tensor = tensor.contiguous();
+    let numel = tensor.numel();
+    // Complex values are finite when both their parts are.
+    AT_DISPATCH_FLOATING_AND_COMPLEX_TYPES_AND2!(
+        Half,
+        BFloat16,
+        tensor.scalar_type(),
+        "has_non_finite",
+        || {
+            let data = tensor.data_ptr_casted::<Scalart>();
+            (0..numel).any(|i| unsafe { !(*data.add(i)).is_finite() })
+        }
+    );
+    false
+}
@@ -9,6 +9,8 @@ pub struct AutogradMeta {
     pub grad_: Option<Tensor>,
     pub requires_grad: bool,
     pub output_nr: usize,
+    // Tangent of this tensor and the dual level it belongs to.
+    pub fw_grad_: Option<(usize, Tensor)>,
 }
 
 impl AutogradMeta {
@@ -23,6 +25,7 @@ impl AutogradMeta {
             grad_: None,
             requires_grad,
             output_nr,
+            fw_grad_: None,
         }
     }
     pub fn new_without_edge(impl_: &TensorImpl, requires_grad: bool) -> AutogradMeta {
@@ -80,6 +83,7 @@ impl AutogradMetaFactory {
             grad_accumulator_: None,
             requires_grad: false,
             output_nr: edge.input_nr,
+            fw_grad_: None,
         }
     }
 }
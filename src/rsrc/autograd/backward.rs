@@ -8,32 +8,60 @@ use crate::util_autograd;
 pub fn backward(tensors: &VariableList, grad_tensors: &VariableList, create_graph: bool) {
     let grads = _make_grads(tensors, grad_tensors);
     // println!("Created grads for backpass: {:?}", grads);
-    run_backward(tensors, grads, create_graph, &mut vec![])
+    run_backward(tensors, grads, create_graph, &[]);
+}
+
+// Computes and returns the gradients of outputs with respect to inputs instead of
+// accumulating them into .grad. Inputs which are not reachable from outputs get an
+// undefined tensor.
+pub fn grad(
+    outputs: &VariableList,
+    inputs: &VariableList,
+    grad_outputs: &VariableList,
+    create_graph: bool,
+) -> VariableList {
+    let grads = _make_grads(outputs, grad_outputs);
+    run_backward(outputs, grads, create_graph, inputs)
 }
 
 fn run_backward(
     outputs: &VariableList,
     grad_outputs: VariableList,
     create_graph: bool,
-    _inputs: &mut VariableList,
-) {
+    inputs: &[Tensor],
+) -> VariableList {
     let num_tensors = outputs.len();
     let mut roots: EdgeList = vec![];
     roots.reserve(num_tensors);
-    for output in outputs {
+    for (i, output) in outputs.iter().enumerate() {
         let gradient_edge = util_autograd::gradient_edge(output);
+        assert!(
+            gradient_edge.is_valid(),
+            "element {} of tensors does not require grad and does not have a grad_fn",
+            i
+        );
         roots.push(gradient_edge);
     }
-    let mut output_edges: EdgeList = vec![];
-    Engine::get_default_engine().execute(roots, grad_outputs, create_graph, &mut output_edges);
+    let mut output_edges: EdgeList = Vec::with_capacity(inputs.len());
+    for input in inputs {
+        output_edges.push(util_autograd::gradient_edge(input));
+    }
+    Engine::get_default_engine().execute(roots, grad_outputs, create_graph, &mut output_edges)
 }
 
 fn _make_grads(outputs: &VariableList, grad_outputs: &VariableList) -> VariableList {
     let num_tensors = outputs.len();
-    let _num_gradients = grad_outputs.len();
+    let num_gradients = grad_outputs.len();
     let mut new_grads: VariableList = vec![];
     new_grads.reserve(num_tensors);
-    if grad_outputs.is_empty() {
+    if !grad_outputs.is_empty() {
+        assert_eq!(
+            num_tensors, num_gradients,
+            "got {} tensors and {} gradients",
+            num_tensors, num_gradients
+        );
+        new_grads.extend(grad_outputs.iter().cloned());
+    } else {
         for output in outputs {
             if output.requires_grad() {
                 new_grads.push(ones_like(output, TensorOptions::default()))
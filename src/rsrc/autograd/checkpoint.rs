@@ -0,0 +1,77 @@
+use crate::core::{get_default_cpu_generator, NoGradGuard};
+use crate::nn::{Module, Sequential};
+use crate::ops::{CheckpointBackward, Node, NodeTrait};
+use crate::tensor::*;
+use crate::util_autograd;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// Runs `function` without recording the graph and only keeps `inputs` and `parameters`
+// around. The forward is recomputed during backward to rebuild the graph. `parameters`
+// are the tensors `function` uses besides its inputs, e.g. the weights of a module,
+// which need gradients too.
+pub fn checkpoint<F>(function: F, inputs: &[Tensor], parameters: &[Tensor]) -> Tensor
+where
+    F: Fn(&[Tensor]) -> Tensor + 'static,
+{
+    let rng_state = get_default_cpu_generator().get_state();
+    let output = {
+        let _guard = NoGradGuard::default();
+        function(inputs)
+    };
+    let saved: Vec<&Tensor> = inputs.iter().chain(parameters.iter()).collect();
+    if !util_autograd::compute_requires_grad(&saved) {
+        return output;
+    }
+    let mut _grad_fn = CheckpointBackward {
+        input_metadata_: smallvec::smallvec![],
+        next_edges: None,
+        function: Rc::new(function),
+        inputs: inputs.to_vec(),
+        parameters: parameters.to_vec(),
+        rng_state,
+    };
+    _grad_fn.set_next_edges(util_autograd::collect_next_edges(&saved));
+    let grad_fn = Rc::new(RefCell::new(Node::new(_grad_fn)));
+    util_autograd::set_history(&output, grad_fn);
+    output
+}
+
+// Splits the modules of `sequential` into `segments` chunks and checkpoints all but the
+// last one, whose activations are needed right away by backward.
+pub fn checkpoint_sequential(sequential: &Sequential, segments: usize, input: &Tensor) -> Tensor {
+    let modules = sequential.children();
+    assert!(
+        segments > 0 && segments <= modules.len(),
+        "expected between 1 and {} segments but got {}",
+        modules.len(),
+        segments
+    );
+    let segment_size = modules.len() / segments;
+    let mut output = input.clone();
+    let mut start = 0;
+    for _ in 0..segments - 1 {
+        let segment = modules[start..start + segment_size].to_vec();
+        let parameters: Vec<Tensor> = segment
+            .iter()
+            .filter_map(|m| m.parameters())
+            .flatten()
+            .collect();
+        output = checkpoint(
+            move |inputs| {
+                let mut output = inputs[0].clone();
+                for m in segment.iter() {
+                    output = m.forward(&[&output]);
+                }
+                output
+            },
+            &[output],
+            &parameters,
+        );
+        start += segment_size;
+    }
+    for m in modules[start..].iter() {
+        output = m.forward(&[&output]);
+    }
+    output
+}
@@ -1,3 +1,4 @@
+use super::anomaly_mode::*;
 use super::task::*;
 use crate::aten;
 use crate::core::AutoGradMode;
@@ -8,6 +9,11 @@ use std::cell::RefCell;
 use std::collections::HashSet;
 use std::rc::Rc;
 
+// The engine evaluates every node on the calling thread. Independent branches can't be
+// handed to a worker pool yet: nodes, tensors and their storages are shared through
+// `Rc`/`RefCell`, and branches routinely hold clones of the same tensor (a saved input,
+// or a gradient routed to several edges), so evaluating them concurrently would race on
+// the non-atomic reference counts. That needs `Arc` based tensors first.
 pub struct Engine {
     local_ready_queue: Rc<RefCell<ReadyQueue>>,
 }
@@ -68,15 +74,18 @@ impl Engine {
                 let function = edge.function().unwrap().borrow();
                 let metadata = function.input_metadata(edge.input_nr);
                 // remove shrinks vector that's why can't use i so use 0 to always get first element.
-                let grad = grads.pop().unwrap();
+                let mut grad = grads.pop().unwrap();
                 if grad.sizes() != metadata.shape() {
                     if !util::is_expandable_to(metadata.shape(), grad.sizes()) {
                         panic!("invalid gradient at index {} - got {:?}, but expected shape comapatible with {:?}", i, grad.sizes(), metadata.shape());
                     }
-                    new_grads.push(aten::sum_to(grad, metadata.shape()))
-                } else {
-                    new_grads.push(grad);
+                    grad = aten::sum_to(grad, metadata.shape());
+                }
+                // E.g. real inputs of complex ops only get the real part of their gradient.
+                if grad.scalar_type() != metadata.dtype() {
+                    grad = grad.to_dtype(metadata.dtype());
                 }
+                new_grads.push(grad);
             }
             new_grads.reverse();
             new_grads
@@ -89,7 +98,28 @@ impl Engine {
         let inputs = InputBuffer::variables(inputs);
         let fn_ = unsafe { &mut *func };
         let outputs = fn_.call(inputs);
-        Self::validate_outputs(fn_.next_edges(), outputs)
+        let outputs = Self::validate_outputs(fn_.next_edges(), outputs);
+        if AnomalyMode::is_enabled() {
+            Self::check_anomaly(fn_, &outputs);
+        }
+        outputs
+    }
+
+    fn check_anomaly(fn_: &Node, outputs: &VariableList) {
+        for (i, output) in outputs.iter().enumerate() {
+            if has_non_finite(output) {
+                let traceback = fn_.metadata().map_or_else(
+                    || "No forward pass information available. Enable detect anomaly during forward pass for more information.".to_string(),
+                    |m| format!("Traceback of forward call that caused the error:\n{}", m.traceback()),
+                );
+                panic!(
+                    "Function '{}' returned nan or inf values in its {}th output.\n{}",
+                    fn_.debug_print(),
+                    i,
+                    traceback
+                );
+            }
+        }
     }
 
     pub fn evaluate_function(
@@ -99,6 +129,23 @@ impl Engine {
         inputs: InputBuffer,
     ) {
         let _fnc = func.as_ptr();
+        {
+            let task = graph_task.borrow();
+            let exec_info = task.exec_info.borrow();
+            if !exec_info.is_empty() {
+                let fn_info = exec_info.get(&(_fnc as *const Node));
+                if let Some(fn_info) = fn_info {
+                    let mut captured_vars = task.captured_vars.borrow_mut();
+                    for (input_nr, output_idx) in &fn_info.captures {
+                        captured_vars[*output_idx] =
+                            inputs.buffer.get(*input_nr).cloned().unwrap_or_default();
+                    }
+                }
+                if !fn_info.is_some_and(|info| info.needed) {
+                    return;
+                }
+            }
+        }
         let mut outputs = Self::call_function(func.as_ptr(), inputs);
         let fn_ = func.borrow_mut();
         let task = graph_task.borrow();
@@ -203,13 +250,18 @@ impl Engine {
         roots: EdgeList,
         inputs: VariableList,
         create_graph: bool,
-        _output_edges: &mut EdgeList,
-    ) {
+        output_edges: &mut EdgeList,
+    ) -> VariableList {
         // println!("Inpots to Graph Root: {:?}", inputs);
-        let graph_root = Node::new(GraphRoot::new(roots, inputs));
+        let graph_root = Rc::new(RefCell::new(Node::new(GraphRoot::new(roots, inputs))));
         let mut task = GraphTask::new(create_graph, 0, self.local_ready_queue.clone());
-        Self::compute_dependencies(&graph_root, &mut task);
+        Self::compute_dependencies(graph_root.as_ptr(), &mut task);
+        if !output_edges.is_empty() {
+            task.init_to_execute(&graph_root.borrow(), output_edges);
+        }
         let task = Rc::new(RefCell::new(task));
-        self.execute_with_graph_task(&task, Rc::new(RefCell::new(graph_root)))
+        self.execute_with_graph_task(&task, graph_root);
+        let captured_vars = task.borrow().captured_vars.replace(vec![]);
+        captured_vars
     }
 }
@@ -0,0 +1,150 @@
+use crate::aten::native;
+use crate::tensor::*;
+use crate::util_autograd::TensorHook;
+use std::cell::Cell;
+
+thread_local! {
+    // Levels are per thread, so a level entered on one thread doesn't expose its
+    // tangents to another.
+    static CURRENT_LEVEL: Cell<Option<usize>> = const { Cell::new(None) };
+    static NEXT_LEVEL: Cell<usize> = const { Cell::new(0) };
+}
+
+pub fn current_level() -> Option<usize> {
+    CURRENT_LEVEL.with(Cell::get)
+}
+
+fn set_current_level(level: Option<usize>) {
+    CURRENT_LEVEL.with(|current| current.set(level))
+}
+
+// Tangents are only visible while the level they were created in is active. Leaving
+// the level makes every tangent created inside it unreachable.
+pub struct DualLevel {
+    level: usize,
+}
+
+impl DualLevel {
+    pub fn new() -> Self {
+        assert!(
+            current_level().is_none(),
+            "Nested forward mode AD is not supported at the moment"
+        );
+        let level = NEXT_LEVEL.with(|next| next.replace(next.get() + 1));
+        set_current_level(Some(level));
+        Self { level }
+    }
+
+    pub fn level(&self) -> usize {
+        self.level
+    }
+}
+
+impl Default for DualLevel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for DualLevel {
+    fn drop(&mut self) {
+        set_current_level(None)
+    }
+}
+
+// Enters a new forward AD level until the returned guard is dropped.
+pub fn dual_level() -> DualLevel {
+    DualLevel::new()
+}
+
+// Hides all tangents while the forward derivative formulas run, so the ops they use
+// don't try to propagate tangents themselves.
+struct ForwardADDisabled {
+    prev_level: Option<usize>,
+}
+
+impl ForwardADDisabled {
+    fn new() -> Self {
+        let self_ = Self {
+            prev_level: current_level(),
+        };
+        set_current_level(None);
+        self_
+    }
+}
+
+impl Drop for ForwardADDisabled {
+    fn drop(&mut self) {
+        set_current_level(self.prev_level)
+    }
+}
+
+pub fn fw_grad(tensor: &Tensor) -> Option<Tensor> {
+    let level = current_level()?;
+    match TensorHook::get_autograd_meta(tensor)?.fw_grad_.as_ref() {
+        Some((l, tangent)) if *l == level => Some(tangent.clone()),
+        _ => None,
+    }
+}
+
+fn set_fw_grad(tensor: &Tensor, level: usize, tangent: Tensor) {
+    TensorHook::materialize_autograd_meta(tensor).fw_grad_ = Some((level, tangent));
+}
+
+// Aliases `tensor` so the result shares its storage and routes gradients back to it.
+fn alias(tensor: &Tensor) -> Tensor {
+    let _guard = ForwardADDisabled::new();
+    expand(tensor, tensor.sizes(), false)
+}
+
+// Returns a dual tensor whose primal is `tensor` and whose tangent is `tangent`.
+pub fn make_dual(tensor: &Tensor, tangent: &Tensor) -> Tensor {
+    let level = current_level().expect("make_dual can only be used inside a dual_level");
+    assert!(
+        tensor.sizes() == tangent.sizes(),
+        "Trying to set a forward gradient that has a different size than that of the original Tensor, this is not supported. Tensor is of size {:?} while the given forward gradient is of size {:?}.",
+        tensor.sizes(),
+        tangent.sizes()
+    );
+    assert!(
+        tensor.scalar_type() == tangent.scalar_type(),
+        "Expected tangent to have dtype {:?} but got {:?}",
+        tensor.scalar_type(),
+        tangent.scalar_type()
+    );
+    let dual = alias(tensor);
+    set_fw_grad(&dual, level, tangent.clone());
+    dual
+}
+
+// Splits a dual tensor into its primal and its tangent at the current level.
+pub fn unpack_dual(tensor: &Tensor) -> (Tensor, Option<Tensor>) {
+    let tangent = fw_grad(tensor);
+    (alias(tensor), tangent)
+}
+
+// Sets the tangent of `result` to `formula(tangents)` when any of `inputs` is a dual
+// tensor at the current level. Inputs without a tangent are given zeros.
+pub(crate) fn set_fw_grad_with<F>(result: &Tensor, inputs: &[&Tensor], formula: F)
+where
+    F: FnOnce(&[Tensor]) -> Tensor,
+{
+    let level = match current_level() {
+        Some(level) => level,
+        None => return,
+    };
+    let tangents: Vec<Option<Tensor>> = inputs.iter().map(|t| fw_grad(t)).collect();
+    if tangents.iter().all(Option::is_none) {
+        return;
+    }
+    let _guard = ForwardADDisabled::new();
+    let tangents: Vec<Tensor> = tangents
+        .into_iter()
+        .zip(inputs.iter())
+        .map(|(tangent, input)| {
+            tangent.unwrap_or_else(|| native::full_like(input, 0.0, input.options(), None))
+        })
+        .collect();
+    let tangent = formula(&tangents);
+    set_fw_grad(result, level, tangent);
+}
@@ -0,0 +1,378 @@
+use super::forward_ad::{dual_level, make_dual, unpack_dual};
+use super::*;
+use crate::aten::native;
+use crate::tensor::*;
+
+type MultiOutputFunction<'a> = &'a dyn Fn(&[Tensor]) -> VariableList;
+
+fn zeros_like(tensor: &Tensor) -> Tensor {
+    native::full_like(tensor, 0.0, tensor.options(), None)
+}
+
+fn one_hot(like: &Tensor, index: usize) -> Tensor {
+    let flat = native::zeros(&[like.numel()], like.options());
+    select(&flat, 0, index as i64).fill_(1.0);
+    flat.view(like.sizes())
+}
+
+fn check_sizes(tensors: &[Tensor], like: &[Tensor], what: &str) {
+    assert_eq!(
+        tensors.len(),
+        like.len(),
+        "expected {} {} but got {}",
+        like.len(),
+        what,
+        tensors.len()
+    );
+    for (i, (t, l)) in tensors.iter().zip(like.iter()).enumerate() {
+        assert!(
+            t.sizes() == l.sizes(),
+            "Mismatch in size of {} {}: got {:?} but expected {:?}",
+            what,
+            i,
+            t.sizes(),
+            l.sizes()
+        );
+    }
+}
+
+// Unless the caller wants the graph and the input already requires grad, the function
+// is evaluated on detached copies so the results don't leak into the caller's graph.
+fn grad_preprocess(inputs: &[Tensor], create_graph: bool, need_graph: bool) -> VariableList {
+    inputs
+        .iter()
+        .map(|input| {
+            if create_graph && input.requires_grad() {
+                input.clone()
+            } else {
+                let input = input.tensor_data();
+                input.set_requires_grad(need_graph);
+                input
+            }
+        })
+        .collect()
+}
+
+fn grad_postprocess(tensor: Tensor, create_graph: bool) -> Tensor {
+    if create_graph {
+        tensor
+    } else {
+        tensor.tensor_data()
+    }
+}
+
+// Like `grad` but skips the outputs which don't require grad. Inputs which can't be
+// reached get an undefined tensor.
+fn autograd_grad(
+    outputs: &[Tensor],
+    inputs: &[Tensor],
+    grad_outputs: &[Tensor],
+    create_graph: bool,
+) -> VariableList {
+    let (outputs, grad_outputs): (VariableList, VariableList) = outputs
+        .iter()
+        .zip(grad_outputs.iter())
+        .filter(|(output, _)| output.requires_grad())
+        .map(|(output, grad_output)| (output.clone(), grad_output.clone()))
+        .unzip();
+    if outputs.is_empty() {
+        return vec![Tensor::default(); inputs.len()];
+    }
+    grad(&outputs, &inputs.to_vec(), &grad_outputs, create_graph)
+}
+
+fn fill_in_zeros(grads: VariableList, refs: &[Tensor], strict: bool, reason: &str) -> VariableList {
+    grads
+        .into_iter()
+        .zip(refs.iter())
+        .enumerate()
+        .map(|(i, (grad, input))| {
+            if grad.defined() {
+                grad
+            } else {
+                assert!(
+                    !strict,
+                    "{} {}. This is not allowed in strict mode.",
+                    reason, i
+                );
+                zeros_like(input)
+            }
+        })
+        .collect()
+}
+
+// Stacks the slices of a jacobian along `dim` and reshapes the result to
+// output.sizes() + input.sizes().
+fn assemble(slices: &[Tensor], dim: usize, output: &Tensor, input: &Tensor) -> Tensor {
+    let mut sizes = output.sizes().to_vec();
+    sizes.extend_from_slice(input.sizes());
+    if slices.is_empty() {
+        return native::zeros(sizes.as_slice(), output.options());
+    }
+    stack(slices, dim as i64).view(sizes.as_slice())
+}
+
+// Computes the output of `func` and the vector-jacobian product of `v` with respect to
+// each of `inputs` using reverse mode AD.
+pub fn vjp<F>(
+    func: F,
+    inputs: &[Tensor],
+    v: &Tensor,
+    create_graph: bool,
+    strict: bool,
+) -> (Tensor, VariableList)
+where
+    F: Fn(&[Tensor]) -> Tensor,
+{
+    let inputs = grad_preprocess(inputs, create_graph, true);
+    let output = func(&inputs);
+    check_sizes(std::slice::from_ref(v), std::slice::from_ref(&output), "v");
+    let grads = autograd_grad(
+        std::slice::from_ref(&output),
+        &inputs,
+        std::slice::from_ref(v),
+        create_graph,
+    );
+    let grads = fill_in_zeros(
+        grads,
+        &inputs,
+        strict,
+        "The output of the user-provided function is independent of input",
+    );
+    (
+        grad_postprocess(output, create_graph),
+        grads
+            .into_iter()
+            .map(|g| grad_postprocess(g, create_graph))
+            .collect(),
+    )
+}
+
+// Computes the output of `func` and its jacobian-vector product with `v` using
+// forward mode AD, i.e. a single forward pass with `v` as the tangents of `inputs`.
+pub fn jvp<F>(
+    func: F,
+    inputs: &[Tensor],
+    v: &[Tensor],
+    create_graph: bool,
+    strict: bool,
+) -> (Tensor, Tensor)
+where
+    F: Fn(&[Tensor]) -> Tensor,
+{
+    check_sizes(v, inputs, "v");
+    let inputs = grad_preprocess(inputs, create_graph, false);
+    let (output, tangent) = {
+        let _level = dual_level();
+        let duals: VariableList = inputs
+            .iter()
+            .zip(v.iter())
+            .map(|(input, tangent)| make_dual(input, tangent))
+            .collect();
+        unpack_dual(&func(&duals))
+    };
+    let tangent = tangent.unwrap_or_else(|| {
+        assert!(
+            !strict,
+            "The output of the user-provided function is independent of the inputs. This is not allowed in strict mode."
+        );
+        zeros_like(&output)
+    });
+    (
+        grad_postprocess(output, create_graph),
+        grad_postprocess(tangent, create_graph),
+    )
+}
+
+// One backward pass per output element, each one giving a row of the jacobian.
+fn reverse_jacobian(
+    func: MultiOutputFunction,
+    inputs: &[Tensor],
+    create_graph: bool,
+    strict: bool,
+) -> Vec<VariableList> {
+    let inputs = grad_preprocess(inputs, create_graph, true);
+    let outputs = func(&inputs);
+    outputs
+        .iter()
+        .map(|output| {
+            let mut rows: Vec<VariableList> = vec![vec![]; inputs.len()];
+            for j in 0..output.numel() {
+                let grads = autograd_grad(
+                    std::slice::from_ref(output),
+                    &inputs,
+                    &[one_hot(output, j)],
+                    create_graph,
+                );
+                let grads = fill_in_zeros(
+                    grads,
+                    &inputs,
+                    strict,
+                    "The output of the user-provided function is independent of input",
+                );
+                for (row, grad) in rows.iter_mut().zip(grads) {
+                    row.push(grad);
+                }
+            }
+            rows.iter()
+                .zip(inputs.iter())
+                .map(|(rows, input)| {
+                    grad_postprocess(assemble(rows, 0, output, input), create_graph)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// One forward pass per input element, each one giving a column of the jacobian.
+fn forward_jacobian(
+    func: MultiOutputFunction,
+    inputs: &[Tensor],
+    create_graph: bool,
+) -> Vec<VariableList> {
+    let inputs = grad_preprocess(inputs, create_graph, false);
+    let outputs = func(&inputs);
+    let mut columns: Vec<Vec<VariableList>> = vec![vec![vec![]; inputs.len()]; outputs.len()];
+    for (i, input) in inputs.iter().enumerate() {
+        for k in 0..input.numel() {
+            let _level = dual_level();
+            let duals: VariableList = inputs
+                .iter()
+                .enumerate()
+                .map(|(n, x)| {
+                    if n == i {
+                        make_dual(x, &one_hot(x, k))
+                    } else {
+                        x.clone()
+                    }
+                })
+                .collect();
+            for (o, output) in func(&duals).iter().enumerate() {
+                let tangent = unpack_dual(output)
+                    .1
+                    .unwrap_or_else(|| zeros_like(&outputs[o]));
+                columns[o][i].push(tangent);
+            }
+        }
+    }
+    columns
+        .iter()
+        .zip(outputs.iter())
+        .map(|(columns, output)| {
+            columns
+                .iter()
+                .zip(inputs.iter())
+                .map(|(columns, input)| {
+                    let dim = output.dim() as usize;
+                    grad_postprocess(assemble(columns, dim, output, input), create_graph)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// Result is indexed as [output][input].
+fn jacobian_impl(
+    func: MultiOutputFunction,
+    inputs: &[Tensor],
+    create_graph: bool,
+    strict: bool,
+    vectorize: bool,
+) -> Vec<VariableList> {
+    assert!(
+        !(strict && vectorize),
+        "jacobian: `strict=true` and `vectorize=true` are not supported together. Please either set `strict=false` or `vectorize=false`."
+    );
+    if vectorize {
+        forward_jacobian(func, inputs, create_graph)
+    } else {
+        reverse_jacobian(func, inputs, create_graph, strict)
+    }
+}
+
+// Returns the jacobian of `func` with respect to each of `inputs`, shaped as
+// output.sizes() + input.sizes(). There is no batched backward to vectorize over, so
+// `vectorize` computes the jacobian column by column in forward mode instead of row
+// by row in reverse mode, which is cheaper when the output is larger than the inputs.
+pub fn jacobian<F>(
+    func: F,
+    inputs: &[Tensor],
+    create_graph: bool,
+    strict: bool,
+    vectorize: bool,
+) -> VariableList
+where
+    F: Fn(&[Tensor]) -> Tensor,
+{
+    let func = |inputs: &[Tensor]| vec![func(inputs)];
+    jacobian_impl(&func, inputs, create_graph, strict, vectorize).remove(0)
+}
+
+// Returns the hessian of the scalar function `func` as hessian[i][j], the block for
+// inputs i and j shaped as inputs[i].sizes() + inputs[j].sizes().
+pub fn hessian<F>(
+    func: F,
+    inputs: &[Tensor],
+    create_graph: bool,
+    strict: bool,
+    vectorize: bool,
+) -> Vec<VariableList>
+where
+    F: Fn(&[Tensor]) -> Tensor,
+{
+    let func = |inputs: &[Tensor]| {
+        let output = func(inputs);
+        assert!(
+            output.numel() == 1,
+            "The function given to hessian should return a single element Tensor"
+        );
+        vec![output]
+    };
+    let gradient =
+        |inputs: &[Tensor]| jacobian_impl(&func, inputs, true, strict, vectorize).remove(0);
+    // Backward doesn't propagate tangents, so the outer jacobian is always computed in
+    // reverse mode. With `vectorize` this gives reverse-over-forward.
+    jacobian_impl(&gradient, inputs, create_graph, strict, false)
+}
+
+// Computes the output of the scalar function `func` and the product of its hessian
+// with `v`, using a double backward instead of building the full hessian.
+pub fn hvp<F>(
+    func: F,
+    inputs: &[Tensor],
+    v: &[Tensor],
+    create_graph: bool,
+    strict: bool,
+) -> (Tensor, VariableList)
+where
+    F: Fn(&[Tensor]) -> Tensor,
+{
+    check_sizes(v, inputs, "v");
+    let inputs = grad_preprocess(inputs, create_graph, true);
+    let output = func(&inputs);
+    assert!(
+        output.numel() == 1,
+        "The function given to hvp should return a single element Tensor"
+    );
+    let grad_output = ones_like(&output, output.options());
+    let grads = autograd_grad(std::slice::from_ref(&output), &inputs, &[grad_output], true);
+    let grads = fill_in_zeros(
+        grads,
+        &inputs,
+        strict,
+        "The output of the user-provided function is independent of input",
+    );
+    let hv = autograd_grad(&grads, &inputs, v, create_graph);
+    let hv = fill_in_zeros(
+        hv,
+        &inputs,
+        strict,
+        "The hessian of the user-provided function is independent of input",
+    );
+    (
+        grad_postprocess(output, create_graph),
+        hv.into_iter()
+            .map(|t| grad_postprocess(t, create_graph))
+            .collect(),
+    )
+}
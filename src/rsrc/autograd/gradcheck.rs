@@ -0,0 +1,237 @@
+use super::*;
+use crate::c10::ScalarType;
+use crate::tensor::*;
+
+// Jacobians are stored per (input, output) pair as jacobian[input_idx][output_idx],
+// i.e. the rows are indexed by input elements and the columns by output elements.
+type Jacobian = Vec<Vec<f64>>;
+
+#[inline]
+fn element_ptr(tensor: &Tensor, mut index: usize) -> *mut f64 {
+    let sizes = tensor.sizes();
+    let strides = tensor.strides();
+    let mut offset = 0;
+    for d in (0..sizes.len()).rev() {
+        offset += (index % sizes[d]) * strides[d];
+        index /= sizes[d];
+    }
+    unsafe { tensor.data_ptr_casted::<f64>().add(offset) }
+}
+
+fn values(tensor: &Tensor) -> Vec<f64> {
+    (0..tensor.numel())
+        .map(|i| unsafe { *element_ptr(tensor, i) })
+        .collect()
+}
+
+fn check_double(tensor: &Tensor, what: &str, idx: usize) {
+    assert!(
+        tensor.scalar_type() == ScalarType::Double,
+        "gradcheck expects {} {} to be of dtype Double but got {:?}",
+        what,
+        idx,
+        tensor.scalar_type()
+    );
+}
+
+fn grad_fn_name(tensor: &Tensor) -> String {
+    tensor
+        .grad_fn()
+        .map_or_else(|| "None".to_string(), |f| f.borrow().debug_print())
+}
+
+// Central differences, perturbing every element of every differentiable input.
+// Result is indexed as [input][output] and None for inputs which don't require grad.
+fn get_numerical_jacobian(
+    func: &dyn Fn(&[Tensor]) -> VariableList,
+    inputs: &[Tensor],
+    num_outputs: usize,
+    eps: f64,
+) -> Vec<Option<Vec<Jacobian>>> {
+    let mut jacobians = Vec::with_capacity(inputs.len());
+    for input in inputs {
+        if !input.requires_grad() {
+            jacobians.push(None);
+            continue;
+        }
+        let mut jacobian = vec![Vec::with_capacity(input.numel()); num_outputs];
+        for k in 0..input.numel() {
+            let ptr = element_ptr(input, k);
+            let orig = unsafe { *ptr };
+            unsafe { *ptr = orig - eps };
+            let outputs_a = func(inputs).iter().map(values).collect::<Vec<_>>();
+            unsafe { *ptr = orig + eps };
+            let outputs_b = func(inputs).iter().map(values).collect::<Vec<_>>();
+            unsafe { *ptr = orig };
+            for o in 0..num_outputs {
+                let column = outputs_a[o]
+                    .iter()
+                    .zip(outputs_b[o].iter())
+                    .map(|(a, b)| (b - a) / (2.0 * eps))
+                    .collect();
+                jacobian[o].push(column);
+            }
+        }
+        jacobians.push(Some(jacobian));
+    }
+    jacobians
+}
+
+// Backpropagates a one-hot grad_output for every element of output.
+fn get_analytical_jacobian(inputs: &[Tensor], output: &Tensor) -> Vec<Option<Jacobian>> {
+    let numel = output.numel();
+    let diff_inputs: VariableList = inputs
+        .iter()
+        .filter(|t| t.requires_grad())
+        .cloned()
+        .collect();
+    let mut jacobians: Vec<Option<Jacobian>> = inputs
+        .iter()
+        .map(|t| {
+            if t.requires_grad() {
+                Some(vec![vec![0.0; numel]; t.numel()])
+            } else {
+                None
+            }
+        })
+        .collect();
+    if !output.requires_grad() {
+        return jacobians;
+    }
+    for j in 0..numel {
+        let grad_output = full_like(output, 0.0, output.options());
+        unsafe { *element_ptr(&grad_output, j) = 1.0 };
+        let grads = grad(
+            &vec![output.clone()],
+            &diff_inputs,
+            &vec![grad_output],
+            false,
+        );
+        let mut grads = grads.into_iter();
+        for jacobian in jacobians.iter_mut().flatten() {
+            let g = grads.next().unwrap();
+            if !g.defined() {
+                continue;
+            }
+            for (k, v) in values(&g).into_iter().enumerate() {
+                jacobian[k][j] = v;
+            }
+        }
+    }
+    jacobians
+}
+
+fn check_jacobians(
+    func: &dyn Fn(&[Tensor]) -> VariableList,
+    inputs: &[Tensor],
+    eps: f64,
+    atol: f64,
+    rtol: f64,
+    check_name: &str,
+) {
+    for (i, input) in inputs.iter().enumerate() {
+        check_double(input, "input", i);
+    }
+    assert!(
+        inputs.iter().any(|t| t.requires_grad()),
+        "{}: at least one input should require grad",
+        check_name
+    );
+    let outputs = func(inputs);
+    for (o, output) in outputs.iter().enumerate() {
+        check_double(output, "output", o);
+    }
+    let numerical = get_numerical_jacobian(func, inputs, outputs.len(), eps);
+    for (o, output) in outputs.iter().enumerate() {
+        let analytical = get_analytical_jacobian(inputs, output);
+        for (i, (a, n)) in analytical.iter().zip(numerical.iter()).enumerate() {
+            let (a, n) = match (a, n) {
+                (Some(a), Some(n)) => (a, &n[o]),
+                _ => continue,
+            };
+            // (input element, output element, excess over the allowed tolerance)
+            let mut worst: Option<(usize, usize, f64)> = None;
+            for k in 0..a.len() {
+                for j in 0..a[k].len() {
+                    let excess = (a[k][j] - n[k][j]).abs() - (atol + rtol * n[k][j].abs());
+                    let is_nan = a[k][j].is_nan() != n[k][j].is_nan();
+                    if (excess > 0.0 || is_nan) && worst.is_none_or(|w| excess > w.2) {
+                        worst = Some((k, j, excess));
+                    }
+                }
+            }
+            if let Some((k, j, _)) = worst {
+                panic!(
+                    "{}: Jacobian mismatch for output {} with respect to input {} (grad_fn: {}),\n\
+                     worst element is input index {} / output index {}:\n\
+                     numerical: {}\nanalytical: {}\ndifference: {}",
+                    check_name,
+                    o,
+                    i,
+                    grad_fn_name(output),
+                    k,
+                    j,
+                    n[k][j],
+                    a[k][j],
+                    (a[k][j] - n[k][j]).abs()
+                );
+            }
+        }
+    }
+}
+
+/// Checks the gradients computed by the engine for `func` against finite
+/// differences. Inputs must be Double; only the ones requiring grad are checked.
+/// Panics with the worst mismatching element and the output's grad_fn on failure.
+pub fn gradcheck<F>(func: F, inputs: &[Tensor], eps: f64, atol: f64, rtol: f64)
+where
+    F: Fn(&[Tensor]) -> Tensor,
+{
+    let func = |inputs: &[Tensor]| vec![func(inputs)];
+    check_jacobians(&func, inputs, eps, atol, rtol, "gradcheck")
+}
+
+/// Same as `gradcheck` but for the second order gradients, i.e. it runs gradcheck
+/// on the gradients of `func` computed with `create_graph` using random grad_outputs.
+/// Panics on failure like `gradcheck`.
+pub fn gradgradcheck<F>(func: F, inputs: &[Tensor], eps: f64, atol: f64, rtol: f64)
+where
+    F: Fn(&[Tensor]) -> Tensor,
+{
+    let output = func(inputs);
+    check_double(&output, "output", 0);
+    let grad_output = empty_like(&output, output.options(), None);
+    grad_output.uniform(-1.0, 1.0);
+    grad_output.set_requires_grad(true);
+
+    let num_inputs = inputs.len();
+    let grad_func = |all_inputs: &[Tensor]| -> VariableList {
+        let (inputs, grad_outputs) = all_inputs.split_at(num_inputs);
+        let diff_inputs: VariableList = inputs
+            .iter()
+            .filter(|t| t.requires_grad())
+            .cloned()
+            .collect();
+        let grads = grad(
+            &vec![func(inputs)],
+            &diff_inputs,
+            &grad_outputs.to_vec(),
+            true,
+        );
+        // Inputs the output doesn't depend on get a zero gradient.
+        grads
+            .into_iter()
+            .zip(diff_inputs.iter())
+            .map(|(g, input)| {
+                if g.defined() {
+                    g
+                } else {
+                    full_like(input, 0.0, input.options())
+                }
+            })
+            .collect()
+    };
+    let mut all_inputs = inputs.to_vec();
+    all_inputs.push(grad_output);
+    check_jacobians(&grad_func, &all_inputs, eps, atol, rtol, "gradgradcheck")
+}
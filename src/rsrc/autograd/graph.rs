@@ -0,0 +1,104 @@
+use crate::ops::Node;
+use crate::tensor::Tensor;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::rc::Rc;
+
+fn tensor_label(name: &str, tensor: &Tensor) -> String {
+    format!(
+        "{}\\n{:?}\\nrequires_grad={}",
+        name,
+        tensor.sizes(),
+        tensor.requires_grad()
+    )
+}
+
+fn node_label(node: &Node) -> String {
+    let mut label = node.debug_print();
+    for i in 0..node.num_inputs() {
+        write!(
+            label,
+            "\\ninput {}: {:?}",
+            i,
+            node.input_metadata(i).shape()
+        )
+        .unwrap();
+    }
+    label
+}
+
+// Emits the graph reachable from `tensor.grad_fn()` in Graphviz DOT format. Edges point
+// in the forward direction and are labelled with the index of the next edge, i.e. which
+// input of the forward op they correspond to.
+pub fn to_dot(tensor: &Tensor) -> String {
+    let mut dot = String::from("digraph {\n    node [fontsize=10, shape=box];\n");
+    writeln!(
+        dot,
+        "    output [label=\"{}\", shape=oval, style=filled, fillcolor=darkolivegreen1];",
+        tensor_label("output", tensor)
+    )
+    .unwrap();
+
+    let root = match tensor.grad_fn() {
+        Some(root) => root,
+        None => {
+            if tensor.requires_grad() {
+                dot.push_str("    output [fillcolor=lightblue];\n");
+            }
+            dot.push_str("}\n");
+            return dot;
+        }
+    };
+
+    // Assign ids in discovery order so the output is stable across runs.
+    let mut ids: HashMap<*const Node, usize> = HashMap::new();
+    let mut stack: Vec<Rc<RefCell<Node>>> = vec![root.clone()];
+    ids.insert(root.as_ptr(), 0);
+    writeln!(dot, "    node0 -> output;").unwrap();
+    while let Some(fn_) = stack.pop() {
+        let node = fn_.borrow();
+        let id = ids[&(fn_.as_ptr() as *const Node)];
+        match node.variable() {
+            Some(leaf) => {
+                writeln!(
+                    dot,
+                    "    node{} [label=\"{}\", style=filled, fillcolor=lightgray];",
+                    id,
+                    node_label(&node)
+                )
+                .unwrap();
+                writeln!(
+                    dot,
+                    "    leaf{} [label=\"{}\", shape=oval, style=filled, fillcolor=lightblue];",
+                    id,
+                    tensor_label("leaf", leaf)
+                )
+                .unwrap();
+                writeln!(dot, "    leaf{} -> node{};", id, id).unwrap();
+            }
+            None => {
+                writeln!(dot, "    node{} [label=\"{}\"];", id, node_label(&node)).unwrap();
+            }
+        }
+        for (i, edge) in node.next_edges().into_iter().flatten().enumerate() {
+            let next = match edge.function() {
+                Some(next) => next,
+                None => continue,
+            };
+            let next_ptr = next.as_ptr() as *const Node;
+            let next_id = match ids.get(&next_ptr) {
+                Some(next_id) => *next_id,
+                None => {
+                    let next_id = ids.len();
+                    ids.insert(next_ptr, next_id);
+                    stack.push(next.clone());
+                    next_id
+                }
+            };
+            writeln!(dot, "    node{} -> node{} [label=\"{}\"];", next_id, id, i).unwrap();
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
@@ -1,14 +1,23 @@
+mod anomaly_mode;
 mod autogradmeta;
 pub mod backward;
+mod checkpoint;
 mod saved_variable;
+pub use self::anomaly_mode::*;
 pub use self::autogradmeta::*;
 pub use self::saved_variable::SavedTensor;
 pub use backward::*;
+pub use checkpoint::*;
 mod engine;
+pub mod forward_ad;
 mod function;
+pub mod functional;
+mod gradcheck;
+pub mod graph;
 mod task;
 mod variable_factories;
 pub use engine::*;
 pub use function::*;
+pub use gradcheck::*;
 pub use task::*;
 pub use variable_factories::*;
@@ -9,6 +9,9 @@ pub struct SavedTensor {
     data: Tensor,
     grad_accumulator: Option<Weak<RefCell<Node>>>,
     grad_fn: Option<Rc<RefCell<Node>>>,
+    // Set for outputs saved by their own grad_fn. Held weakly to avoid a
+    // reference cycle between the node and the tensor it saved.
+    weak_grad_fn: Option<Weak<RefCell<Node>>>,
     saved_version: u32,
     output_nr: usize,
     was_default_constructed: bool,
@@ -24,6 +27,7 @@ impl Default for SavedTensor {
             was_default_constructed: true,
             grad_accumulator: None,
             grad_fn: None,
+            weak_grad_fn: None,
             saved_version: 0,
             output_nr: 0,
             requires_grad: false,
@@ -36,6 +40,10 @@ impl Default for SavedTensor {
 impl SavedTensor {
     pub fn new(tensor: &Tensor, is_output: bool) -> Self {
         if tensor.defined() {
+            assert!(
+                !tensor.is_inference(),
+                "Inference tensors cannot be saved for backward. To work around you can make a clone to get a normal tensor and use it in autograd."
+            );
             let was_default_constructed = false;
             let output_nr = tensor.output_nr();
             let requires_grad = tensor.requires_grad();
@@ -56,6 +64,7 @@ impl SavedTensor {
                 data,
                 grad_accumulator,
                 grad_fn,
+                weak_grad_fn: None,
                 saved_version,
                 output_nr,
                 was_default_constructed,
@@ -68,6 +77,17 @@ impl SavedTensor {
         }
     }
 
+    /// Saves `tensor` as output `output_nr` of `grad_fn`, the node which is
+    /// being constructed for it.
+    pub fn new_output(tensor: &Tensor, grad_fn: Weak<RefCell<Node>>, output_nr: usize) -> Self {
+        let mut saved = Self::new(tensor, true);
+        saved.weak_grad_fn = Some(grad_fn);
+        saved.output_nr = output_nr;
+        saved.requires_grad = true;
+        saved.has_grad_fn = true;
+        saved
+    }
+
     pub fn new_with_optional(tensor: Option<&Tensor>, is_output: bool) -> Self {
         let t = match tensor {
             Some(t) => t.clone(),
@@ -84,12 +104,33 @@ impl SavedTensor {
             return Tensor::default();
         }
 
-        if self.saved_version != self.version_counter.current_version() {
-            panic!()
+        let grad_fn = self
+            .grad_fn
+            .clone()
+            .or_else(|| self.weak_grad_fn.as_ref().and_then(|f| f.upgrade()));
+
+        let current_version = self.version_counter.current_version();
+        if self.saved_version != current_version {
+            let output = match &grad_fn {
+                Some(grad_fn) => format!(
+                    ", which is output {} of {},",
+                    self.output_nr,
+                    grad_fn.borrow().debug_print()
+                ),
+                None => String::new(),
+            };
+            panic!(
+                "one of the variables needed for gradient computation has been modified by an inplace operation: [{:?} {:?}]{} is at version {}; expected version {} instead. Hint: enable anomaly detection with `detect_anomaly()` to find the operation that failed to compute its gradient.",
+                self.data.scalar_type(),
+                self.data.sizes(),
+                output,
+                current_version,
+                self.saved_version
+            );
         }
 
         let mut tensor: Tensor;
-        if let Some(grad_fn) = &self.grad_fn {
+        if let Some(grad_fn) = &grad_fn {
             tensor = make_variable_with_edge(
                 self.data.clone(),
                 Edge::new(Some(grad_fn.clone()), self.output_nr),
@@ -98,10 +139,9 @@ impl SavedTensor {
             tensor = make_variable(self.data.clone(), self.requires_grad);
         }
 
-        util_autograd::TensorHook::set_version_counter(
-            &tensor,
-            TensorVersion::new_with_version(self.saved_version),
-        );
+        // Share the counter so in-place modifications of the unpacked tensor are seen
+        // by the saved one.
+        util_autograd::TensorHook::set_version_counter(&tensor, self.version_counter.clone());
 
         if self.requires_grad
             && tensor.grad_fn().is_none()
@@ -1,7 +1,7 @@
 use crate::ops::*;
 use crate::tensor::*;
 use std::cell::{Cell, RefCell};
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::rc::{Rc, Weak};
 
 pub struct ReadyQueue {
@@ -27,6 +27,21 @@ impl ReadyQueue {
     }
 }
 
+// Used by autograd::grad to only run the part of the graph which leads to the
+// requested inputs, and to capture the gradients flowing into them.
+#[derive(Default)]
+pub struct ExecInfo {
+    pub needed: bool,
+    // (input_nr, index into GraphTask::captured_vars)
+    pub captures: Vec<(usize, usize)>,
+}
+
+impl ExecInfo {
+    pub fn should_execute(&self) -> bool {
+        self.needed || !self.captures.is_empty()
+    }
+}
+
 pub struct GraphTask {
     pub dependencies: RefCell<HashMap<*const Node, usize>>,
     pub depth: usize,
@@ -34,6 +49,8 @@ pub struct GraphTask {
     pub outstanding_tasks: Cell<u32>,
     pub grad_mode: bool,
     pub not_ready_queue: RefCell<HashMap<*const Node, InputBuffer>>,
+    pub exec_info: RefCell<HashMap<*const Node, ExecInfo>>,
+    pub captured_vars: RefCell<VariableList>,
 }
 
 // Todo: Use ReadyQueue instead of VecDeque for push logic which increaments outstanding_task.
@@ -48,6 +65,55 @@ impl GraphTask {
             outstanding_tasks: Cell::new(0),
             grad_mode,
             not_ready_queue: RefCell::new(not_ready_queue),
+            exec_info: RefCell::new(HashMap::new()),
+            captured_vars: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn init_to_execute(&self, graph_root: &Node, outputs: &EdgeList) {
+        let mut exec_info = self.exec_info.borrow_mut();
+        for (output_idx, output_edge) in outputs.iter().enumerate() {
+            let output = output_edge
+                .function()
+                .expect("One of the differentiated Tensors does not require grad")
+                .as_ptr() as *const Node;
+            exec_info
+                .entry(output)
+                .or_default()
+                .captures
+                .push((output_edge.input_nr, output_idx));
+        }
+        *self.captured_vars.borrow_mut() = vec![Tensor::default(); outputs.len()];
+
+        // Post-order walk which marks every node that leads to a captured one.
+        let mut visited: HashSet<*const Node> = HashSet::new();
+        let mut stack: Vec<(*const Node, usize)> = vec![(graph_root as *const Node, 0)];
+        visited.insert(graph_root);
+        while let Some((fn_, child)) = stack.pop() {
+            let node = unsafe { &*fn_ };
+            let next = node.next_edges().and_then(|e| e.get(child));
+            if let Some(edge) = next {
+                stack.push((fn_, child + 1));
+                if let Some(next_fn) = edge.function() {
+                    let next_ptr = next_fn.as_ptr() as *const Node;
+                    if visited.insert(next_ptr) {
+                        stack.push((next_ptr, 0));
+                    }
+                }
+            } else {
+                let needed = node.next_edges().is_some_and(|edges| {
+                    edges.iter().any(|e| {
+                        e.function().is_some_and(|f| {
+                            exec_info
+                                .get(&(f.as_ptr() as *const Node))
+                                .is_some_and(|info| info.should_execute())
+                        })
+                    })
+                });
+                if needed {
+                    exec_info.entry(fn_).or_default().needed = true;
+                }
+            }
         }
     }
 
@@ -126,3 +126,37 @@ pub fn tensor<A: Into<Option<TensorOptions>>>(
     );
     result
 }
+
+// A sparse tensor with values[k] at the coordinates in column k of the int64 indices.
+pub fn sparse_coo_tensor<A: Into<Option<TensorOptions>>>(
+    indices: &Tensor,
+    values: &Tensor,
+    size: &[usize],
+    options: A,
+) -> Tensor {
+    let options = get_options(options);
+    let values = match options.dtype_opt() {
+        Some(dtype) => values.to_dtype(crate::c10::type_meta_to_scalar_type(&dtype)),
+        None => values.clone(),
+    };
+    let tensor = native::sparse_coo_tensor(indices, &values, size);
+    make_variable(tensor, options.requires_grad())
+}
+
+// A sparse CSR matrix, row i has values[crow_indices[i]..crow_indices[i + 1]] at the
+// columns in the same range of col_indices.
+pub fn sparse_csr_tensor<A: Into<Option<TensorOptions>>>(
+    crow_indices: &Tensor,
+    col_indices: &Tensor,
+    values: &Tensor,
+    size: &[usize],
+    options: A,
+) -> Tensor {
+    let options = get_options(options);
+    let values = match options.dtype_opt() {
+        Some(dtype) => values.to_dtype(crate::c10::type_meta_to_scalar_type(&dtype)),
+        None => values.clone(),
+    };
+    let tensor = native::sparse_csr_tensor(crow_indices, col_indices, &values, size);
+    make_variable(tensor, options.requires_grad())
+}
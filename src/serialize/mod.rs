@@ -0,0 +1,210 @@
+pub mod npy;
+pub mod safetensors;
+
+use crate::aten::native;
+use crate::c10::{elementSize, ScalarType, TensorOptions};
+use crate::tensor::Tensor;
+use crate::util::OrderedDict;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Take, Write};
+use std::path::Path;
+
+// Checkpoint layout, all integers little endian:
+//   magic "ROVOCKPT", format version (u32), number of entries (u64), then per entry:
+//   key length (u64), key (utf-8), dtype (u8), ndim (u64), sizes (ndim x u64),
+//   strides (ndim x u64), data length in bytes (u64), data.
+// The data covers every element the strides can reach, so tensors are restored with
+// the same strides they were saved with.
+const MAGIC: &[u8; 8] = b"ROVOCKPT";
+const FORMAT_VERSION: u32 = 1;
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+// Codes are part of the file format and must not change when dtypes are added.
+fn dtype_code(scalar_type: ScalarType) -> u8 {
+    match scalar_type {
+        ScalarType::Int => 0,
+        ScalarType::Long => 1,
+        ScalarType::Float => 2,
+        ScalarType::Double => 3,
+        ScalarType::Half => 4,
+        ScalarType::BFloat16 => 5,
+        ScalarType::ComplexFloat => 6,
+        ScalarType::ComplexDouble => 7,
+        ScalarType::Byte => 8,
+        ScalarType::Char => 9,
+        ScalarType::Short => 10,
+        ScalarType::Bool => 11,
+        _ => panic!("Saving tensors of dtype {:?} is not supported", scalar_type),
+    }
+}
+
+fn dtype_from_code(code: u8) -> io::Result<ScalarType> {
+    match code {
+        0 => Ok(ScalarType::Int),
+        1 => Ok(ScalarType::Long),
+        2 => Ok(ScalarType::Float),
+        3 => Ok(ScalarType::Double),
+        4 => Ok(ScalarType::Half),
+        5 => Ok(ScalarType::BFloat16),
+        6 => Ok(ScalarType::ComplexFloat),
+        7 => Ok(ScalarType::ComplexDouble),
+        8 => Ok(ScalarType::Byte),
+        9 => Ok(ScalarType::Char),
+        10 => Ok(ScalarType::Short),
+        11 => Ok(ScalarType::Bool),
+        _ => Err(invalid_data(format!("unknown dtype code {}", code))),
+    }
+}
+
+// Bytes other than 0 and 1 are not valid bools, reading them as such is undefined
+// behavior.
+fn check_bool_data(scalar_type: ScalarType, data: &[u8]) -> io::Result<()> {
+    if scalar_type == ScalarType::Bool && data.iter().any(|b| *b > 1) {
+        return Err(invalid_data(
+            "bool data contains values other than 0 and 1".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// Number of bytes from the first element of a tensor to the end of the last element
+// its strides can reach, None if that overflows.
+fn storage_span(sizes: &[usize], strides: &[usize], element_size: usize) -> Option<usize> {
+    if sizes.contains(&0) {
+        return Some(0);
+    }
+    let mut last = 0usize;
+    for (size, stride) in sizes.iter().zip(strides.iter()) {
+        last = last.checked_add((size - 1).checked_mul(*stride)?)?;
+    }
+    last.checked_add(1)?.checked_mul(element_size)
+}
+
+fn write_u64<W: Write>(writer: &mut W, value: usize) -> io::Result<()> {
+    writer.write_all(&(value as u64).to_le_bytes())
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<usize> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes) as usize)
+}
+
+// Reads the length of something taking `len * item_size` bytes and checks that the
+// file has that many bytes left, so a corrupt length is an error rather than a huge
+// allocation.
+fn read_len<R: Read>(reader: &mut Take<R>, item_size: usize, what: &str) -> io::Result<usize> {
+    let len = read_u64(reader)?;
+    match len.checked_mul(item_size) {
+        Some(nbytes) if nbytes as u64 <= reader.limit() => Ok(len),
+        _ => Err(invalid_data(format!(
+            "{} {} doesn't fit in the {} bytes left in the file",
+            what,
+            len,
+            reader.limit()
+        ))),
+    }
+}
+
+pub fn write_tensor<W: Write>(writer: &mut W, tensor: &Tensor) -> io::Result<()> {
+    writer.write_all(&[dtype_code(tensor.scalar_type())])?;
+    write_u64(writer, tensor.sizes().len())?;
+    for &size in tensor.sizes() {
+        write_u64(writer, size)?;
+    }
+    for &stride in tensor.strides() {
+        write_u64(writer, stride)?;
+    }
+    let nbytes = storage_span(tensor.sizes(), tensor.strides(), tensor.element_size()).unwrap();
+    write_u64(writer, nbytes)?;
+    let data = unsafe { std::slice::from_raw_parts(tensor.data_ptr_casted::<u8>(), nbytes) };
+    writer.write_all(data)
+}
+
+// `reader` is limited to the rest of the file, lengths read from it are checked
+// against that before anything is allocated.
+pub fn read_tensor<R: Read>(reader: &mut Take<R>) -> io::Result<Tensor> {
+    let mut code = [0u8; 1];
+    reader.read_exact(&mut code)?;
+    let scalar_type = dtype_from_code(code[0])?;
+    // Each dimension has a size and a stride.
+    let ndim = read_len(reader, 16, "number of dimensions")?;
+    let sizes = (0..ndim)
+        .map(|_| read_u64(reader))
+        .collect::<io::Result<Vec<_>>>()?;
+    let strides = (0..ndim)
+        .map(|_| read_u64(reader))
+        .collect::<io::Result<Vec<_>>>()?;
+    let nbytes = read_len(reader, 1, "data length")?;
+    let expected = storage_span(&sizes, &strides, elementSize(scalar_type));
+    if sizes
+        .iter()
+        .try_fold(1usize, |n, &size| n.checked_mul(size))
+        .is_none()
+        || expected != Some(nbytes)
+    {
+        return Err(invalid_data(format!(
+            "{} bytes of data don't match a tensor of size {:?} and strides {:?}",
+            nbytes, sizes, strides
+        )));
+    }
+    let tensor = native::empty_strided_cpu(
+        sizes.as_slice(),
+        strides.as_slice(),
+        TensorOptions::with_dtype(scalar_type),
+    );
+    let data = unsafe { std::slice::from_raw_parts_mut(tensor.data_ptr_casted::<u8>(), nbytes) };
+    reader.read_exact(data)?;
+    check_bool_data(scalar_type, data)?;
+    Ok(tensor)
+}
+
+// Writes `tensors`, e.g. a state_dict, to `path` in the checkpoint format.
+pub fn save<P: AsRef<Path>>(tensors: &OrderedDict<String, Tensor>, path: P) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(MAGIC)?;
+    writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+    write_u64(&mut writer, tensors.size())?;
+    for (key, tensor) in tensors.iter() {
+        write_u64(&mut writer, key.len())?;
+        writer.write_all(key.as_bytes())?;
+        write_tensor(&mut writer, tensor)?;
+    }
+    writer.flush()
+}
+
+// Reads a checkpoint written by `save`, keeping the order of the entries.
+pub fn load<P: AsRef<Path>>(path: P) -> io::Result<OrderedDict<String, Tensor>> {
+    let file = File::open(path)?;
+    let file_size = file.metadata()?.len();
+    let mut reader = BufReader::new(file).take(file_size);
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(invalid_data("not a rovo checkpoint".to_string()));
+    }
+    let mut version = [0u8; 4];
+    reader.read_exact(&mut version)?;
+    let version = u32::from_le_bytes(version);
+    if version > FORMAT_VERSION {
+        return Err(invalid_data(format!(
+            "checkpoint format version {} is newer than the supported version {}",
+            version, FORMAT_VERSION
+        )));
+    }
+    let mut result = OrderedDict::new_with_key_description("Tensor".to_string());
+    for _ in 0..read_u64(&mut reader)? {
+        let mut key = vec![0u8; read_len(&mut reader, 1, "key length")?];
+        reader.read_exact(&mut key)?;
+        let key = String::from_utf8(key).map_err(|e| invalid_data(e.to_string()))?;
+        if result.contains(&key) {
+            return Err(invalid_data(format!("duplicate key {:?}", key)));
+        }
+        let tensor = read_tensor(&mut reader)?;
+        result.insert(key, tensor);
+    }
+    Ok(result)
+}
@@ -0,0 +1,325 @@
+use crate::aten::native;
+use crate::c10::{elementSize, to_value_type, ScalarType, TensorOptions};
+use crate::tensor::Tensor;
+use crate::util::OrderedDict;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Seek, Write};
+use std::path::Path;
+use zip::{write::FileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+// .npy layout: magic "\x93NUMPY", major and minor version (u8 each), header length
+// (u16 for version 1, u32 for versions 2 and 3, little endian), then a python dict
+// literal like {'descr': '<f4', 'fortran_order': False, 'shape': (2, 3), } padded with
+// spaces and ending with '\n', then the raw data. .npz files are zip archives of .npy
+// files named after their keys.
+const MAGIC: &[u8; 6] = b"\x93NUMPY";
+// Header plus preamble is padded to a multiple of this, as NumPy does.
+const HEADER_ALIGNMENT: usize = 64;
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn descr(scalar_type: ScalarType) -> &'static str {
+    match scalar_type {
+        ScalarType::Byte => "|u1",
+        ScalarType::Char => "|i1",
+        ScalarType::Short => "<i2",
+        ScalarType::Bool => "|b1",
+        ScalarType::Int => "<i4",
+        ScalarType::Long => "<i8",
+        ScalarType::Float => "<f4",
+        ScalarType::Double => "<f8",
+        ScalarType::Half => "<f2",
+        ScalarType::ComplexFloat => "<c8",
+        ScalarType::ComplexDouble => "<c16",
+        _ => panic!(
+            "Saving tensors of dtype {:?} to npy is not supported",
+            scalar_type
+        ),
+    }
+}
+
+// Returns the dtype and whether the data has to be byte swapped.
+fn parse_descr(descr: &str) -> io::Result<(ScalarType, bool)> {
+    let unsupported = || invalid_data(format!("unsupported npy dtype '{}'", descr));
+    let mut chars = descr.chars();
+    let little_endian = match chars.next() {
+        Some('<') => true,
+        Some('>') => false,
+        Some('=') | Some('|') => cfg!(target_endian = "little"),
+        _ => return Err(unsupported()),
+    };
+    let scalar_type = match chars.as_str() {
+        "u1" => ScalarType::Byte,
+        "i1" => ScalarType::Char,
+        "i2" => ScalarType::Short,
+        "b1" => ScalarType::Bool,
+        "i4" => ScalarType::Int,
+        "i8" => ScalarType::Long,
+        "f4" => ScalarType::Float,
+        "f8" => ScalarType::Double,
+        "f2" => ScalarType::Half,
+        "c8" => ScalarType::ComplexFloat,
+        "c16" => ScalarType::ComplexDouble,
+        _ => return Err(unsupported()),
+    };
+    Ok((scalar_type, little_endian != cfg!(target_endian = "little")))
+}
+
+// Raw text of the value of `key` in the header dict, up to the next top level comma.
+fn header_value<'a>(header: &'a str, key: &str) -> io::Result<&'a str> {
+    let pattern = format!("'{}':", key);
+    let start = header
+        .find(&pattern)
+        .ok_or_else(|| invalid_data(format!("npy header is missing '{}'", key)))?
+        + pattern.len();
+    let rest = &header[start..];
+    let mut depth = 0;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' | '}' if depth == 0 => return Ok(rest[..i].trim()),
+            _ => {}
+        }
+    }
+    Err(invalid_data(format!("malformed npy header {}", header)))
+}
+
+struct Header {
+    scalar_type: ScalarType,
+    byte_swap: bool,
+    fortran_order: bool,
+    shape: Vec<usize>,
+}
+
+fn parse_header(header: &str) -> io::Result<Header> {
+    let descr = header_value(header, "descr")?;
+    let (scalar_type, byte_swap) = parse_descr(descr.trim_matches(|c| c == '\'' || c == '"'))?;
+    let fortran_order = match header_value(header, "fortran_order")? {
+        "True" => true,
+        "False" => false,
+        other => return Err(invalid_data(format!("invalid fortran_order {}", other))),
+    };
+    let shape = header_value(header, "shape")?;
+    let shape = shape
+        .strip_prefix('(')
+        .and_then(|s| s.strip_suffix(')'))
+        .ok_or_else(|| invalid_data(format!("invalid shape {}", shape)))?
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse()
+                .map_err(|_| invalid_data(format!("invalid shape {}", shape)))
+        })
+        .collect::<io::Result<Vec<usize>>>()?;
+    Ok(Header {
+        scalar_type,
+        byte_swap,
+        fortran_order,
+        shape,
+    })
+}
+
+// Strides of a column major (Fortran ordered) tensor of size `sizes`.
+fn fortran_strides(sizes: &[usize]) -> Vec<usize> {
+    let mut stride = 1;
+    sizes
+        .iter()
+        .map(|&size| {
+            let current = stride;
+            stride *= size.max(1);
+            current
+        })
+        .collect()
+}
+
+// Reads `len` bytes without trusting `len` to size the buffer up front, which grows with
+// the data actually read instead.
+fn read_bytes<R: Read>(reader: &mut R, len: usize, what: &str) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(invalid_data(format!(
+            "npy {} needs {} bytes but only {} are left",
+            what,
+            len,
+            bytes.len()
+        )));
+    }
+    Ok(bytes)
+}
+
+pub fn read_npy<R: Read>(reader: &mut R) -> io::Result<Tensor> {
+    let mut preamble = [0u8; 8];
+    reader.read_exact(&mut preamble)?;
+    if &preamble[..6] != MAGIC {
+        return Err(invalid_data("not a npy file".to_string()));
+    }
+    let header_len = match preamble[6] {
+        1 => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len)?;
+            u16::from_le_bytes(len) as usize
+        }
+        2 | 3 => {
+            let mut len = [0u8; 4];
+            reader.read_exact(&mut len)?;
+            u32::from_le_bytes(len) as usize
+        }
+        version => {
+            return Err(invalid_data(format!(
+                "unsupported npy format version {}",
+                version
+            )))
+        }
+    };
+    let header = read_bytes(reader, header_len, "header")?;
+    let header = String::from_utf8(header).map_err(|e| invalid_data(e.to_string()))?;
+    let header = parse_header(&header)?;
+
+    // Sizes of 0 count as 1, so the strides of every dimension fit as well.
+    let itemsize = elementSize(header.scalar_type);
+    header
+        .shape
+        .iter()
+        .try_fold(itemsize, |acc, &size| acc.checked_mul(size.max(1)))
+        .ok_or_else(|| {
+            invalid_data(format!(
+                "npy array of shape {:?} is too large",
+                header.shape
+            ))
+        })?;
+    let numel = header.shape.iter().product::<usize>();
+    let bytes = read_bytes(reader, numel * itemsize, "data")?;
+    let options = TensorOptions::with_dtype(header.scalar_type);
+    let tensor = if header.fortran_order {
+        native::empty_cpu(&[numel], options, None)
+    } else {
+        native::empty_cpu(&header.shape, options, None)
+    };
+    let data =
+        unsafe { std::slice::from_raw_parts_mut(tensor.data_ptr_casted::<u8>(), bytes.len()) };
+    data.copy_from_slice(&bytes);
+    if header.byte_swap {
+        // The real and imaginary parts of complex numbers are swapped separately.
+        data.chunks_exact_mut(elementSize(to_value_type(header.scalar_type)))
+            .for_each(|item| item.reverse());
+    }
+    super::check_bool_data(header.scalar_type, data)?;
+    if header.fortran_order {
+        Ok(tensor.as_strided(&header.shape, &fortran_strides(&header.shape)))
+    } else {
+        Ok(tensor)
+    }
+}
+
+fn format_header(tensor: &Tensor, fortran_order: bool) -> String {
+    let shape = match tensor.sizes() {
+        [size] => format!("({},)", size),
+        sizes => format!(
+            "({})",
+            sizes
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    format!(
+        "{{'descr': '{}', 'fortran_order': {}, 'shape': {}, }}",
+        descr(tensor.scalar_type()),
+        if fortran_order { "True" } else { "False" },
+        shape
+    )
+}
+
+// Writes `tensor` in C order, or in Fortran order if that's how its data is laid out,
+// e.g. for a transposed matrix, so the data can be written without copying.
+pub fn write_npy<W: Write>(writer: &mut W, tensor: &Tensor) -> io::Result<()> {
+    let is_fortran_contiguous = tensor.dim() > 1
+        && tensor
+            .sizes()
+            .iter()
+            .zip(tensor.strides().iter().zip(fortran_strides(tensor.sizes())))
+            .all(|(&size, (&stride, expected))| size == 1 || stride == expected);
+    let fortran_order = !tensor.is_contiguous() && is_fortran_contiguous;
+    let tensor = if tensor.is_contiguous() || fortran_order {
+        tensor.clone()
+    } else {
+        tensor.contiguous()
+    };
+
+    let mut header = format_header(&tensor, fortran_order).into_bytes();
+    let mut preamble_len = MAGIC.len() + 4;
+    if preamble_len + header.len() + 1 > u16::MAX as usize {
+        preamble_len += 2;
+    }
+    while !(preamble_len + header.len() + 1).is_multiple_of(HEADER_ALIGNMENT) {
+        header.push(b' ');
+    }
+    header.push(b'\n');
+    writer.write_all(MAGIC)?;
+    if preamble_len == MAGIC.len() + 4 {
+        writer.write_all(&[1, 0])?;
+        writer.write_all(&(header.len() as u16).to_le_bytes())?;
+    } else {
+        writer.write_all(&[2, 0])?;
+        writer.write_all(&(header.len() as u32).to_le_bytes())?;
+    }
+    writer.write_all(&header)?;
+    let nbytes = tensor.numel() * tensor.element_size();
+    let data = unsafe { std::slice::from_raw_parts(tensor.data_ptr_casted::<u8>(), nbytes) };
+    writer.write_all(data)
+}
+
+pub fn load_npy<P: AsRef<Path>>(path: P) -> io::Result<Tensor> {
+    read_npy(&mut BufReader::new(File::open(path)?))
+}
+
+pub fn save_npy<P: AsRef<Path>>(tensor: &Tensor, path: P) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_npy(&mut writer, tensor)?;
+    writer.flush()
+}
+
+pub fn read_npz<R: Read + Seek>(reader: R) -> io::Result<OrderedDict<String, Tensor>> {
+    let mut archive = ZipArchive::new(reader)?;
+    let mut result = OrderedDict::new_with_key_description("Tensor".to_string());
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let name = file.name();
+        let key = name.strip_suffix(".npy").unwrap_or(name).to_string();
+        if result.contains(&key) {
+            return Err(invalid_data(format!("duplicate npz member {:?}", key)));
+        }
+        let tensor = read_npy(&mut file)?;
+        result.insert(key, tensor);
+    }
+    Ok(result)
+}
+
+// Like numpy.savez, entries are stored uncompressed.
+pub fn write_npz<W: Write + Seek>(
+    writer: W,
+    tensors: &OrderedDict<String, Tensor>,
+) -> io::Result<()> {
+    let mut zip = ZipWriter::new(writer);
+    let options = FileOptions::default().compression_method(CompressionMethod::Stored);
+    for (key, tensor) in tensors.iter() {
+        zip.start_file(format!("{}.npy", key), options)?;
+        write_npy(&mut zip, tensor)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+pub fn load_npz<P: AsRef<Path>>(path: P) -> io::Result<OrderedDict<String, Tensor>> {
+    read_npz(BufReader::new(File::open(path)?))
+}
+
+pub fn save_npz<P: AsRef<Path>>(tensors: &OrderedDict<String, Tensor>, path: P) -> io::Result<()> {
+    write_npz(File::create(path)?, tensors)
+}
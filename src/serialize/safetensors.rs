@@ -0,0 +1,210 @@
+use crate::aten::native;
+use crate::c10::{MappedFile, ScalarType, TensorOptions, TypeMeta};
+use crate::tensor::Tensor;
+use crate::util::OrderedDict;
+use serde_json::{json, Map, Value};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+// safetensors layout: header length (u64, little endian), a JSON header mapping every
+// tensor name to {"dtype", "shape", "data_offsets": [begin, end]}, then the raw
+// contiguous little endian data. Offsets are relative to the end of the header. An
+// optional "__metadata__" entry holds string to string metadata.
+const METADATA_KEY: &str = "__metadata__";
+// Guards against allocating a huge buffer for a corrupted header length.
+const MAX_HEADER_SIZE: usize = 100_000_000;
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn dtype_name(scalar_type: ScalarType) -> &'static str {
+    match scalar_type {
+        ScalarType::Int => "I32",
+        ScalarType::Long => "I64",
+        ScalarType::Float => "F32",
+        ScalarType::Double => "F64",
+        ScalarType::Half => "F16",
+        ScalarType::BFloat16 => "BF16",
+        ScalarType::ComplexFloat => "C64",
+        ScalarType::Byte => "U8",
+        ScalarType::Char => "I8",
+        ScalarType::Short => "I16",
+        ScalarType::Bool => "BOOL",
+        _ => panic!(
+            "Saving tensors of dtype {:?} to safetensors is not supported",
+            scalar_type
+        ),
+    }
+}
+
+fn dtype_from_name(name: &str) -> io::Result<ScalarType> {
+    match name {
+        "I32" => Ok(ScalarType::Int),
+        "I64" => Ok(ScalarType::Long),
+        "F32" => Ok(ScalarType::Float),
+        "F64" => Ok(ScalarType::Double),
+        "F16" => Ok(ScalarType::Half),
+        "BF16" => Ok(ScalarType::BFloat16),
+        "C64" => Ok(ScalarType::ComplexFloat),
+        "U8" => Ok(ScalarType::Byte),
+        "I8" => Ok(ScalarType::Char),
+        "I16" => Ok(ScalarType::Short),
+        "BOOL" => Ok(ScalarType::Bool),
+        _ => Err(invalid_data(format!(
+            "unsupported safetensors dtype {}",
+            name
+        ))),
+    }
+}
+
+// Writes `tensors` to `path`. Tensors are stored contiguous in the order of the dict.
+pub fn save<P: AsRef<Path>>(tensors: &OrderedDict<String, Tensor>, path: P) -> io::Result<()> {
+    let contiguous: Vec<_> = tensors.values().map(|t| t.contiguous()).collect();
+    let mut header = Map::new();
+    let mut offset = 0;
+    for (key, tensor) in tensors.keys().zip(contiguous.iter()) {
+        let nbytes = tensor.numel() * tensor.element_size();
+        header.insert(
+            key.clone(),
+            json!({
+                "dtype": dtype_name(tensor.scalar_type()),
+                "shape": tensor.sizes(),
+                "data_offsets": [offset, offset + nbytes],
+            }),
+        );
+        offset += nbytes;
+    }
+    let mut header = Value::Object(header).to_string().into_bytes();
+    // Pad with spaces so the data of every dtype starts aligned.
+    while !header.len().is_multiple_of(8) {
+        header.push(b' ');
+    }
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(&(header.len() as u64).to_le_bytes())?;
+    writer.write_all(&header)?;
+    for tensor in &contiguous {
+        let nbytes = tensor.numel() * tensor.element_size();
+        let data = unsafe { std::slice::from_raw_parts(tensor.data_ptr_casted::<u8>(), nbytes) };
+        writer.write_all(data)?;
+    }
+    writer.flush()
+}
+
+struct Entry {
+    key: String,
+    scalar_type: ScalarType,
+    shape: Vec<usize>,
+    begin: usize,
+    end: usize,
+}
+
+fn parse_entry(key: &str, info: &Value) -> io::Result<Entry> {
+    let field_error = |field: &str| invalid_data(format!("invalid {} for tensor {}", field, key));
+    let scalar_type = dtype_from_name(
+        info.get("dtype")
+            .and_then(Value::as_str)
+            .ok_or_else(|| field_error("dtype"))?,
+    )?;
+    let shape = info
+        .get("shape")
+        .and_then(Value::as_array)
+        .ok_or_else(|| field_error("shape"))?
+        .iter()
+        .map(|s| s.as_u64().map(|s| s as usize))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| field_error("shape"))?;
+    let offsets = info
+        .get("data_offsets")
+        .and_then(Value::as_array)
+        .filter(|o| o.len() == 2)
+        .and_then(|o| Some((o[0].as_u64()? as usize, o[1].as_u64()? as usize)))
+        .ok_or_else(|| field_error("data_offsets"))?;
+    Ok(Entry {
+        key: key.to_string(),
+        scalar_type,
+        shape,
+        begin: offsets.0,
+        end: offsets.1,
+    })
+}
+
+// Reads a safetensors file, ordering the tensors by their position in the file.
+// The file is memory mapped and tensors point straight into the mapping, unless their
+// data isn't aligned to the dtype, in which case it's copied.
+pub fn load<P: AsRef<Path>>(path: P) -> io::Result<OrderedDict<String, Tensor>> {
+    let file = Rc::new(MappedFile::open(path)?);
+    let bytes = file.as_slice();
+    if bytes.len() < 8 {
+        return Err(invalid_data(
+            "file too small for a safetensors header".to_string(),
+        ));
+    }
+    let mut header_len = [0u8; 8];
+    header_len.copy_from_slice(&bytes[..8]);
+    let header_len = u64::from_le_bytes(header_len) as usize;
+    if header_len > MAX_HEADER_SIZE || 8 + header_len > bytes.len() {
+        return Err(invalid_data(format!(
+            "invalid safetensors header length {}",
+            header_len
+        )));
+    }
+    let header: Value = serde_json::from_slice(&bytes[8..8 + header_len])
+        .map_err(|e| invalid_data(e.to_string()))?;
+    let header = header
+        .as_object()
+        .ok_or_else(|| invalid_data("safetensors header is not a JSON object".to_string()))?;
+
+    let mut entries = header
+        .iter()
+        .filter(|(key, _)| key.as_str() != METADATA_KEY)
+        .map(|(key, info)| parse_entry(key, info))
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|e| e.begin);
+
+    let data_start = 8 + header_len;
+    let data_len = bytes.len() - data_start;
+    let mut result = OrderedDict::new_with_key_description("Tensor".to_string());
+    for entry in entries {
+        let dtype: TypeMeta = entry.scalar_type.into();
+        // Sizes of 0 count as 1, so the strides of every dimension fit as well.
+        let fits = entry
+            .shape
+            .iter()
+            .try_fold(dtype.itemsize(), |acc, &size| acc.checked_mul(size.max(1)))
+            .is_some();
+        if !fits {
+            return Err(invalid_data(format!(
+                "tensor {} of shape {:?} is too large",
+                entry.key, entry.shape
+            )));
+        }
+        let nbytes = entry.shape.iter().product::<usize>() * dtype.itemsize();
+        if entry.begin > entry.end || entry.end > data_len || entry.end - entry.begin != nbytes {
+            return Err(invalid_data(format!(
+                "data_offsets [{}, {}] of tensor {} don't match its shape {:?} and dtype {:?}",
+                entry.begin, entry.end, entry.key, entry.shape, entry.scalar_type
+            )));
+        }
+        let offset = data_start + entry.begin;
+        super::check_bool_data(entry.scalar_type, &bytes[offset..offset + nbytes])?;
+        let tensor = if offset.is_multiple_of(dtype.itemsize()) {
+            native::from_mapped_file(&file, offset, &entry.shape, entry.scalar_type)?
+        } else {
+            let tensor = native::empty_cpu(
+                &entry.shape,
+                TensorOptions::with_dtype(entry.scalar_type),
+                None,
+            );
+            let data =
+                unsafe { std::slice::from_raw_parts_mut(tensor.data_ptr_casted::<u8>(), nbytes) };
+            data.copy_from_slice(&bytes[offset..offset + nbytes]);
+            tensor
+        };
+        result.insert(entry.key, tensor);
+    }
+    Ok(result)
+}
@@ -0,0 +1,26 @@
+use crate::tensor::{self, Tensor};
+
+// Product of a sparse COO or CSR matrix and a strided one, which is strided. Gradients
+// flow to the strided operand only.
+pub fn mm(sparse: &Tensor, dense: &Tensor) -> Tensor {
+    assert!(
+        sparse.is_sparse() || sparse.is_sparse_csr(),
+        "sparse::mm expects a sparse first operand"
+    );
+    tensor::mm(sparse, dense, false)
+}
+
+// Product of a sparse matrix and a strided vector.
+pub fn mv(sparse: &Tensor, vec: &Tensor) -> Tensor {
+    assert_eq!(vec.dim(), 1, "sparse::mv expects a 1-D vector");
+    mm(sparse, &vec.view(&[vec.size(0), 1])).view(&[sparse.size(0)])
+}
+
+// Sum of all the values of a sparse tensor.
+pub fn sum(sparse: &Tensor) -> Tensor {
+    assert!(
+        sparse.is_sparse() || sparse.is_sparse_csr(),
+        "sparse::sum expects a sparse tensor"
+    );
+    tensor::sum(sparse, None)
+}
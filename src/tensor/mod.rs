@@ -20,8 +20,33 @@ pub type EdgeList = Vec<Edge>;
 //Improve debug impl
 impl std::fmt::Debug for Tensor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.defined() {
-            let tensor = self.to_dtype(crate::c10::ScalarType::Double);
+        if self.is_sparse() {
+            write!(
+                f,
+                "Sparse Tensor: indices: {:?}\tvalues: {:?}\tsize: {:?}\tnnz: {}",
+                self.indices(),
+                self.values(),
+                self.sizes(),
+                self.nnz()
+            )
+        } else if self.is_sparse_csr() {
+            write!(
+                f,
+                "Sparse CSR Tensor: crow_indices: {:?}\tcol_indices: {:?}\tvalues: {:?}\tsize: {:?}",
+                self.crow_indices(),
+                self.col_indices(),
+                self.values(),
+                self.sizes()
+            )
+        } else if self.defined() {
+            use crate::c10::{isIntegralType, is_complex_type, ScalarType};
+            let dtype = match self.scalar_type() {
+                ScalarType::Bool => ScalarType::Bool,
+                t if isIntegralType(t, false) => ScalarType::Long,
+                t if is_complex_type(t) => ScalarType::ComplexDouble,
+                _ => ScalarType::Double,
+            };
+            let tensor = self.to_dtype(dtype).contiguous();
             write!(
                 f,
                 "Tensor: {:?}\tsize: {:?}",
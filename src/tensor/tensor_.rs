@@ -4,14 +4,17 @@ use native::wrapped_scalar_tensor;
 use super::tensor_ops;
 use crate::aten::{self, native};
 use crate::c10::{
-    type_meta_to_scalar_type, Device, Layout, MemoryFormat, Scalar, ScalarType, Storage,
-    TensorOptions, TypeMeta,
+    get_channels_last_strides_2d, get_channels_last_strides_3d, type_meta_to_scalar_type, Device,
+    Layout, MappedFile, MemoryFormat, Scalar, ScalarType, Storage, TensorOptions, TypeMeta,
 };
 use crate::core::Generator;
 use crate::ops::*;
+use crate::serialize;
 use crate::tensor::*;
-use crate::util_autograd::TensorHook;
+use crate::util_autograd::{increment_version, TensorHook};
 use std::cell::RefCell;
+use std::io;
+use std::path::Path;
 use std::rc::Rc;
 use std::{ffi::c_void, ptr::NonNull};
 
@@ -47,9 +50,6 @@ pub struct Tensor {
     pub _impl: Rc<RefCell<Option<TensorImpl>>>,
 }
 
-unsafe impl Send for Tensor {}
-unsafe impl Sync for Tensor {}
-
 // impl Default for Tensor {
 //     fn default() -> Self {
 //         let impl_ = SINGLETON.get_or_init(|| Self::from_impl(TensorImpl::undefined_instance()));
@@ -126,6 +126,10 @@ impl Tensor {
         if !self.defined() {
             panic!("strides() called on an undefined Tensor");
         }
+        assert!(
+            self.layout() == Layout::Strided,
+            "sparse tensors do not have strides"
+        );
         self.get_unsafe_tensor_impl().strides()
     }
 
@@ -196,6 +200,10 @@ impl Tensor {
         aten::native::contiguous(self)
     }
 
+    pub fn contiguous_(&self, memory_format: MemoryFormat) -> Self {
+        aten::native::contiguous_(self, memory_format)
+    }
+
     pub fn element_size(&self) -> usize {
         self.get_unsafe_tensor_impl().itemsize()
     }
@@ -264,6 +272,16 @@ impl Tensor {
         }
     }
 
+    // Whether the tensor was created in InferenceMode.
+    pub fn is_inference(&self) -> bool {
+        self.get_unsafe_tensor_impl().is_inference()
+    }
+
+    // Number of times the tensor, or any view of its storage, was modified in place.
+    pub fn _version(&self) -> u32 {
+        TensorHook::version_counter(self).current_version()
+    }
+
     pub fn is_non_overlapping_and_dense(&self) -> bool {
         self.get_unsafe_tensor_impl().is_non_overlapping_and_dense()
     }
@@ -309,6 +327,93 @@ impl Tensor {
         self.get_unsafe_tensor_impl().layout()
     }
 
+    pub fn is_sparse(&self) -> bool {
+        self.defined() && self.get_unsafe_tensor_impl().is_sparse()
+    }
+
+    // The sparse_dim x nnz int64 coordinates of the values of a sparse tensor.
+    pub fn indices(&self) -> Self {
+        self.get_unsafe_tensor_impl().sparse_parts().indices.clone()
+    }
+
+    pub fn values(&self) -> Self {
+        if self.is_sparse_csr() {
+            return self
+                .get_unsafe_tensor_impl()
+                .sparse_csr_parts()
+                .values
+                .clone();
+        }
+        self.get_unsafe_tensor_impl().sparse_parts().values.clone()
+    }
+
+    pub fn nnz(&self) -> usize {
+        if self.is_sparse_csr() {
+            return self.values().size(0);
+        }
+        native::nnz(self)
+    }
+
+    pub fn is_sparse_csr(&self) -> bool {
+        self.defined() && self.get_unsafe_tensor_impl().is_sparse_csr()
+    }
+
+    // Offsets of the rows of a sparse CSR matrix into col_indices() and values().
+    pub fn crow_indices(&self) -> Self {
+        self.get_unsafe_tensor_impl()
+            .sparse_csr_parts()
+            .crow_indices
+            .clone()
+    }
+
+    pub fn col_indices(&self) -> Self {
+        self.get_unsafe_tensor_impl()
+            .sparse_csr_parts()
+            .col_indices
+            .clone()
+    }
+
+    pub fn to_sparse_csr(&self) -> Self {
+        native::to_sparse_csr(self)
+    }
+
+    pub fn sparse_dim(&self) -> usize {
+        native::sparse_dim(self)
+    }
+
+    pub fn dense_dim(&self) -> usize {
+        native::dense_dim(self)
+    }
+
+    pub fn is_coalesced(&self) -> bool {
+        self.get_unsafe_tensor_impl().sparse_parts().coalesced
+    }
+
+    pub fn coalesce(&self) -> Self {
+        native::coalesce(self)
+    }
+
+    pub fn to_sparse(&self) -> Self {
+        if self.is_sparse_csr() {
+            return native::sparse_csr_to_sparse(self);
+        }
+        self.to_sparse_dim(self.ndimension())
+    }
+
+    pub fn to_sparse_dim(&self, sparse_dim: usize) -> Self {
+        native::dense_to_sparse(self, sparse_dim)
+    }
+
+    pub fn to_dense(&self) -> Self {
+        if self.is_sparse() {
+            native::sparse_to_dense(self)
+        } else if self.is_sparse_csr() {
+            native::sparse_csr_to_dense(self)
+        } else {
+            self.clone()
+        }
+    }
+
     pub fn uniform(&self, from: f64, to: f64) {
         crate::aten::native::distribution_templates::uniform_impl_(self, from, to, None);
     }
@@ -316,8 +421,12 @@ impl Tensor {
     pub fn uniform_with_gen(&self, from: f64, to: f64, gen: Option<Generator>) {
         crate::aten::native::distribution_templates::uniform_impl_(self, from, to, gen);
     }
-    pub fn unsqueeze(&self, _i: usize) -> Self {
-        todo!()
+
+    pub fn normal(&self, mean: f64, std: f64) {
+        crate::aten::native::distribution_templates::normal_impl_(self, mean, std, None);
+    }
+    pub fn unsqueeze(&self, dim: usize) -> Self {
+        tensor_ops::unsqueeze(self, dim as i64)
     }
 
     pub fn randn(_dims: &[usize]) -> Self {
@@ -362,12 +471,61 @@ impl Tensor {
 
     pub fn add_with_alpha_(&self, other: &Tensor, scalar: impl Into<Scalar>) {
         native::add_out(self, self, other, scalar.into());
+        increment_version(self);
+    }
+
+    pub fn exp(&self) -> Self {
+        tensor_ops::exp(self)
     }
 
     pub fn sum(&self) -> Self {
         tensor_ops::sum(self, None)
     }
 
+    pub fn real(&self) -> Self {
+        tensor_ops::real(self)
+    }
+
+    pub fn imag(&self) -> Self {
+        tensor_ops::imag(self)
+    }
+
+    pub fn conj(&self) -> Self {
+        tensor_ops::conj(self)
+    }
+
+    pub fn abs(&self) -> Self {
+        tensor_ops::abs(self)
+    }
+
+    pub fn angle(&self) -> Self {
+        tensor_ops::angle(self)
+    }
+
+    // Signs of real values and z / |z| for complex ones, not differentiable.
+    pub fn sgn(&self) -> Self {
+        native::sgn(self)
+    }
+
+    // Division rounded with `rounding_mode`, not differentiable. Integer inputs give
+    // integer results when the division is rounded.
+    pub fn div_mode(&self, other: &Tensor, rounding_mode: Option<native::RoundingMode>) -> Self {
+        native::div_mode(self, other, rounding_mode)
+    }
+
+    pub fn floor_divide(&self, other: &Tensor) -> Self {
+        native::floor_divide(self, other)
+    }
+
+    // Views between complex tensors and real ones with a last dimension of size 2.
+    pub fn view_as_real(&self) -> Self {
+        tensor_ops::view_as_real(self)
+    }
+
+    pub fn view_as_complex(&self) -> Self {
+        tensor_ops::view_as_complex(self)
+    }
+
     pub fn mean(&self) -> Self {
         tensor_ops::mean(self)
     }
@@ -381,7 +539,7 @@ impl Tensor {
     /// Implicit flag is false by default, and is only true for
     /// oprations which broadcasts tensor implicitly.
     pub fn expand(&self, size: &[usize], implicit: bool) -> Tensor {
-        aten::native::expand(self, size, implicit)
+        tensor_ops::expand(self, size, implicit)
     }
 
     pub fn detach_(&mut self) -> &Self {
@@ -393,11 +551,18 @@ impl Tensor {
     }
 
     pub fn zero_(&mut self) {
-        self.fill_(0.0)
+        if self.is_sparse() {
+            native::zero_sparse_(self);
+        } else if self.is_sparse_csr() {
+            native::zero_sparse_csr_(self);
+        } else {
+            self.fill_(0.0)
+        }
     }
 
     pub fn mul_<A: AsRef<Self>>(&mut self, other: A) {
         native::mul_out(self, self, other.as_ref());
+        increment_version(self);
     }
 
     pub fn mul_scalar<S: Into<Scalar>>(&mut self, other: S) {
@@ -406,6 +571,7 @@ impl Tensor {
 
     pub fn div_<A: AsRef<Self>>(&mut self, other: A) {
         native::div_out(self, self, other.as_ref());
+        increment_version(self);
     }
 
     pub fn div_scalar<S: Into<Scalar>>(&mut self, other: S) {
@@ -413,7 +579,7 @@ impl Tensor {
     }
 
     pub fn view(&self, shape: &[usize]) -> Self {
-        aten::native::view(self, shape)
+        tensor_ops::view(self, shape)
     }
 
     pub fn options(&self) -> TensorOptions {
@@ -421,34 +587,85 @@ impl Tensor {
         options
             .set_dtype(*self.dtype())
             .set_device(self.device())
-            .set_layout(self.layout());
-        options
+            .set_layout(self.layout())
     }
 
     pub fn to_dtype(&self, dtype: ScalarType) -> Self {
         native::to_dtype(self, dtype, false, false, None)
     }
 
+    // Reads a NumPy .npy file. Fortran ordered arrays keep their column major strides.
+    pub fn from_npy<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        serialize::npy::load_npy(path)
+    }
+
+    pub fn to_npy<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        serialize::npy::save_npy(self, path)
+    }
+
+    // Contiguous tensor of `shape` reading its data from `path`, starting `offset` bytes
+    // into the file. The file is memory mapped, so only the pages that are accessed are
+    // loaded. Writes to the tensor are not written back to the file.
+    pub fn from_file_mmap<P: AsRef<Path>>(
+        path: P,
+        dtype: ScalarType,
+        shape: &[usize],
+        offset: usize,
+    ) -> io::Result<Self> {
+        let file = MappedFile::open(path)?;
+        native::from_mapped_file(&Rc::new(file), offset, shape, dtype)
+    }
+
+    // Like `from_file_mmap`, but writes to the tensor go to the file, which is created or
+    // grown to fit the tensor if needed. This allows writing a dataset incrementally, e.g.
+    // by copying batches into slices of the tensor.
+    pub fn from_file_mmap_writable<P: AsRef<Path>>(
+        path: P,
+        dtype: ScalarType,
+        shape: &[usize],
+        offset: usize,
+    ) -> io::Result<Self> {
+        let len = shape
+            .iter()
+            .try_fold(TypeMeta::from(dtype).itemsize(), |acc, &s| {
+                acc.checked_mul(s)
+            })
+            .and_then(|nbytes| nbytes.checked_add(offset))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "a tensor of shape {:?} and dtype {:?} at offset {} is too large",
+                        shape, dtype, offset
+                    ),
+                )
+            })?;
+        let file = MappedFile::open_writable(path, len)?;
+        native::from_mapped_file(&Rc::new(file), offset, shape, dtype)
+    }
+
     /// Defaults:
     ///     channels_last_strides_exact_match: false
-    pub fn suggest_memory_format(&self, _channels_last_strides_exact_match: bool) -> MemoryFormat {
+    pub fn suggest_memory_format(&self, channels_last_strides_exact_match: bool) -> MemoryFormat {
         // Setting channels_last_strides_exact_match to true forces function to
         // check 0,1 - sized dimension strides.
-        //   if !self.is_mkldnn() && !self.is_sparse() {
-        //     if (impl_->is_strides_like_channels_last()) {
-        //       if (!channels_last_strides_exact_match ||
-        //           get_channels_last_strides_2d(sizes()) == strides()) {
-        //         return at::MemoryFormat::ChannelsLast;
-        //       }
-        //     }
-        //     else if (impl_->is_strides_like_channels_last_3d()) {
-        //       if (!channels_last_strides_exact_match ||
-        //           get_channels_last_strides_3d(sizes()) == strides()) {
-        //         return at::MemoryFormat::ChannelsLast3d;
-        //       }
-        //     }
-        //   }
-        return MemoryFormat::Contiguous;
+        if self.layout() != Layout::Strided {
+            return MemoryFormat::Contiguous;
+        }
+        let impl_ = self.get_unsafe_tensor_impl();
+        if impl_.is_strides_like_channels_last() {
+            if !channels_last_strides_exact_match
+                || get_channels_last_strides_2d(self.sizes()) == self.strides()
+            {
+                return MemoryFormat::ChannelsLast;
+            }
+        } else if impl_.is_strides_like_channels_last_3d()
+            && (!channels_last_strides_exact_match
+                || get_channels_last_strides_3d(self.sizes()) == self.strides())
+        {
+            return MemoryFormat::ChannelsLast3d;
+        }
+        MemoryFormat::Contiguous
     }
 }
 
@@ -1,5 +1,10 @@
 use crate::autograd::*;
-use crate::c10::{Device, Layout, MemoryFormat, Storage, TypeMeta, K_STRIDED};
+use crate::c10::{
+    get_channels_last_strides_2d, get_channels_last_strides_3d, is_channels_last_strides_2d,
+    is_channels_last_strides_3d, ComplexDouble, Device, Layout, MemoryFormat, Storage, TypeMeta,
+    K_SPARSE, K_SPARSE_CSR, K_STRIDED,
+};
+use crate::core::InferenceMode;
 use crate::ops::*;
 use crate::tensor::*;
 use std::cell::RefCell;
@@ -17,38 +22,86 @@ impl VersionCounter {
     }
 }
 
-#[derive(Clone, Debug, Default)]
+// The counter is None for inference tensors, which don't track versions.
+#[derive(Clone, Debug)]
 pub struct TensorVersion {
-    pub version_counter: Rc<RefCell<VersionCounter>>,
+    pub version_counter: Option<Rc<RefCell<VersionCounter>>>,
+}
+
+impl Default for TensorVersion {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TensorVersion {
     pub fn new() -> Self {
         TensorVersion {
-            version_counter: Rc::new(RefCell::new(VersionCounter::default())),
+            version_counter: Some(Rc::new(RefCell::new(VersionCounter::default()))),
         }
     }
 
     pub fn new_with_version(version: u32) -> Self {
         Self {
-            version_counter: Rc::new(RefCell::new(VersionCounter::new(version))),
+            version_counter: Some(Rc::new(RefCell::new(VersionCounter::new(version)))),
         }
     }
 
+    pub fn disabled() -> Self {
+        Self {
+            version_counter: None,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.version_counter.is_some()
+    }
+
     pub fn bump(&self) {
-        let mut version = self.version_counter.borrow_mut();
-        version.version += 1;
+        match self.version_counter.as_ref() {
+            Some(counter) => counter.borrow_mut().version += 1,
+            None => assert!(
+                InferenceMode::is_enabled(),
+                "Inplace update to inference tensor outside InferenceMode is not allowed. You can make a clone to get a normal tensor before doing inplace update."
+            ),
+        }
     }
 
     pub fn current_version(&self) -> u32 {
-        self.version_counter.borrow().version
+        self.version_counter
+            .as_ref()
+            .expect("Inference tensors do not track version counter.")
+            .borrow()
+            .version
     }
 
     pub fn unique(&self) -> bool {
-        1 == Rc::strong_count(&self.version_counter)
+        self.version_counter
+            .as_ref()
+            .is_none_or(|counter| 1 == Rc::strong_count(counter))
     }
 }
 
+// Indices and values of a sparse COO tensor, which has no strided storage. `indices` is
+// a Long tensor of shape [sparse_dim, nnz] and `values` has shape [nnz, *dense sizes].
+// Uncoalesced tensors may repeat an index, its values are then summed.
+#[derive(Clone)]
+pub struct SparseParts {
+    pub indices: Tensor,
+    pub values: Tensor,
+    pub sparse_dim: usize,
+    pub coalesced: bool,
+}
+
+// Compressed sparse row matrix. Row i has the columns col_indices[crow_indices[i]..
+// crow_indices[i + 1]], sorted, and values at the same positions.
+#[derive(Clone)]
+pub struct SparseCsrParts {
+    pub crow_indices: Tensor,
+    pub col_indices: Tensor,
+    pub values: Tensor,
+}
+
 #[derive(Default)]
 pub struct TensorImpl {
     storage: Storage,
@@ -64,6 +117,12 @@ pub struct TensorImpl {
     is_wrapped_number: bool,
     is_defined: bool,
     is_non_overlapping_and_dense: bool,
+    is_channels_last_contiguous: bool,
+    is_channels_last_3d_contiguous: bool,
+    is_channels_last: bool,
+    is_channels_last_3d: bool,
+    sparse: Option<SparseParts>,
+    sparse_csr: Option<SparseCsrParts>,
 }
 
 impl TensorImpl {
@@ -72,7 +131,11 @@ impl TensorImpl {
         let storage_offset = 0;
         let strides = smallvec::smallvec![1];
         let numel = 0;
-        let version_counter = TensorVersion::new();
+        let version_counter = if InferenceMode::is_enabled() {
+            TensorVersion::disabled()
+        } else {
+            TensorVersion::new()
+        };
         Self {
             storage,
             data_type,
@@ -87,9 +150,39 @@ impl TensorImpl {
             is_wrapped_number: false,
             is_defined: true,
             is_non_overlapping_and_dense: false,
+            is_channels_last_contiguous: false,
+            is_channels_last_3d_contiguous: false,
+            is_channels_last: false,
+            is_channels_last_3d: false,
+            sparse: None,
+            sparse_csr: None,
         }
     }
 
+    pub fn new_sparse(sizes: &[usize], parts: SparseParts) -> Self {
+        let data_type = *parts.values.dtype();
+        let device = Some(parts.values.device());
+        let mut impl_ = Self::new(Storage::default(), data_type, device);
+        impl_.sizes = smallvec::SmallVec::from_slice(sizes);
+        impl_.strides.clear();
+        impl_.sparse = Some(parts);
+        impl_.refresh_numel();
+        impl_.refresh_contiguous();
+        impl_
+    }
+
+    pub fn new_sparse_csr(sizes: &[usize], parts: SparseCsrParts) -> Self {
+        let data_type = *parts.values.dtype();
+        let device = Some(parts.values.device());
+        let mut impl_ = Self::new(Storage::default(), data_type, device);
+        impl_.sizes = smallvec::SmallVec::from_slice(sizes);
+        impl_.strides.clear();
+        impl_.sparse_csr = Some(parts);
+        impl_.refresh_numel();
+        impl_.refresh_contiguous();
+        impl_
+    }
+
     pub fn with_storage_and_dtype(storage: Storage, data_type: TypeMeta) -> Self {
         let device = Some(storage.device());
         Self::new(storage, data_type, device)
@@ -121,6 +214,10 @@ impl TensorImpl {
     }
 
     pub fn set_requires_grad(&mut self, requires_grad: bool) {
+        assert!(
+            !requires_grad || !self.is_inference() || InferenceMode::is_enabled(),
+            "Setting requires_grad=true on inference tensor outside InferenceMode is not allowed."
+        );
         if self.autogradmeta.is_none() {
             self.autogradmeta = Some(AutogradMetaFactory::make());
         }
@@ -152,6 +249,10 @@ impl TensorImpl {
         &self.version_counter
     }
 
+    pub fn is_inference(&self) -> bool {
+        !self.version_counter.enabled()
+    }
+
     pub fn unique_version(&self) -> bool {
         self.version_counter.unique()
     }
@@ -195,8 +296,34 @@ impl TensorImpl {
         self.refresh_contiguous();
     }
     fn refresh_contiguous(&mut self) {
+        self.is_channels_last_contiguous = false;
+        self.is_channels_last_3d_contiguous = false;
+        self.is_channels_last = false;
+        self.is_channels_last_3d = false;
+        // Sparse tensors have no strides.
+        if self.sparse.is_some() || self.sparse_csr.is_some() {
+            self.is_contiguous = false;
+            self.is_non_overlapping_and_dense = false;
+            return;
+        }
         self.is_contiguous = self.compute_contiguous();
-        self.is_non_overlapping_and_dense = self.is_contiguous;
+        match self.dim() {
+            4 => {
+                self.is_channels_last_contiguous =
+                    self.compute_channels_last_contiguous(&[1, 3, 2, 0]);
+                self.is_channels_last = is_channels_last_strides_2d(&self.sizes, &self.strides);
+            }
+            5 => {
+                self.is_channels_last_3d_contiguous =
+                    self.compute_channels_last_contiguous(&[1, 4, 3, 2, 0]);
+                self.is_channels_last_3d = is_channels_last_strides_3d(&self.sizes, &self.strides);
+            }
+            _ => {}
+        }
+        self.is_non_overlapping_and_dense = self.is_contiguous
+            || self.is_channels_last_contiguous
+            || self.is_channels_last_3d_contiguous
+            || self.compute_non_overlapping_and_dense();
     }
 
     pub fn shallow_copy_and_detach(&self, version_counter: &TensorVersion) -> Self {
@@ -219,8 +346,19 @@ impl TensorImpl {
         dest_impl.device_opt = src_impl.device_opt.clone();
         dest_impl.is_contiguous = src_impl.is_contiguous;
         dest_impl.is_non_overlapping_and_dense = src_impl.is_non_overlapping_and_dense;
+        dest_impl.is_channels_last_contiguous = src_impl.is_channels_last_contiguous;
+        dest_impl.is_channels_last_3d_contiguous = src_impl.is_channels_last_3d_contiguous;
+        dest_impl.is_channels_last = src_impl.is_channels_last;
+        dest_impl.is_channels_last_3d = src_impl.is_channels_last_3d;
         dest_impl.is_wrapped_number = src_impl.is_wrapped_number;
-        dest_impl.set_version_counter(version_counter.clone());
+        dest_impl.sparse = src_impl.sparse.clone();
+        dest_impl.sparse_csr = src_impl.sparse_csr.clone();
+        // Copies of inference tensors stay inference tensors.
+        if src_impl.is_inference() {
+            dest_impl.set_version_counter(TensorVersion::disabled());
+        } else {
+            dest_impl.set_version_counter(version_counter.clone());
+        }
     }
     fn compute_contiguous(&self) -> bool {
         let mut is_contiguous = true;
@@ -245,6 +383,39 @@ impl TensorImpl {
         }
         is_contiguous
     }
+    // Whether the dimensions, visited from the innermost in `order`, are packed densely.
+    fn compute_channels_last_contiguous(&self, order: &[usize]) -> bool {
+        let mut expected = 1;
+        for &d in order {
+            if self.sizes[d] != 1 {
+                if self.strides[d] != expected {
+                    return false;
+                }
+                expected *= self.sizes[d];
+            }
+        }
+        true
+    }
+    // Whether some permutation of the dimensions is contiguous.
+    fn compute_non_overlapping_and_dense(&self) -> bool {
+        if self.dim() == 1 {
+            return self.sizes[0] < 2 || self.strides[0] == 1;
+        }
+        let mut perm: Vec<usize> = (0..self.sizes.len()).collect();
+        // Dimensions of size 0 or 1 go last, their strides don't matter.
+        perm.sort_by_key(|&d| (self.sizes[d] < 2, self.strides[d]));
+        let mut require_stride = 1;
+        for d in perm {
+            if self.sizes[d] < 2 {
+                return true;
+            }
+            if self.strides[d] != require_stride {
+                return false;
+            }
+            require_stride *= self.sizes[d];
+        }
+        true
+    }
     pub fn size(&self, d: i64) -> usize {
         let d = maybe_wrap_dim(d as i64, self.dim(), false);
         self.sizes[d]
@@ -278,9 +449,23 @@ impl TensorImpl {
                     }
                 }
             }
-            MemoryFormat::Preserve => {}
-            MemoryFormat::ChannelsLast => {}
-            MemoryFormat::ChannelsLast3d => {}
+            MemoryFormat::ChannelsLast => {
+                assert_eq!(
+                    self.dim(),
+                    4,
+                    "required rank 4 tensor to use channels_last format"
+                );
+                self.strides = get_channels_last_strides_2d(&self.sizes).into();
+            }
+            MemoryFormat::ChannelsLast3d => {
+                assert_eq!(
+                    self.dim(),
+                    5,
+                    "required rank 5 tensor to use channels_last_3d format"
+                );
+                self.strides = get_channels_last_strides_3d(&self.sizes).into();
+            }
+            MemoryFormat::Preserve => panic!("unsupported memory format {:?}", memory_format),
         }
         self.refresh_contiguous()
     }
@@ -312,19 +497,71 @@ impl TensorImpl {
     pub fn is_wrapped_number(&self) -> bool {
         self.is_wrapped_number
     }
-    pub fn is_contiguous_(&self, _memory_format: MemoryFormat) -> bool {
-        self.is_contiguous
+    pub fn is_contiguous_(&self, memory_format: MemoryFormat) -> bool {
+        match memory_format {
+            MemoryFormat::ChannelsLast => self.is_channels_last_contiguous,
+            MemoryFormat::ChannelsLast3d => self.is_channels_last_3d_contiguous,
+            _ => self.is_contiguous,
+        }
+    }
+    // Whether the strides are ordered like NHWC, possibly with gaps between them.
+    pub fn is_strides_like_channels_last(&self) -> bool {
+        self.is_channels_last
+    }
+    pub fn is_strides_like_channels_last_3d(&self) -> bool {
+        self.is_channels_last_3d
     }
     pub fn is_non_overlapping_and_dense(&self) -> bool {
         return self.is_non_overlapping_and_dense;
     }
     pub fn layout(&self) -> Layout {
-        // if is_sparse() {
-        //     return K_SPARSE;
-        // }
+        if self.is_sparse() {
+            return K_SPARSE;
+        }
+        if self.is_sparse_csr() {
+            return K_SPARSE_CSR;
+        }
         return K_STRIDED;
     }
 
+    pub fn is_sparse(&self) -> bool {
+        self.sparse.is_some()
+    }
+
+    pub fn sparse_parts(&self) -> &SparseParts {
+        self.sparse
+            .as_ref()
+            .expect("sparse_parts() called on a strided tensor")
+    }
+
+    pub fn set_sparse_parts(&mut self, parts: SparseParts) {
+        assert!(
+            self.is_sparse(),
+            "set_sparse_parts() called on a strided tensor"
+        );
+        self.data_type = *parts.values.dtype();
+        self.sparse = Some(parts);
+    }
+
+    pub fn is_sparse_csr(&self) -> bool {
+        self.sparse_csr.is_some()
+    }
+
+    pub fn sparse_csr_parts(&self) -> &SparseCsrParts {
+        self.sparse_csr
+            .as_ref()
+            .expect("sparse_csr_parts() called on a tensor without the sparse CSR layout")
+    }
+
+    pub fn set_sparse_csr_parts(&mut self, parts: SparseCsrParts) {
+        assert!(
+            self.is_sparse_csr(),
+            "set_sparse_csr_parts() called on a tensor without the sparse CSR layout"
+        );
+        self.data_type = *parts.values.dtype();
+        self.sparse_csr = Some(parts);
+    }
+
     pub fn device(&self) -> Device {
         self.device_opt.as_ref().unwrap().clone()
     }
@@ -346,6 +583,20 @@ impl TensorImpl {
 
     fn print_impl(&self) -> String {
         let data = self.data().as_ptr();
+        if &self.data_type == TypeMeta::make::<ComplexDouble>() {
+            let slice =
+                unsafe { std::slice::from_raw_parts(data as *const ComplexDouble, self.numel) };
+            let values: Vec<_> = slice.iter().map(|z| z.to_string()).collect();
+            return format!("[{}]", values.join(", "));
+        }
+        if &self.data_type == TypeMeta::make::<i64>() {
+            let slice = std::ptr::slice_from_raw_parts(data as *const i64, self.numel);
+            return format!("{:?}", unsafe { &*slice });
+        }
+        if &self.data_type == TypeMeta::make::<bool>() {
+            let slice = std::ptr::slice_from_raw_parts(data as *const bool, self.numel);
+            return format!("{:?}", unsafe { &*slice });
+        }
         let slice = std::ptr::slice_from_raw_parts(data as *const f64, self.numel);
         format!("{:?}", unsafe { &*slice })
     }
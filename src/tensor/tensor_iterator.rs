@@ -4,7 +4,10 @@ use autograd::empty_like;
 use super::Tensor;
 use crate::aten;
 use crate::autograd;
-use crate::c10::{can_cast, elementSize, Device, DeviceType, ScalarType, TensorOptions, KCPU};
+use crate::c10::{
+    can_cast, elementSize, get_default_dtype, isIntegralType, type_meta_to_scalar_type, Device,
+    DeviceType, MemoryFormat, ScalarType, TensorOptions, KCPU,
+};
 use crate::util::BitSet;
 
 use std::{ffi::c_void, ops::Range, ptr::NonNull};
@@ -73,6 +76,7 @@ impl<'a> DimCounter<'a> {
                 overflow = 0;
             }
             self.values[i] = value;
+            i += 1;
         }
         assert!(overflow == 0 || overflow == 1);
     }
@@ -273,7 +277,7 @@ impl TensorIterator {
         // insertion sort with support for ambiguous comparisons
         for i in 1..self.ndim() {
             let mut dim1 = i;
-            for dim0 in (0..i - 1).rev() {
+            for dim0 in (0..i).rev() {
                 let comparison = should_swap(self.perm[dim0], self.perm[dim1]);
                 if comparison > 0 {
                     self.perm.swap(dim0, dim1);
@@ -394,6 +398,9 @@ impl TensorIterator {
         if has_different_input_dtypes && config.promote_inputs_to_common_dtype_ {
             self.common_dtype_ = self.compute_common_dtype();
         }
+        if config.promote_integer_inputs_to_float_ && isIntegralType(self.common_dtype_, true) {
+            self.common_dtype_ = type_meta_to_scalar_type(&get_default_dtype());
+        }
         // let mut max_cpu_scalars_on_cuda = if config.allow_cpu_scalars_ { 1 } else { 0 };
         // let mut current_cpu_scalars_on_cuda = 0;
         for op in self.operands_.iter_mut() {
@@ -473,6 +480,7 @@ impl TensorIterator {
         let ndim = self.ndim();
         let mut stride_bytes = vec![];
         let mut tensor_shape = vec![];
+        let mut tensor_stride = vec![];
         let mut inverted_vec = vec![true; self.num_outputs_];
         for i in 0..self.num_outputs_ {
             {
@@ -503,6 +511,7 @@ impl TensorIterator {
                     }
                     inverted_vec[i] = inverted;
                     tensor_shape.push(self.invert_perm(&self.shape_.as_slice()));
+                    tensor_stride.push(self.invert_perm(op.stride_bytes.as_slice()));
                 }
             }
             {
@@ -515,13 +524,16 @@ impl TensorIterator {
                             None,
                         ));
                     } else {
-                        // let tensor_stride = self.invert_perm(&op.stride_bytes.as_slice());
-                        // for dim in 0..ndim {
-                        //     tensor_stride[dim] /= el_size;
-                        // }
-                        // op.tensor =
-                        //     aten::native::empty_strided(tensor_shape, tensor_stride, op.options());
-                        todo!()
+                        let el_size = elementSize(op.target_dtype);
+                        let tensor_stride: Vec<usize> = tensor_stride[i]
+                            .iter()
+                            .map(|stride| stride / el_size)
+                            .collect();
+                        op.tensor.move_tensor(aten::native::empty_strided_cpu(
+                            tensor_shape[i].as_slice(),
+                            tensor_stride.as_slice(),
+                            op.options(),
+                        ));
                     }
                     op.current_dtype = op.target_dtype;
                 }
@@ -581,7 +593,7 @@ impl TensorIterator {
 
         self.shape_.resize(prev_dim + 1, 0);
         for i in 0..self.ntensors() {
-            self.operands_[i].stride_bytes.resize(ndim, 0);
+            self.operands_[i].stride_bytes.resize(prev_dim + 1, 0);
         }
         self.has_coalesced_dimensions = true;
     }
@@ -603,6 +615,10 @@ impl TensorIterator {
         // tensors in iterator, but here I am using only this method for both tasks.
 
         for tensor in config.tensors_.iter() {
+            assert!(
+                !tensor.is_sparse() && !tensor.is_sparse_csr(),
+                "TensorIterator does not support sparse tensors, convert them with to_dense()"
+            );
             self.operands_.push(NewOperandInfo::new(tensor.clone()))
         }
         self.num_outputs_ = config.num_outputs_;
@@ -691,11 +707,17 @@ impl TensorIterator {
             return;
         }
 
+        // Resized outputs follow the memory format of the first input of the same shape.
+        let memory_format = self.operands_[self.num_outputs_..]
+            .iter()
+            .find(|op| op.tensor.defined() && op.tensor.sizes() == self.shape_.as_slice())
+            .map(|op| op.tensor.suggest_memory_format(false))
+            .unwrap_or(MemoryFormat::Contiguous);
         for i in 0..self.num_outputs_ {
             let tensor = &self.operands_[i].tensor;
             if tensor.defined() && tensor.sizes() != self.shape_.as_slice() {
                 if config.resize_outputs_ {
-                    tensor.resize(self.shape_.as_slice(), None);
+                    tensor.resize(self.shape_.as_slice(), Some(memory_format));
                     continue;
                 }
 
@@ -742,18 +764,47 @@ impl TensorIterator {
             return false;
         }
         match setup_type {
-            FastSetupType::Contiguous => {
+            FastSetupType::Contiguous | FastSetupType::ChannelsLast => {
+                let memory_format = if setup_type == FastSetupType::Contiguous {
+                    MemoryFormat::Contiguous
+                } else {
+                    MemoryFormat::ChannelsLast
+                };
                 for i in 0..self.num_outputs_ {
                     let op = &mut self.operands_[i];
                     if !op.tensor.defined() {
                         assert!(op.is_type_defined(), "No type for operand {}", i);
-                        op.tensor
-                            .move_tensor(autograd::empty(self.shape_.as_slice(), None, None));
+                        op.tensor.move_tensor(autograd::empty(
+                            self.shape_.as_slice(),
+                            op.options(),
+                            memory_format,
+                        ));
                         op.current_dtype = op.target_dtype;
                     }
                 }
             }
-            _ => {}
+            FastSetupType::NonOverLappingDense => {
+                // All defined operands share their strides, the outputs take them too.
+                let strides = self
+                    .operands_
+                    .iter()
+                    .find(|op| op.tensor.defined())
+                    .map(|op| op.tensor.strides().to_vec())
+                    .unwrap();
+                for i in 0..self.num_outputs_ {
+                    let op = &mut self.operands_[i];
+                    if !op.tensor.defined() {
+                        assert!(op.is_type_defined(), "No type for operand {}", i);
+                        op.tensor.move_tensor(aten::native::empty_strided_cpu(
+                            self.shape_.as_slice(),
+                            strides.as_slice(),
+                            op.options(),
+                        ));
+                        op.current_dtype = op.target_dtype;
+                    }
+                }
+            }
+            FastSetupType::None => {}
         };
         if self.ndim() > 1 {
             self.has_coalesced_dimensions = true;
@@ -778,18 +829,34 @@ impl TensorIterator {
             return FastSetupType::None;
         }
         let mut is_contiguous = true;
-        // let mut is_channels_last = true;
-        // let mut is_non_overlapping_and_dense = true;
+        let mut is_channels_last = true;
+        let mut is_non_overlapping_and_dense = true;
         for op in &self.operands_ {
             if op.tensor.defined() {
-                is_contiguous &= op.tensor.is_contiguous();
-                // is_contiguous &= op.tensor.is_contiguous(MemoryFormat::Contiguous);
-                // is_contiguous &= op.tensor.is_contiguous(MemoryFormat::Contiguous);
+                is_contiguous &= op.tensor.is_contiguous_(MemoryFormat::Contiguous);
+                is_channels_last &= op.tensor.is_contiguous_(MemoryFormat::ChannelsLast);
+                is_non_overlapping_and_dense &= op.tensor.is_non_overlapping_and_dense();
             }
         }
         if is_contiguous {
             return FastSetupType::Contiguous;
         }
+        if is_channels_last {
+            return FastSetupType::ChannelsLast;
+        }
+        if is_non_overlapping_and_dense {
+            // Dense operands can still be walked linearly when they all share the
+            // same strides.
+            let mut strides = self
+                .operands_
+                .iter()
+                .filter(|op| op.tensor.defined())
+                .map(|op| op.tensor.strides());
+            let first = strides.next();
+            if first.is_some() && strides.all(|s| Some(s) == first) {
+                return FastSetupType::NonOverLappingDense;
+            }
+        }
         FastSetupType::None
     }
 
@@ -824,6 +891,20 @@ impl TensorIterator {
             .enforce_safe_casting_to_output(true)
             .build()
     }
+    // Like binary_op, but integer and bool inputs are promoted to the default dtype.
+    pub fn binary_float_op(out: &Tensor, a: &Tensor, b: &Tensor) -> Self {
+        TensorIteratorConfig::default()
+            .set_check_mem_overlap(true)
+            .add_output(out)
+            .add_input(a)
+            .add_input(b)
+            .allow_cpu_scalars(true)
+            .promote_inputs_to_common_dtype(true)
+            .cast_common_dtype_to_outputs(true)
+            .enforce_safe_casting_to_output(true)
+            .promote_integer_inputs_to_float(true)
+            .build()
+    }
     pub fn reduce_op(out: &Tensor, a: &Tensor) -> Self {
         assert!(out.defined());
         TensorIteratorConfig::default()
@@ -836,6 +917,18 @@ impl TensorIterator {
             .build()
     }
 
+    // Copies outputs that were computed in the common dtype into the tensors the
+    // iterator was built with.
+    pub fn cast_outputs(&mut self) {
+        for op in self.operands_.iter_mut().filter(|op| op.is_output) {
+            if let Some(original) = op.original_tensor.take() {
+                aten::native::copy_(&original, &op.tensor, false);
+                op.tensor = original;
+                op.current_dtype = op.tensor.scalar_type();
+            }
+        }
+    }
+
     /// Pytorch uses this function with index to get output but
     /// this function only gives first operand.
     pub fn output(&self) -> &Tensor {
@@ -1088,6 +1181,7 @@ pub struct TensorIteratorConfig {
     check_all_same_device_: bool,
     enforce_safe_casting_to_output_: bool,
     promote_inputs_to_common_dtype_: bool,
+    promote_integer_inputs_to_float_: bool,
     cast_common_dtype_to_outputs_: bool,
 }
 
@@ -1106,6 +1200,7 @@ impl Default for TensorIteratorConfig {
             check_all_same_device_: true,
             enforce_safe_casting_to_output_: false,
             promote_inputs_to_common_dtype_: false,
+            promote_integer_inputs_to_float_: false,
             cast_common_dtype_to_outputs_: false,
             static_dtype_and_device_: None,
         }
@@ -1143,6 +1238,17 @@ impl TensorIteratorConfig {
         }
         self
     }
+    pub fn promote_integer_inputs_to_float(
+        &mut self,
+        promote_integer_inputs_to_float: bool,
+    ) -> &mut Self {
+        self.promote_integer_inputs_to_float_ = promote_integer_inputs_to_float;
+        assert!(
+            !promote_integer_inputs_to_float || self.promote_inputs_to_common_dtype_,
+            "promote_integer_inputs_to_float requires promote_inputs_to_common_dtype"
+        );
+        self
+    }
     pub fn is_reduction(&mut self, is_reduction: bool) -> &mut Self {
         self.is_reduction_ = is_reduction;
         self
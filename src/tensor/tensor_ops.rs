@@ -2,7 +2,7 @@ use aten::typedefault;
 use loss::Reduction;
 use util_autograd::{collect_next_edges, compute_requires_grad, set_history};
 
-use crate::autograd::SavedTensor;
+use crate::autograd::{forward_ad, SavedTensor};
 use crate::c10::Scalar;
 use crate::ops::*;
 use crate::tensor::*;
@@ -12,7 +12,7 @@ use crate::{
         self,
         native::{self, *},
     },
-    c10::ScalarType,
+    c10::{is_complex_type, ScalarType},
 };
 use std::cell::RefCell;
 use std::ops::{Add, Div, Mul, Neg, Sub};
@@ -52,6 +52,7 @@ impl Add<Self> for &Tensor {
         if grad_fn.is_some() {
             util_autograd::set_history(&result, grad_fn.unwrap());
         }
+        forward_ad::set_fw_grad_with(&result, &[self, rhs], |t| &t[0] + &t[1]);
         result
     }
 }
@@ -78,6 +79,7 @@ where
         if grad_fn.is_some() {
             util_autograd::set_history(&result, grad_fn.unwrap());
         }
+        forward_ad::set_fw_grad_with(&result, &[self], |t| t[0].clone());
         result
     }
 }
@@ -114,6 +116,7 @@ impl Mul<Self> for &Tensor {
         if grad_fn.is_some() {
             util_autograd::set_history(&result, grad_fn.unwrap());
         }
+        forward_ad::set_fw_grad_with(&result, &[self, rhs], |t| &(&t[0] * rhs) + &(self * &t[1]));
         result
     }
 }
@@ -142,6 +145,7 @@ where
         if grad_fn.is_some() {
             util_autograd::set_history(&result, grad_fn.unwrap());
         }
+        forward_ad::set_fw_grad_with(&result, &[self], |t| &t[0] * rhs);
         result
     }
 }
@@ -181,6 +185,7 @@ impl Sub<Self> for &Tensor {
         if grad_fn.is_some() {
             util_autograd::set_history(&result, grad_fn.unwrap());
         }
+        forward_ad::set_fw_grad_with(&result, &[self, rhs], |t| &t[0] - &t[1]);
         result
     }
 }
@@ -205,6 +210,9 @@ impl Div<Self> for &Tensor {
         if grad_fn.is_some() {
             util_autograd::set_history(&result, grad_fn.unwrap());
         }
+        forward_ad::set_fw_grad_with(&result, &[self, rhs], |t| {
+            &(&t[0] - &(&result * &t[1])) / rhs
+        });
         result
     }
 }
@@ -240,6 +248,7 @@ where
         if grad_fn.is_some() {
             util_autograd::set_history(&result, grad_fn.unwrap());
         }
+        forward_ad::set_fw_grad_with(&result, &[self], |t| &t[0] / rhs);
         result
     }
 }
@@ -272,6 +281,7 @@ impl Neg for &Tensor {
         if grad_fn.is_some() {
             util_autograd::set_history(&result, grad_fn.unwrap());
         }
+        forward_ad::set_fw_grad_with(&result, &[self], |t| -&t[0]);
         result
     }
 }
@@ -291,6 +301,7 @@ pub fn transpose(self_: &Tensor, dim0: i64, dim1: i64) -> Tensor {
     if grad_fn.is_some() {
         util_autograd::set_history(&result, grad_fn.unwrap());
     }
+    forward_ad::set_fw_grad_with(&result, &[self_], |t| transpose(&t[0], dim0, dim1));
     result
 }
 
@@ -309,6 +320,8 @@ pub fn transpose_(self_: &Tensor, dim0: i64, dim1: i64) -> &Tensor {
     if grad_fn.is_some() {
         util_autograd::set_history(result, grad_fn.unwrap());
     }
+    // The tangent isn't transposed in place since it may be shared with other tensors.
+    forward_ad::set_fw_grad_with(result, &[self_], |t| transpose(&t[0], dim0, dim1));
     result
 }
 
@@ -327,11 +340,15 @@ pub fn t(self_: &Tensor) -> Tensor {
     if grad_fn.is_some() {
         util_autograd::set_history(&result, grad_fn.unwrap());
     }
+    forward_ad::set_fw_grad_with(&result, &[self_], |t| self::t(&t[0]));
     result
 }
 
 pub fn mm<T: AsRef<Tensor>>(mat1: &Tensor, mat2: T, consume: bool) -> Tensor {
     let mat2 = mat2.as_ref();
+    if mat1.is_sparse() || mat1.is_sparse_csr() {
+        check_no_requires_grad(mat1, "mm with respect to a sparse matrix");
+    }
     let mut grad_fn: Option<Rc<RefCell<Node>>> = None;
     if util_autograd::compute_requires_grad(&[mat1, mat2]) {
         let mut _grad_fn = MmBackward {
@@ -346,7 +363,7 @@ pub fn mm<T: AsRef<Tensor>>(mat1: &Tensor, mat2: T, consume: bool) -> Tensor {
         // if consume {
         //     _grad_fn.mat2_ = Some(SavedTensor::new(mat2, false));
         // } else {
-            _grad_fn.mat2_ = Some(SavedTensor::new(&mat2, false));
+        _grad_fn.mat2_ = Some(SavedTensor::new(&mat2, false));
         // }
         _grad_fn.mat2_sizes = mat2.sizes().to_vec();
 
@@ -356,6 +373,9 @@ pub fn mm<T: AsRef<Tensor>>(mat1: &Tensor, mat2: T, consume: bool) -> Tensor {
     if grad_fn.is_some() {
         util_autograd::set_history(&result, grad_fn.unwrap());
     }
+    forward_ad::set_fw_grad_with(&result, &[mat1, mat2], |t| {
+        &mm(&t[0], mat2, false) + &mm(mat1, &t[1], false)
+    });
     result
 }
 
@@ -389,6 +409,9 @@ pub fn addmm(
     if grad_fn.is_some() {
         util_autograd::set_history(&result, grad_fn.unwrap());
     }
+    forward_ad::set_fw_grad_with(&result, &[self_, mat1, mat2], |t| {
+        &addmm(&t[0], &t[1], mat2, alpha, beta) + &(&mm(mat1, &t[2], false) * beta)
+    });
     result
 }
 
@@ -407,8 +430,9 @@ pub fn mean(self_: &Tensor) -> Tensor {
     }
     let result = aten::native::mean(self_, None);
     if grad_fn.is_some() {
-        util_autograd::set_history(&self_, grad_fn.unwrap());
+        util_autograd::set_history(&result, grad_fn.unwrap());
     }
+    forward_ad::set_fw_grad_with(&result, &[self_], |t| mean(&t[0]));
     result
 }
 
@@ -430,6 +454,7 @@ pub fn sum(self_: &Tensor, dtype: Option<ScalarType>) -> Tensor {
         util_autograd::set_history(&result, grad_fn.unwrap());
     }
 
+    forward_ad::set_fw_grad_with(&result, &[self_], |t| sum(&t[0], dtype));
     result
 }
 pub fn sum_dim_int_list(self_: &Tensor, dim: &[usize], keep_dim: bool) -> Tensor {
@@ -452,6 +477,9 @@ pub fn sum_dim_int_list(self_: &Tensor, dim: &[usize], keep_dim: bool) -> Tensor
         util_autograd::set_history(&result, grad_fn.unwrap());
     }
 
+    forward_ad::set_fw_grad_with(&result, &[self_], |t| {
+        sum_dim_int_list(&t[0], dim, keep_dim)
+    });
     result
 }
 
@@ -462,17 +490,44 @@ pub fn sigmoid(tensor: &Tensor) -> Tensor {
     let result = aten::native::sigmoid(tensor);
 
     if util_autograd::compute_requires_grad(&[tensor]) {
-        let mut _grad_fn = SigmoidBackward {
-            next_edges: None,
-            input_metadata_: smallvec::smallvec![],
-            result_: Some(SavedTensor::new(&result, false)),
-        };
-        _grad_fn.set_next_edges(util_autograd::collect_next_edges(&[tensor]));
-        grad_fn = Some(Rc::new(RefCell::new(Node::new(_grad_fn))));
+        grad_fn = Some(Rc::new_cyclic(|weak_fn| {
+            let mut _grad_fn = SigmoidBackward {
+                next_edges: None,
+                input_metadata_: smallvec::smallvec![],
+                result_: Some(SavedTensor::new_output(&result, weak_fn.clone(), 0)),
+            };
+            _grad_fn.set_next_edges(util_autograd::collect_next_edges(&[tensor]));
+            RefCell::new(Node::new(_grad_fn))
+        }));
+    }
+    if let Some(fn_) = grad_fn {
+        util_autograd::set_history(&result, fn_);
+    }
+    forward_ad::set_fw_grad_with(&result, &[tensor], |t| {
+        &t[0] * &(&result - &(&result * &result))
+    });
+    result
+}
+
+pub fn exp(tensor: &Tensor) -> Tensor {
+    let mut grad_fn: Option<Rc<RefCell<Node>>> = None;
+    let result = aten::native::exp(tensor);
+
+    if util_autograd::compute_requires_grad(&[tensor]) {
+        grad_fn = Some(Rc::new_cyclic(|weak_fn| {
+            let mut _grad_fn = ExpBackward {
+                next_edges: None,
+                input_metadata_: smallvec::smallvec![],
+                result_: Some(SavedTensor::new_output(&result, weak_fn.clone(), 0)),
+            };
+            _grad_fn.set_next_edges(util_autograd::collect_next_edges(&[tensor]));
+            RefCell::new(Node::new(_grad_fn))
+        }));
     }
     if let Some(fn_) = grad_fn {
         util_autograd::set_history(&result, fn_);
     }
+    forward_ad::set_fw_grad_with(&result, &[tensor], |t| &t[0] * &result);
     result
 }
 
@@ -494,6 +549,9 @@ pub fn squeeze(tensor: &Tensor) -> Tensor {
     if let Some(fn_) = grad_fn {
         util_autograd::set_history(&result, fn_);
     }
+    forward_ad::set_fw_grad_with(&result, &[tensor], |t| {
+        &t[0] * &(&result - &(&result * &result))
+    });
     result
 }
 
@@ -521,6 +579,18 @@ pub fn binary_cross_entropy(
     if grad_fn.is_some() {
         util_autograd::set_history(&result, grad_fn.unwrap());
     }
+    forward_ad::set_fw_grad_with(&result, &[input], |t| {
+        let denom = &(&(-input) + 1.0) * input;
+        let mut tangent = &t[0] * &(&(input - target) / &denom);
+        if let Some(w) = weight {
+            tangent = &tangent * w;
+        }
+        match reduction {
+            Reduction::None => tangent,
+            Reduction::Mean => mean(&tangent),
+            Reduction::Sum => sum(&tangent, None),
+        }
+    });
     result
 }
 
@@ -533,17 +603,23 @@ pub fn _log_softmax(self_: &Tensor, dim: i64, half_to_float: bool) -> Tensor {
     let result = log_softmax_cpu(self_, dim, half_to_float);
     let mut grad_fn: Option<Rc<RefCell<Node>>> = None;
     if util_autograd::compute_requires_grad(&[self_]) {
-        let mut _grad_fn = LogSoftmaxBackward::default();
-        _grad_fn.set_next_edges(util_autograd::collect_next_edges(&[self_]));
-        _grad_fn.self_ = Some(SavedTensor::new(self_, false));
-        _grad_fn.result = Some(SavedTensor::new(&result, true));
-        _grad_fn.dim = dim;
-        grad_fn = Some(Rc::new(RefCell::new(Node::new(_grad_fn))));
+        grad_fn = Some(Rc::new_cyclic(|weak_fn| {
+            let mut _grad_fn = LogSoftmaxBackward::default();
+            _grad_fn.set_next_edges(util_autograd::collect_next_edges(&[self_]));
+            _grad_fn.self_ = Some(SavedTensor::new(self_, false));
+            _grad_fn.result = Some(SavedTensor::new_output(&result, weak_fn.clone(), 0));
+            _grad_fn.dim = dim;
+            RefCell::new(Node::new(_grad_fn))
+        }));
     }
 
     if grad_fn.is_some() {
         util_autograd::set_history(&result, grad_fn.unwrap());
     }
+    forward_ad::set_fw_grad_with(&result, &[self_], |t| {
+        let dim = maybe_wrap_dim(dim, self_.dim(), true);
+        &t[0] - &sum_dim_int_list(&(&exp(&result) * &t[0]), &[dim], true)
+    });
     result
 }
 pub fn nll_loss_forward(
@@ -567,10 +643,303 @@ pub fn nll_loss_forward(
         _grad_fn.target = Some(SavedTensor::new(target, false));
         _grad_fn.weight = Some(SavedTensor::new_with_optional(weight, false));
         _grad_fn.total_weight = Some(SavedTensor::new(&total_weight, true));
+        _grad_fn.reduction = reduction;
+        _grad_fn.ignore_index = ignore_index;
         grad_fn = Some(Rc::new(RefCell::new(Node::new(_grad_fn))));
     }
     if grad_fn.is_some() {
         set_history(&output, grad_fn.unwrap());
     }
+    // nll_loss is linear in its input.
+    forward_ad::set_fw_grad_with(&output, &[self_], |t| {
+        nll_loss_forward(&t[0], target, weight, reduction, ignore_index).0
+    });
     (output, total_weight)
 }
+
+pub fn expand(self_: &Tensor, size: &[usize], implicit: bool) -> Tensor {
+    let mut grad_fn: Option<Rc<RefCell<Node>>> = None;
+    if util_autograd::compute_requires_grad(&[self_]) {
+        let mut _grad_fn = ExpandBackward {
+            next_edges: None,
+            input_metadata_: smallvec::smallvec![],
+            self_sizes: self_.sizes().to_vec(),
+        };
+        _grad_fn.set_next_edges(util_autograd::collect_next_edges(&[self_]));
+        grad_fn = Some(Rc::new(RefCell::new(Node::new(_grad_fn))));
+    }
+    let result = native::expand(self_, size, implicit);
+    if let Some(fn_) = grad_fn {
+        util_autograd::set_history(&result, fn_);
+    }
+    forward_ad::set_fw_grad_with(&result, &[self_], |t| expand(&t[0], size, implicit));
+    result
+}
+
+pub fn view(self_: &Tensor, size: &[usize]) -> Tensor {
+    let mut grad_fn: Option<Rc<RefCell<Node>>> = None;
+    if util_autograd::compute_requires_grad(&[self_]) {
+        let mut _grad_fn = ViewBackward {
+            next_edges: None,
+            input_metadata_: smallvec::smallvec![],
+            self_sizes: self_.sizes().to_vec(),
+        };
+        _grad_fn.set_next_edges(util_autograd::collect_next_edges(&[self_]));
+        grad_fn = Some(Rc::new(RefCell::new(Node::new(_grad_fn))));
+    }
+    let result = native::view(self_, size);
+    if let Some(fn_) = grad_fn {
+        util_autograd::set_history(&result, fn_);
+    }
+    forward_ad::set_fw_grad_with(&result, &[self_], |t| view(&t[0].contiguous(), size));
+    result
+}
+
+pub fn stack(tensors: &[Tensor], dim: i64) -> Tensor {
+    assert!(!tensors.is_empty(), "stack expects a non-empty TensorList");
+    let dim = maybe_wrap_dim(dim, tensors[0].dim() + 1, true);
+    let tensor_refs: Vec<&Tensor> = tensors.iter().collect();
+    let mut grad_fn: Option<Rc<RefCell<Node>>> = None;
+    if util_autograd::compute_requires_grad(&tensor_refs) {
+        let mut _grad_fn = StackBackward {
+            next_edges: None,
+            input_metadata_: smallvec::smallvec![],
+            dim,
+        };
+        _grad_fn.set_next_edges(util_autograd::collect_next_edges(&tensor_refs));
+        grad_fn = Some(Rc::new(RefCell::new(Node::new(_grad_fn))));
+    }
+    let result = native::stack(tensors, dim);
+    if let Some(fn_) = grad_fn {
+        util_autograd::set_history(&result, fn_);
+    }
+    forward_ad::set_fw_grad_with(&result, &tensor_refs, |t| stack(t, dim as i64));
+    result
+}
+
+pub fn unsqueeze(self_: &Tensor, dim: i64) -> Tensor {
+    let dim = maybe_wrap_dim(dim, self_.dim() + 1, true);
+    let mut sizes = self_.sizes().to_vec();
+    sizes.insert(dim, 1);
+    view(self_, sizes.as_slice())
+}
+
+// Real part of a complex tensor as a view, real tensors are returned as is.
+pub fn real(self_: &Tensor) -> Tensor {
+    if !is_complex_type(self_.scalar_type()) {
+        return self_.clone();
+    }
+    let mut grad_fn: Option<Rc<RefCell<Node>>> = None;
+    if util_autograd::compute_requires_grad(&[self_]) {
+        let mut _grad_fn = RealBackward {
+            next_edges: None,
+            input_metadata_: smallvec::smallvec![],
+            self_scalar_type: self_.scalar_type(),
+        };
+        _grad_fn.set_next_edges(util_autograd::collect_next_edges(&[self_]));
+        grad_fn = Some(Rc::new(RefCell::new(Node::new(_grad_fn))));
+    }
+    let result = native::real(self_);
+    if let Some(fn_) = grad_fn {
+        util_autograd::set_history(&result, fn_);
+    }
+    forward_ad::set_fw_grad_with(&result, &[self_], |t| real(&t[0]).contiguous());
+    result
+}
+
+pub fn imag(self_: &Tensor) -> Tensor {
+    let mut grad_fn: Option<Rc<RefCell<Node>>> = None;
+    if util_autograd::compute_requires_grad(&[self_]) {
+        let mut _grad_fn = ImagBackward {
+            next_edges: None,
+            input_metadata_: smallvec::smallvec![],
+            self_scalar_type: self_.scalar_type(),
+        };
+        _grad_fn.set_next_edges(util_autograd::collect_next_edges(&[self_]));
+        grad_fn = Some(Rc::new(RefCell::new(Node::new(_grad_fn))));
+    }
+    let result = native::imag(self_);
+    if let Some(fn_) = grad_fn {
+        util_autograd::set_history(&result, fn_);
+    }
+    forward_ad::set_fw_grad_with(&result, &[self_], |t| imag(&t[0]).contiguous());
+    result
+}
+
+// Complex conjugate, real tensors are returned as is.
+pub fn conj(self_: &Tensor) -> Tensor {
+    if !is_complex_type(self_.scalar_type()) {
+        return self_.clone();
+    }
+    let mut grad_fn: Option<Rc<RefCell<Node>>> = None;
+    if util_autograd::compute_requires_grad(&[self_]) {
+        let mut _grad_fn = ConjBackward {
+            next_edges: None,
+            input_metadata_: smallvec::smallvec![],
+        };
+        _grad_fn.set_next_edges(util_autograd::collect_next_edges(&[self_]));
+        grad_fn = Some(Rc::new(RefCell::new(Node::new(_grad_fn))));
+    }
+    let result = native::conj(self_);
+    if let Some(fn_) = grad_fn {
+        util_autograd::set_history(&result, fn_);
+    }
+    forward_ad::set_fw_grad_with(&result, &[self_], |t| conj(&t[0]));
+    result
+}
+
+pub fn abs(self_: &Tensor) -> Tensor {
+    let mut grad_fn: Option<Rc<RefCell<Node>>> = None;
+    if util_autograd::compute_requires_grad(&[self_]) {
+        let mut _grad_fn = AbsBackward {
+            next_edges: None,
+            input_metadata_: smallvec::smallvec![],
+            self_: None,
+        };
+        _grad_fn.set_next_edges(util_autograd::collect_next_edges(&[self_]));
+        _grad_fn.self_ = Some(SavedTensor::new(self_, false));
+        grad_fn = Some(Rc::new(RefCell::new(Node::new(_grad_fn))));
+    }
+    let result = native::abs(self_);
+    if let Some(fn_) = grad_fn {
+        util_autograd::set_history(&result, fn_);
+    }
+    forward_ad::set_fw_grad_with(&result, &[self_], |t| {
+        real(&(&t[0] * &conj(&native::sgn(self_)))).contiguous()
+    });
+    result
+}
+
+pub fn angle(self_: &Tensor) -> Tensor {
+    let mut grad_fn: Option<Rc<RefCell<Node>>> = None;
+    if util_autograd::compute_requires_grad(&[self_]) {
+        let mut _grad_fn = AngleBackward {
+            next_edges: None,
+            input_metadata_: smallvec::smallvec![],
+            self_: None,
+        };
+        _grad_fn.set_next_edges(util_autograd::collect_next_edges(&[self_]));
+        _grad_fn.self_ = Some(SavedTensor::new(self_, false));
+        grad_fn = Some(Rc::new(RefCell::new(Node::new(_grad_fn))));
+    }
+    let result = native::angle(self_);
+    if let Some(fn_) = grad_fn {
+        util_autograd::set_history(&result, fn_);
+    }
+    forward_ad::set_fw_grad_with(&result, &[self_], |t| {
+        real(&conj(&native::angle_backward(&conj(&t[0]), self_))).contiguous()
+    });
+    result
+}
+
+// Complex tensor with absolute values `abs` and angles `angle`.
+pub fn polar(abs: &Tensor, angle: &Tensor) -> Tensor {
+    let mut grad_fn: Option<Rc<RefCell<Node>>> = None;
+    let result = native::polar(abs, angle);
+    if util_autograd::compute_requires_grad(&[abs, angle]) {
+        grad_fn = Some(Rc::new_cyclic(|weak_fn| {
+            let mut _grad_fn = PolarBackward {
+                next_edges: None,
+                input_metadata_: smallvec::smallvec![],
+                result_: Some(SavedTensor::new_output(&result, weak_fn.clone(), 0)),
+            };
+            _grad_fn.set_next_edges(util_autograd::collect_next_edges(&[abs, angle]));
+            RefCell::new(Node::new(_grad_fn))
+        }));
+    }
+    if let Some(fn_) = grad_fn {
+        util_autograd::set_history(&result, fn_);
+    }
+    forward_ad::set_fw_grad_with(&result, &[abs, angle], |t| {
+        let i = Scalar::complex(num::complex::Complex::new(0.0, 1.0));
+        &native::polar(&t[0], angle) + &(&(&result * i) * &t[1])
+    });
+    result
+}
+
+// Complex tensor with real parts `real` and imaginary parts `imag`.
+pub fn complex(real: &Tensor, imag: &Tensor) -> Tensor {
+    let mut grad_fn: Option<Rc<RefCell<Node>>> = None;
+    if util_autograd::compute_requires_grad(&[real, imag]) {
+        let mut _grad_fn = ComplexBackward {
+            next_edges: None,
+            input_metadata_: smallvec::smallvec![],
+        };
+        _grad_fn.set_next_edges(util_autograd::collect_next_edges(&[real, imag]));
+        grad_fn = Some(Rc::new(RefCell::new(Node::new(_grad_fn))));
+    }
+    let result = native::complex(real, imag);
+    if let Some(fn_) = grad_fn {
+        util_autograd::set_history(&result, fn_);
+    }
+    forward_ad::set_fw_grad_with(&result, &[real, imag], |t| complex(&t[0], &t[1]));
+    result
+}
+
+pub fn view_as_real(self_: &Tensor) -> Tensor {
+    let mut grad_fn: Option<Rc<RefCell<Node>>> = None;
+    if util_autograd::compute_requires_grad(&[self_]) {
+        let mut _grad_fn = ViewAsRealBackward {
+            next_edges: None,
+            input_metadata_: smallvec::smallvec![],
+        };
+        _grad_fn.set_next_edges(util_autograd::collect_next_edges(&[self_]));
+        grad_fn = Some(Rc::new(RefCell::new(Node::new(_grad_fn))));
+    }
+    let result = native::view_as_real(self_);
+    if let Some(fn_) = grad_fn {
+        util_autograd::set_history(&result, fn_);
+    }
+    forward_ad::set_fw_grad_with(&result, &[self_], |t| view_as_real(&t[0].contiguous()));
+    result
+}
+
+pub fn view_as_complex(self_: &Tensor) -> Tensor {
+    let mut grad_fn: Option<Rc<RefCell<Node>>> = None;
+    if util_autograd::compute_requires_grad(&[self_]) {
+        let mut _grad_fn = ViewAsComplexBackward {
+            next_edges: None,
+            input_metadata_: smallvec::smallvec![],
+        };
+        _grad_fn.set_next_edges(util_autograd::collect_next_edges(&[self_]));
+        grad_fn = Some(Rc::new(RefCell::new(Node::new(_grad_fn))));
+    }
+    let result = native::view_as_complex(self_);
+    if let Some(fn_) = grad_fn {
+        util_autograd::set_history(&result, fn_);
+    }
+    forward_ad::set_fw_grad_with(&result, &[self_], |t| view_as_complex(&t[0].contiguous()));
+    result
+}
+
+// Rows of `weight` at `indices`, the gradient of `weight` is sparse when `sparse` is set.
+pub fn embedding(
+    weight: &Tensor,
+    indices: &Tensor,
+    padding_idx: Option<usize>,
+    sparse: bool,
+) -> Tensor {
+    check_no_requires_grad(indices, "indices");
+    let mut grad_fn: Option<Rc<RefCell<Node>>> = None;
+    if util_autograd::compute_requires_grad(&[weight]) {
+        let mut _grad_fn = EmbeddingBackward {
+            next_edges: None,
+            input_metadata_: smallvec::smallvec![],
+            indices_: None,
+            num_weights: weight.size(0),
+            padding_idx,
+            sparse,
+        };
+        _grad_fn.set_next_edges(util_autograd::collect_next_edges(&[weight]));
+        _grad_fn.indices_ = Some(SavedTensor::new(indices, false));
+        grad_fn = Some(Rc::new(RefCell::new(Node::new(_grad_fn))));
+    }
+    let result = native::embedding(weight, indices);
+    if let Some(fn_) = grad_fn {
+        util_autograd::set_history(&result, fn_);
+    }
+    forward_ad::set_fw_grad_with(&result, &[weight], |t| {
+        embedding(&t[0], indices, padding_idx, sparse)
+    });
+    result
+}
@@ -18,7 +18,6 @@ pub fn select(self_: &Tensor, dim: i64, mut index: i64) -> Tensor {
     let storage_offset = self_.storage_offset() + index * strides[dim];
     sizes.remove(dim);
     strides.remove(dim);
-    dbg!(&sizes, &strides);
     let result = as_strided_tensorimpl(
         self_,
         sizes.as_slice(),
@@ -37,6 +36,9 @@ pub fn as_strided_tensorimpl(
     let result = crate::aten::native::make_tensor(self_.storage(), self_.dtype());
     set_strided(&result, size, stride, storage_offset);
     result
+        .get_unsafe_tensor_impl()
+        .set_version_counter(self_.get_unsafe_tensor_impl().version_counter().clone());
+    result
 }
 
 pub fn set_strided(self_: &Tensor, size: &[usize], stride: &[usize], storage_offset: usize) {
@@ -21,7 +21,7 @@ where
 
     pub fn insert(&mut self, key: K, value: V) -> Option<&V> {
         assert_eq!(self.index.contains_key(&key), false);
-        self.index.insert(key.clone(), self.size() - 1);
+        self.index.insert(key.clone(), self.size());
         self.items.push(Item::new(key, value));
         self.items.last().map_or(None, |x| Some(&x.pair.1))
     }
@@ -30,6 +30,27 @@ where
         self.items.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.index.contains_key(key)
+    }
+
+    // Items in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.items.iter().map(|item| (item.key(), item.value()))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.items.iter().map(|item| item.key())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.items.iter().map(|item| item.value())
+    }
+
     pub fn find(&self, key: &K) -> Option<&V> {
         let it = self.index.get(&key);
         if let Some(idx) = it {
@@ -51,7 +72,7 @@ impl<K, V> Item<K, V> {
     }
 
     pub fn pair(&self) -> &(K, V) {
-        todo!()
+        &self.pair
     }
 
     pub fn value(&self) -> &V {
@@ -76,6 +76,12 @@ impl TensorHook {
     }
 }
 
+// Must be called by every op which modifies `tensor` in place, so SavedTensor can
+// detect that the values it saved for backward were overwritten.
+pub fn increment_version(tensor: &Tensor) {
+    TensorHook::version_counter(tensor).bump()
+}
+
 pub fn grad_accumulator(tensor: &Tensor) -> Option<Rc<RefCell<Node>>> {
     if let Some(meta) = TensorHook::get_autograd_meta(tensor) {
         if meta.grad_fn_.is_some() {
@@ -1,4 +1,18 @@
-use rovo::{aten::native::argmax, autograd::tensor, c10::TensorOptions, init_rovo};
+use rovo::{
+    aten::native::{argmax, nll_loss},
+    autograd::{
+        backward, checkpoint, detect_anomaly, empty_like,
+        forward_ad::{dual_level, make_dual, unpack_dual},
+        full,
+        functional::{hessian, hvp, jacobian, jvp, vjp},
+        grad, gradcheck, gradgradcheck, graph, tensor,
+    },
+    c10::{ComplexDouble, ScalarType, TensorOptions},
+    core::{GradMode, InferenceMode},
+    init_rovo,
+    nn::{Linear, Module},
+    tensor::{addmm, binary_cross_entropy, loss::Reduction, sigmoid, Tensor},
+};
 
 #[test]
 fn tensor_from_slice() {
@@ -26,3 +40,590 @@ fn test_argmax() {
     );
     println!("{:?}", argmax(&tensor, None, false));
 }
+
+fn double_leaf(data: &[f64], sizes: &[usize]) -> Tensor {
+    let t = tensor(data, TensorOptions::with_dtype(ScalarType::Double)).view(sizes);
+    t.set_requires_grad(true);
+    t
+}
+
+fn check_both<F: Fn(&[Tensor]) -> Tensor>(func: F, inputs: &[Tensor]) {
+    gradcheck(&func, inputs, 1e-6, 1e-5, 1e-3);
+    gradgradcheck(&func, inputs, 1e-6, 1e-5, 1e-3);
+}
+
+#[test]
+fn gradcheck_arithmetic_nodes() {
+    init_rovo();
+    let a = double_leaf(&[0.5, -1.2, 2.0, 0.3, 1.1, -0.7], &[2, 3]);
+    let b = double_leaf(&[1.5, 0.8, -2.1, 1.3, -0.9, 2.4], &[2, 3]);
+    check_both(|x| &x[0] + &x[1], &[a.clone(), b.clone()]);
+    check_both(|x| &x[0] - &x[1], &[a.clone(), b.clone()]);
+    check_both(|x| &x[0] * &x[1], &[a.clone(), b.clone()]);
+    check_both(|x| &x[0] / &x[1], &[a.clone(), b.clone()]);
+    check_both(|x| &x[0] + 2.5, &[a.clone()]);
+    check_both(|x| &x[0] * 1.5, &[a.clone()]);
+    check_both(|x| &x[0] / 4.0, &[a.clone()]);
+    check_both(|x| -&x[0], &[a.clone()]);
+    check_both(|x| x[0].exp(), &[a]);
+}
+
+#[test]
+fn gradcheck_matrix_nodes() {
+    init_rovo();
+    let a = double_leaf(&[0.5, -1.2, 2.0, 0.3, 1.1, -0.7], &[2, 3]);
+    let b = double_leaf(&[1.5, 0.8, -2.1, 1.3, -0.9, 2.4], &[3, 2]);
+    let bias = double_leaf(&[0.1, -0.2, 0.3, 0.4], &[2, 2]);
+    check_both(|x| x[0].t(), &[a.clone()]);
+    check_both(|x| x[0].mm(&x[1], false), &[a.clone(), b.clone()]);
+    check_both(|x| addmm(&x[0], &x[1], &x[2], 1.0, 1.0), &[bias, a, b]);
+}
+
+#[test]
+fn gradcheck_reduction_and_shape_nodes() {
+    init_rovo();
+    let a = double_leaf(&[0.5, -1.2, 2.0, 0.3, 1.1, -0.7], &[2, 3]);
+    check_both(|x| x[0].sum(), &[a.clone()]);
+    check_both(|x| x[0].sum_dim(&[1], true), &[a.clone()]);
+    check_both(|x| x[0].sum_dim(&[0], false), &[a.clone()]);
+    check_both(|x| x[0].mean(), &[a.clone()]);
+    check_both(|x| x[0].view(&[3, 2]), &[a.clone()]);
+    check_both(|x| x[0].unsqueeze(0).expand(&[4, 2, 3], false), &[a]);
+}
+
+#[test]
+fn gradcheck_activation_and_loss_nodes() {
+    init_rovo();
+    let a = double_leaf(&[0.5, -1.2, 2.0, 0.3, 1.1, -0.7], &[2, 3]);
+    let target = tensor(
+        &[1.0f64, 0.0, 1.0, 0.0, 0.0, 1.0],
+        TensorOptions::with_dtype(ScalarType::Double),
+    )
+    .view(&[2, 3]);
+    let class_target = tensor(&[2i64, 0], None);
+    check_both(|x| sigmoid(&x[0]), &[a.clone()]);
+    check_both(|x| x[0].log_softmax(1, None), &[a.clone()]);
+    for reduction in [Reduction::None, Reduction::Mean, Reduction::Sum] {
+        check_both(
+            |x| binary_cross_entropy(&sigmoid(&x[0]), &target, None, reduction),
+            &[a.clone()],
+        );
+    }
+    for reduction in [Reduction::None, Reduction::Mean, Reduction::Sum] {
+        check_both(
+            |x| {
+                nll_loss(
+                    &x[0].log_softmax(1, None),
+                    &class_target,
+                    None,
+                    reduction,
+                    -100,
+                )
+            },
+            &[a.clone()],
+        );
+    }
+}
+
+#[test]
+#[should_panic(expected = "to be of dtype Double")]
+fn gradcheck_rejects_float_inputs() {
+    init_rovo();
+    let a = tensor(&[0.5f32, -1.2, 2.0], TensorOptions::with_requires_grad());
+    gradcheck(|x| x[0].exp(), &[a], 1e-6, 1e-5, 1e-3);
+}
+
+#[test]
+#[should_panic(expected = "gradcheck: Jacobian mismatch for output 0 with respect to input 0")]
+fn gradcheck_reports_wrong_gradients() {
+    init_rovo();
+    // The detached input hides the dependency from the engine.
+    let a = double_leaf(&[0.5, -1.2, 2.0], &[3]);
+    gradcheck(|x| x[0].tensor_data().exp(), &[a], 1e-6, 1e-5, 1e-3);
+}
+
+#[test]
+#[should_panic(expected = "Function 'DivBackwardTensors' returned nan or inf values")]
+fn detect_anomaly_reports_node() {
+    init_rovo();
+    let _guard = detect_anomaly();
+    let a = double_leaf(&[1.0, 2.0], &[2]);
+    let b = double_leaf(&[0.0, 1.0], &[2]);
+    let y = (&a / &b).sum();
+    backward(&vec![y], &vec![], false);
+}
+
+#[test]
+#[should_panic(expected = "Function 'DivBackwardTensors' returned nan or inf values")]
+fn detect_anomaly_checks_half_gradients() {
+    init_rovo();
+    let _guard = detect_anomaly();
+    let a = full(&[2], 1.0, TensorOptions::with_dtype(ScalarType::Half));
+    let b = full(&[2], 0.0, TensorOptions::with_dtype(ScalarType::Half));
+    a.set_requires_grad(true);
+    b.set_requires_grad(true);
+    backward(&vec![(&a / &b).sum()], &vec![], false);
+}
+
+#[test]
+#[should_panic(expected = "Function 'DivBackwardTensors' returned nan or inf values")]
+fn detect_anomaly_checks_complex_gradients() {
+    init_rovo();
+    let _guard = detect_anomaly();
+    let c = |re, im| ComplexDouble::new(re, im);
+    let z = tensor(&[c(1.0, 2.0)], TensorOptions::with_requires_grad());
+    let w = tensor(&[c(0.0, 0.0)], TensorOptions::with_requires_grad());
+    backward(&vec![(&z / &w).real().sum()], &vec![], false);
+}
+
+#[test]
+fn inplace_ops_bump_version() {
+    init_rovo();
+    let mut a = double_leaf(&[1.0, 2.0, 3.0, 4.0], &[2, 2]).tensor_data();
+    let other = a.tensor_data();
+    assert_eq!(a._version(), 0);
+    a.fill_(1.0);
+    a.mul_(&other);
+    a.add_(&other);
+    assert_eq!(a._version(), 3);
+    // Views share the version counter with their base.
+    let mut t = a.t();
+    t.div_(&other);
+    assert_eq!(a._version(), 4);
+    assert_eq!(t._version(), 4);
+}
+
+#[test]
+#[should_panic(
+    expected = "one of the variables needed for gradient computation has been modified by an inplace operation: [Double [2]] is at version 1; expected version 0 instead."
+)]
+fn inplace_modification_of_saved_leaf() {
+    init_rovo();
+    let a = double_leaf(&[1.0, 2.0], &[2]);
+    let b = double_leaf(&[3.0, 4.0], &[2]);
+    let y = (&a * &b).sum();
+    b.tensor_data().fill_(0.0);
+    backward(&vec![y], &vec![], false);
+}
+
+#[test]
+#[should_panic(
+    expected = "which is output 0 of SigmoidBackward, is at version 1; expected version 0 instead."
+)]
+fn inplace_modification_of_saved_output() {
+    init_rovo();
+    let a = double_leaf(&[1.0, 2.0], &[2]);
+    let mut s = sigmoid(&a);
+    let y = s.sum();
+    let other = s.tensor_data();
+    s.mul_(&other);
+    backward(&vec![y], &vec![], false);
+}
+
+#[test]
+fn inference_mode_skips_autograd() {
+    init_rovo();
+    let linear = Linear::new(4, 3);
+    let x = full(&[2, 4], 3.0, TensorOptions::default());
+    let mut normal = x.tensor_data();
+    {
+        let _guard = InferenceMode::default();
+        assert!(!GradMode::is_enabled());
+        let y = linear.forward(&[&x]);
+        assert!(y.is_inference());
+        assert!(!y.requires_grad());
+        assert!(y.grad_fn().is_none());
+        // In-place updates are allowed inside the guard, and normal tensors keep
+        // tracking their version.
+        y.fill_(1.0);
+        let version = normal._version();
+        normal.mul_(&x);
+        assert_eq!(normal._version(), version + 1);
+        assert!(!normal.is_inference());
+        // The guard only applies to the thread that created it.
+        std::thread::spawn(|| assert!(!InferenceMode::is_enabled() && GradMode::is_enabled()))
+            .join()
+            .unwrap();
+    }
+    assert!(GradMode::is_enabled());
+    assert!(!InferenceMode::is_enabled());
+    assert!(!linear.forward(&[&x]).is_inference());
+}
+
+#[test]
+#[should_panic(expected = "Inference tensors cannot be saved for backward.")]
+fn inference_tensor_saved_for_backward() {
+    init_rovo();
+    let linear = Linear::new(4, 3);
+    let x = {
+        let _guard = InferenceMode::default();
+        full(&[2, 4], 3.0, TensorOptions::default())
+    };
+    linear.forward(&[&x]);
+}
+
+#[test]
+#[should_panic(
+    expected = "Inplace update to inference tensor outside InferenceMode is not allowed."
+)]
+fn inference_tensor_inplace_outside_guard() {
+    init_rovo();
+    let x = {
+        let _guard = InferenceMode::default();
+        full(&[2, 4], 3.0, TensorOptions::default())
+    };
+    x.fill_(0.0);
+}
+
+#[test]
+fn graph_to_dot() {
+    init_rovo();
+    let a = double_leaf(&[0.5, -1.2, 2.0, 0.3, 1.1, -0.7], &[2, 3]);
+    let b = double_leaf(&[1.5, 0.8, -2.1, 1.3, -0.9, 2.4], &[3, 2]);
+    let y = sigmoid(&a.mm(&b, false)).sum();
+    let dot = graph::to_dot(&y);
+    assert!(dot.starts_with("digraph {"));
+    assert!(dot.contains("SumBackward0"));
+    assert!(dot.contains("SigmoidBackward\\ninput 0: [2, 2]"));
+    assert!(dot.contains("MmBackward"));
+    assert_eq!(dot.matches("Accumulate_Grad").count(), 2);
+    assert!(dot.contains("leaf\\n[3, 2]\\nrequires_grad=true"));
+}
+
+#[test]
+fn checkpoint_replays_rng_state() {
+    init_rovo();
+    let x = double_leaf(&[1.0; 6], &[2, 3]);
+    let y = checkpoint(
+        |x| {
+            let noise = empty_like(&x[0], x[0].options(), None);
+            noise.uniform(0.0, 1.0);
+            &x[0] * &noise
+        },
+        &[x.clone()],
+        &[],
+    );
+    assert!(y.requires_grad());
+    backward(&vec![y.sum()], &vec![], false);
+    // x is all ones, so its gradient is the noise drawn by the forward pass.
+    assert_eq!(format!("{:?}", x.grad().unwrap()), format!("{:?}", y));
+}
+
+#[test]
+fn gradcheck_through_checkpoint() {
+    init_rovo();
+    let a = double_leaf(&[0.5, -1.2, 2.0, 0.3, 1.1, -0.7], &[2, 3]);
+    let b = double_leaf(&[1.5, 0.8, -2.1, 1.3, -0.9, 2.4], &[3, 2]);
+    // b is used as a parameter of the checkpointed function rather than as its input.
+    check_both(
+        |x| {
+            let w = x[1].clone();
+            checkpoint(move |y| sigmoid(&y[0].mm(&w, false)), &x[..1], &x[1..])
+        },
+        &[a.clone(), b.clone()],
+    );
+}
+
+#[test]
+fn checkpoint_grad_leaves_parameters_untouched() {
+    init_rovo();
+    let x = double_leaf(&[0.5, -1.2, 2.0, 0.3, 1.1, -0.7], &[2, 3]);
+    let w = double_leaf(&[1.5, 0.8, -2.1, 1.3, -0.9, 2.4], &[3, 2]);
+    let w_ = w.clone();
+    let y = checkpoint(
+        move |y| sigmoid(&y[0].mm(&w_, false)),
+        &[x.clone()],
+        &[w.clone()],
+    );
+    let expected = sigmoid(&x.mm(&w, false)).sum();
+    let expected = grad(&vec![expected], &vec![x.clone()], &vec![], false);
+    let result = grad(&vec![y.sum()], &vec![x.clone()], &vec![], false);
+    assert_eq!(format!("{:?}", result), format!("{:?}", expected));
+    assert!(x.grad().is_none());
+    assert!(w.grad().is_none());
+}
+
+#[test]
+fn checkpoint_without_grad_records_nothing() {
+    init_rovo();
+    let x = tensor(
+        &[1.0f64, 2.0],
+        TensorOptions::with_dtype(ScalarType::Double),
+    );
+    let y = checkpoint(|x| x[0].exp(), &[x.clone()], &[]);
+    assert!(!y.requires_grad());
+    assert!(y.grad_fn().is_none());
+}
+
+fn double_tensor(numel: usize, sizes: &[usize]) -> Tensor {
+    let data: Vec<f64> = (0..numel).map(|i| 0.3 * i as f64 - 0.7).collect();
+    tensor(
+        data.as_slice(),
+        TensorOptions::with_dtype(ScalarType::Double),
+    )
+    .view(sizes)
+}
+
+fn item(tensor: &Tensor) -> f64 {
+    unsafe { *tensor.data_ptr_casted::<f64>() }
+}
+
+// Checks u^T (J v) computed in forward mode against (J^T u)^T v computed in reverse mode.
+fn check_jvp<F: Fn(&[Tensor]) -> Tensor>(func: F, inputs: &[Tensor]) {
+    let v: Vec<Tensor> = inputs
+        .iter()
+        .map(|x| double_tensor(x.numel(), x.sizes()))
+        .collect();
+    let (output, jv) = jvp(&func, inputs, &v, false, false);
+    // Use a different direction than v so a transposed jacobian doesn't go unnoticed.
+    let u = double_tensor(output.numel(), output.sizes()).exp();
+    let (_, vj) = vjp(&func, inputs, &u, false, false);
+    let forward = item(&(&u * &jv).sum());
+    let reverse: f64 = vj
+        .iter()
+        .zip(v.iter())
+        .map(|(g, v)| item(&(g * v).sum()))
+        .sum();
+    assert!(
+        (forward - reverse).abs() < 1e-10,
+        "forward mode gave {} but reverse mode gave {}",
+        forward,
+        reverse
+    );
+}
+
+#[test]
+fn forward_ad_dual_tensors() {
+    init_rovo();
+    let a = double_leaf(&[0.5, -1.2, 2.0], &[3]);
+    let tangent = double_tensor(3, &[3]);
+    let dual = {
+        let _level = dual_level();
+        let dual = make_dual(&a, &tangent);
+        let y = &dual * &dual;
+        let (primal, y_tangent) = unpack_dual(&y);
+        assert_eq!(format!("{:?}", primal), format!("{:?}", &a * &a));
+        assert_eq!(
+            format!("{:?}", y_tangent.unwrap()),
+            format!("{:?}", &(&a * &tangent) * 2.0)
+        );
+        assert!(unpack_dual(&primal).1.is_none());
+        dual
+    };
+    // Tangents are dropped with their level, the primal still backpropagates to a.
+    assert!(unpack_dual(&dual).1.is_none());
+    backward(&vec![dual.sum()], &vec![], false);
+    assert!(a.grad().is_some());
+}
+
+#[test]
+fn forward_ad_matches_reverse_mode() {
+    init_rovo();
+    let a = double_leaf(&[0.5, -1.2, 2.0, 0.3, 1.1, -0.7], &[2, 3]);
+    let b = double_leaf(&[1.5, 0.8, -2.1, 1.3, -0.9, 2.4], &[2, 3]);
+    let c = double_leaf(&[1.5, 0.8, -2.1, 1.3, -0.9, 2.4], &[3, 2]);
+    let bias = double_leaf(&[0.1, -0.2, 0.3, 0.4], &[2, 2]);
+    let target = tensor(
+        &[1.0f64, 0.0, 1.0, 0.0, 0.0, 1.0],
+        TensorOptions::with_dtype(ScalarType::Double),
+    )
+    .view(&[2, 3]);
+    let class_target = tensor(&[2i64, 0], None);
+    check_jvp(
+        |x| &(&x[0] + &x[1]) - &(&x[0] * &x[1]),
+        &[a.clone(), b.clone()],
+    );
+    check_jvp(
+        |x| &(-&(&x[0] / &x[1]) + 2.5) * 1.5 / 4.0,
+        &[a.clone(), b.clone()],
+    );
+    check_jvp(|x| x[0].exp().t(), &[a.clone()]);
+    check_jvp(|x| x[0].mm(&x[1], false), &[a.clone(), c.clone()]);
+    check_jvp(
+        |x| addmm(&x[0], &x[1], &x[2], 1.0, 1.0),
+        &[bias, a.clone(), c.clone()],
+    );
+    check_jvp(|x| x[0].sum_dim(&[1], true).mean(), &[a.clone()]);
+    check_jvp(|x| x[0].view(&[3, 2]).sum(), &[a.clone()]);
+    check_jvp(
+        |x| x[0].unsqueeze(0).expand(&[4, 2, 3], false),
+        &[a.clone()],
+    );
+    check_jvp(|x| x[0].log_softmax(1, None), &[a.clone()]);
+    for reduction in [Reduction::None, Reduction::Mean, Reduction::Sum] {
+        check_jvp(
+            |x| binary_cross_entropy(&sigmoid(&x[0]), &target, None, reduction),
+            &[a.clone()],
+        );
+        check_jvp(
+            |x| {
+                nll_loss(
+                    &x[0].log_softmax(1, None),
+                    &class_target,
+                    None,
+                    reduction,
+                    -100,
+                )
+            },
+            &[a.clone()],
+        );
+    }
+}
+
+fn assert_close(a: &Tensor, b: &Tensor) {
+    assert_eq!(a.sizes(), b.sizes());
+    let diff = a - b;
+    assert!(item(&(&diff * &diff).sum()) < 1e-20, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn functional_jacobian() {
+    init_rovo();
+    let a = double_leaf(&[0.5, -1.2, 2.0, 0.3, 1.1, -0.7], &[2, 3]);
+    let b = double_leaf(&[1.5, 0.8, -2.1, 1.3, -0.9, 2.4], &[3, 2]);
+    let f = |x: &[Tensor]| sigmoid(&x[0].mm(&x[1], false));
+    let jac = jacobian(f, &[a.clone(), b.clone()], false, false, false);
+    assert_eq!(jac[0].sizes(), &[2, 2, 2, 3]);
+    assert_eq!(jac[1].sizes(), &[2, 2, 3, 2]);
+    assert!(!jac[0].requires_grad());
+    let vectorized = jacobian(f, &[a.clone(), b.clone()], false, false, true);
+    assert_close(&jac[0], &vectorized[0]);
+    assert_close(&jac[1], &vectorized[1]);
+
+    // d(a * 2)_ij / da_kl is 2 when (i, j) == (k, l).
+    let jac = jacobian(|x| &x[0] * 2.0, &[a.clone()], false, false, false);
+    let mut expected = vec![0.0; 36];
+    for i in 0..6 {
+        expected[i * 7] = 2.0;
+    }
+    let expected = tensor(
+        expected.as_slice(),
+        TensorOptions::with_dtype(ScalarType::Double),
+    );
+    assert_close(&jac[0], &expected.view(&[2, 3, 2, 3]));
+}
+
+#[test]
+#[should_panic(expected = "independent of input 1. This is not allowed in strict mode.")]
+fn functional_jacobian_strict() {
+    init_rovo();
+    let a = double_leaf(&[0.5, -1.2, 2.0], &[3]);
+    let b = double_leaf(&[1.5, 0.8, -2.1], &[3]);
+    jacobian(|x| &x[0] * 2.0, &[a, b], false, true, false);
+}
+
+#[test]
+fn functional_hessian_and_hvp() {
+    init_rovo();
+    // The hessian of sum(x^3) is diag(6x).
+    let x = double_leaf(&[0.5, -1.2, 2.0], &[3]);
+    let h = hessian(
+        |x| (&(&x[0] * &x[0]) * &x[0]).sum(),
+        &[x],
+        false,
+        false,
+        false,
+    );
+    let expected = tensor(
+        &[3.0f64, 0.0, 0.0, 0.0, -7.2, 0.0, 0.0, 0.0, 12.0],
+        TensorOptions::with_dtype(ScalarType::Double),
+    );
+    assert_close(&h[0][0], &expected.view(&[3, 3]));
+
+    let a = double_leaf(&[0.5, -1.2, 2.0, 0.3, 1.1, -0.7], &[2, 3]);
+    let b = double_leaf(&[1.5, 0.8, -2.1, 1.3, -0.9, 2.4], &[3, 2]);
+    let f = |x: &[Tensor]| sigmoid(&x[0].mm(&x[1], false)).sum();
+    let inputs = [a.clone(), b.clone()];
+    let h = hessian(f, &inputs, false, false, false);
+    assert_eq!(h[0][1].sizes(), &[2, 3, 3, 2]);
+    let vectorized = hessian(f, &inputs, false, false, true);
+    for i in 0..2 {
+        for j in 0..2 {
+            assert_close(&h[i][j], &vectorized[i][j]);
+        }
+    }
+
+    let v = [double_tensor(6, &[2, 3]), double_tensor(6, &[3, 2])];
+    let (output, hv) = hvp(f, &inputs, &v, false, false);
+    assert_close(&output, &f(&inputs));
+    for i in 0..2 {
+        let mut expected = double_tensor(6, &[6]) * 0.0;
+        for j in 0..2 {
+            let block = h[i][j].view(&[6, 6]);
+            expected = &expected + &block.mm(&v[j].view(&[6, 1]), false).view(&[6]);
+        }
+        assert_close(&hv[i].view(&[6]), &expected);
+    }
+}
+
+fn complex_values(tensor: &Tensor) -> Vec<ComplexDouble> {
+    let tensor = tensor.to_dtype(ScalarType::ComplexDouble).contiguous();
+    unsafe {
+        std::slice::from_raw_parts(tensor.data_ptr_casted::<ComplexDouble>(), tensor.numel())
+            .to_vec()
+    }
+}
+
+fn assert_complex_close(tensor: &Tensor, expected: &[ComplexDouble]) {
+    let values = complex_values(tensor);
+    assert_eq!(values.len(), expected.len());
+    for (v, e) in values.iter().zip(expected) {
+        assert!((v - e).norm() < 1e-6, "{:?} != {:?}", values, expected);
+    }
+}
+
+// Gradients of real losses with respect to complex inputs are dL/dx + i dL/dy.
+#[test]
+fn complex_backward() {
+    init_rovo();
+    let c = |re, im| ComplexDouble::new(re, im);
+    let z = tensor(
+        &[c(3.0, 4.0), c(0.0, -2.0)],
+        TensorOptions::with_requires_grad(),
+    );
+    backward(&vec![z.abs().sum()], &vec![], false);
+    assert_complex_close(&z.grad().unwrap(), &[c(0.6, 0.8), c(0.0, -1.0)]);
+
+    let z = tensor(
+        &[c(1.0, 2.0), c(-1.0, 0.5)],
+        TensorOptions::with_requires_grad(),
+    );
+    let w = tensor(&[c(2.0, -1.0), c(0.0, 3.0)], None);
+    backward(&vec![(&z * &w).real().sum()], &vec![], false);
+    assert_complex_close(&z.grad().unwrap(), &[c(2.0, 1.0), c(0.0, -3.0)]);
+
+    let z = tensor(&[c(1.0, 2.0)], TensorOptions::with_requires_grad());
+    backward(&vec![(&z * &w.sum()).imag().sum()], &vec![], false);
+    // Im(z w) = x Im(w) + y Re(w) with w = 2 + 2i.
+    assert_complex_close(&z.grad().unwrap(), &[c(2.0, 2.0)]);
+
+    // Real inputs get the real part of the gradient.
+    let x = double_leaf(&[1.0, -2.0], &[2]);
+    backward(&vec![(&x * &w).real().sum()], &vec![], false);
+    let grad = x.grad().unwrap();
+    assert_eq!(grad.scalar_type(), ScalarType::Double);
+    assert_complex_close(&grad, &[c(2.0, 0.0), c(0.0, 0.0)]);
+
+    let a = tensor(
+        &[c(1.0, 1.0), c(0.0, 2.0), c(1.0, 0.0), c(0.0, -1.0)],
+        TensorOptions::with_requires_grad(),
+    );
+    let b = tensor(&[c(2.0, 0.0), c(1.0, 0.0), c(0.0, 1.0), c(1.0, 1.0)], None).view(&[2, 2]);
+    backward(
+        &vec![a.view(&[2, 2]).mm(&b, false).real().sum()],
+        &vec![],
+        false,
+    );
+    // Row sums of conj(b) for each column of a.
+    assert_complex_close(
+        &a.grad().unwrap(),
+        &[c(3.0, 0.0), c(1.0, -2.0), c(3.0, 0.0), c(1.0, -2.0)],
+    );
+
+    let r = double_leaf(&[2.0], &[1]);
+    let theta = double_leaf(&[0.5], &[1]);
+    let p = rovo::tensor::polar(&r, &theta);
+    backward(&vec![p.angle().sum()], &vec![], false);
+    assert!((item(&theta.grad().unwrap()) - 1.0).abs() < 1e-12);
+    assert!(item(&r.grad().unwrap()).abs() < 1e-12);
+}
@@ -0,0 +1,348 @@
+use rovo::autograd::full;
+use rovo::c10::ScalarType;
+use rovo::core::create_cpu_generator;
+use rovo::data::csv::{CsvDataset, CsvOptionsBuilder, CsvRow, CsvStream, CsvTarget, Fill};
+use rovo::data::datasets::{load_idx, IdxData, Mnist};
+use rovo::data::{
+    BatchSampler, DataLoader, DataLoaderOptions, DataLoaderOptionsBuilder, Dataset,
+    IterableDataLoader, IterableDataset, Sampler, SequentialSampler, SubsetRandomSampler,
+    WeightedRandomSampler,
+};
+use rovo::init_rovo;
+use rovo::tensor::Tensor;
+
+// Sample `i` has features [i, 2i] and label i.
+struct Squares {
+    len: usize,
+}
+
+impl Dataset for Squares {
+    type Item = (Vec<f32>, i64);
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn get(&self, index: usize) -> Self::Item {
+        (vec![index as f32, 2.0 * index as f32], index as i64)
+    }
+}
+
+fn values(t: &Tensor) -> Vec<i64> {
+    let t = t.contiguous();
+    unsafe { std::slice::from_raw_parts(t.data_ptr_casted::<i64>(), t.numel()).to_vec() }
+}
+
+fn labels<D: Dataset>(loader: &mut DataLoader<D, (Tensor, Tensor)>) -> Vec<Vec<i64>> {
+    loader.iter().map(|(_, labels)| values(&labels)).collect()
+}
+
+#[test]
+fn dataloader_batches_in_order() {
+    init_rovo();
+    let mut loader = DataLoader::new(Squares { len: 10 }, DataLoaderOptions::new(4));
+    assert_eq!(loader.len(), 3);
+    let batches: Vec<_> = loader.iter().collect();
+    assert_eq!(batches.len(), 3);
+    let (features, last_labels) = &batches[2];
+    assert_eq!(features.sizes(), &[2, 2]);
+    let features = features.contiguous();
+    let features = unsafe { std::slice::from_raw_parts(features.data_ptr_casted::<f32>(), 4) };
+    assert_eq!(features, &[8.0, 16.0, 9.0, 18.0]);
+    assert_eq!(values(last_labels), vec![8, 9]);
+
+    let options = DataLoaderOptionsBuilder::new(4).drop_last(true).build();
+    let mut loader = DataLoader::new(Squares { len: 10 }, options);
+    assert_eq!(loader.len(), 2);
+    assert_eq!(
+        labels(&mut loader),
+        vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7]]
+    );
+}
+
+#[test]
+fn dataloader_shuffle_is_seeded() {
+    init_rovo();
+    let loader = |seed| {
+        let options = DataLoaderOptionsBuilder::new(3)
+            .shuffle(true)
+            .generator(create_cpu_generator(Some(seed)))
+            .build();
+        DataLoader::new(Squares { len: 10 }, options)
+    };
+    let mut first = loader(7);
+    let epoch1 = labels(&mut first);
+    let epoch2 = labels(&mut first);
+    assert_ne!(epoch1, epoch2);
+    assert_eq!(labels(&mut loader(7)), epoch1);
+    for epoch in &[epoch1, epoch2] {
+        let mut seen: Vec<_> = epoch.iter().flatten().cloned().collect();
+        seen.sort_unstable();
+        assert_eq!(seen, (0..10).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn dataloader_workers_keep_order() {
+    init_rovo();
+    let options = DataLoaderOptionsBuilder::new(3)
+        .shuffle(true)
+        .generator(create_cpu_generator(Some(3)))
+        .build();
+    let mut single = DataLoader::new(Squares { len: 20 }, options.clone());
+    let mut workers = DataLoader::new(Squares { len: 20 }, options).num_workers(3);
+    assert_eq!(labels(&mut workers), labels(&mut single));
+    // Dropping an unfinished pass stops the workers.
+    assert_eq!(workers.iter().take(2).count(), 2);
+    assert_eq!(labels(&mut workers).len(), 7);
+}
+
+struct Points;
+
+impl Dataset for Points {
+    type Item = Tensor;
+
+    fn len(&self) -> usize {
+        5
+    }
+
+    fn get(&self, index: usize) -> Self::Item {
+        full(&[2], index as f32, None)
+    }
+}
+
+#[test]
+fn dataloader_custom_collate_and_sampler() {
+    init_rovo();
+    let mut loader = DataLoader::new(Points, DataLoaderOptions::new(2));
+    let sizes: Vec<_> = loader.iter().map(|batch| batch.sizes().to_vec()).collect();
+    assert_eq!(sizes, vec![vec![2, 2], vec![2, 2], vec![1, 2]]);
+
+    let mut loader =
+        DataLoader::new_with_collate(Points, DataLoaderOptions::new(2), |batch| batch.len())
+            .with_sampler(SubsetRandomSampler::new(vec![0, 2, 4], None));
+    assert_eq!(loader.iter().collect::<Vec<_>>(), vec![2, 1]);
+}
+
+#[test]
+fn samplers() {
+    init_rovo();
+    let mut subset = SubsetRandomSampler::new(vec![1, 5, 7], Some(create_cpu_generator(Some(1))));
+    let mut indices = subset.sample();
+    indices.sort_unstable();
+    assert_eq!(indices, vec![1, 5, 7]);
+
+    let weights = vec![0.0, 3.0, 0.0, 1.0];
+    let gen = || Some(create_cpu_generator(Some(2)));
+    let mut weighted = WeightedRandomSampler::new(weights.clone(), 100, true, gen());
+    let drawn = weighted.sample();
+    assert_eq!(drawn.len(), 100);
+    assert!(drawn.iter().all(|&i| i == 1 || i == 3));
+    assert!(drawn.iter().filter(|&&i| i == 1).count() > 50);
+    let mut weighted = WeightedRandomSampler::new(weights, 2, false, gen());
+    let mut drawn = weighted.sample();
+    drawn.sort_unstable();
+    assert_eq!(drawn, vec![1, 3]);
+
+    let mut batches = BatchSampler::new(SequentialSampler::new(5), 2, false);
+    assert_eq!(batches.len(), 3);
+    assert_eq!(batches.batches(), vec![vec![0, 1], vec![2, 3], vec![4]]);
+}
+
+struct Counter {
+    end: i64,
+}
+
+impl IterableDataset for Counter {
+    type Item = i64;
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Self::Item> + '_> {
+        Box::new(0..self.end)
+    }
+}
+
+#[test]
+fn iterable_dataloader() {
+    init_rovo();
+    let loader = IterableDataLoader::new(Counter { end: 7 }, 3, false);
+    let batches: Vec<_> = loader.iter().map(|batch| values(&batch)).collect();
+    assert_eq!(batches, vec![vec![0, 1, 2], vec![3, 4, 5], vec![6]]);
+    let loader = IterableDataLoader::new(Counter { end: 7 }, 3, true);
+    assert_eq!(loader.iter().count(), 2);
+}
+
+fn idx_bytes(code: u8, sizes: &[u32], data: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![0, 0, code, sizes.len() as u8];
+    for size in sizes {
+        bytes.extend_from_slice(&size.to_be_bytes());
+    }
+    bytes.extend_from_slice(data);
+    bytes
+}
+
+#[test]
+fn mnist_from_idx_files() {
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+    init_rovo();
+    let dir = std::env::temp_dir().join(format!("rovo_mnist_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // Three 2x2 images of i16 pixels, gzipped, and their u8 labels uncompressed.
+    let pixels: Vec<u8> = (0..12i16)
+        .flat_map(|p| (p * 1000).to_be_bytes().to_vec())
+        .collect();
+    let mut gz = GzEncoder::new(
+        std::fs::File::create(dir.join("t10k-images-idx3-ubyte.gz")).unwrap(),
+        Compression::default(),
+    );
+    gz.write_all(&idx_bytes(0x0B, &[3, 2, 2], &pixels)).unwrap();
+    gz.finish().unwrap();
+    std::fs::write(
+        dir.join("t10k-labels-idx1-ubyte"),
+        idx_bytes(0x08, &[3], &[7, 2, 1]),
+    )
+    .unwrap();
+
+    let labels = load_idx(dir.join("t10k-labels-idx1-ubyte")).unwrap();
+    assert_eq!(labels.sizes, vec![3]);
+    assert_eq!(labels.data, IdxData::U8(vec![7, 2, 1]));
+    let mnist = Mnist::new(&dir, false).unwrap();
+    assert!(Mnist::new(&dir, true).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(mnist.len(), 3);
+    assert_eq!(mnist.image_sizes(), &[2, 2]);
+    let (image, label) = mnist.get(1);
+    assert_eq!(label, 2);
+    assert_eq!(image.sizes(), &[2, 2]);
+    let image = unsafe { std::slice::from_raw_parts(image.data_ptr_casted::<f32>(), 4) };
+    let expected: Vec<f32> = (4..8)
+        .map(|p| (p * 1000) as f32 / i16::MAX as f32)
+        .collect();
+    assert_eq!(image, expected.as_slice());
+
+    let mut loader = DataLoader::new(mnist, DataLoaderOptions::new(2));
+    let (images, labels) = loader.iter().next().unwrap();
+    assert_eq!(images.sizes(), &[2, 2, 2]);
+    assert_eq!(values(&labels), vec![7, 2]);
+}
+
+#[test]
+fn malformed_idx_files() {
+    use rovo::data::datasets::read_idx;
+    use std::io::ErrorKind;
+    init_rovo();
+    // Data shorter than the header says, and a header whose size overflows.
+    let short = idx_bytes(0x08, &[2, 3], &[1, 2, 3]);
+    let huge = idx_bytes(0x0E, &[u32::MAX; 4], &[]);
+    for bytes in &[short, huge] {
+        let err = read_idx(&mut bytes.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    let dir = std::env::temp_dir().join(format!("rovo_idx_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (images, labels) = (dir.join("images"), dir.join("labels"));
+    std::fs::write(&labels, idx_bytes(0x08, &[1], &[7])).unwrap();
+    // A rank 0 array of images, and more images than labels.
+    for (sizes, data) in &[(&[][..], &[0u8][..]), (&[2, 2][..], &[0; 4][..])] {
+        std::fs::write(&images, idx_bytes(0x08, sizes, data)).unwrap();
+        let err = Mnist::from_files(&images, &labels).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+    // Samples of any rank are fine, e.g. rows of features or multi channel images.
+    for sample in &[&[4usize][..], &[1, 2, 2][..]] {
+        let sizes: Vec<u32> = std::iter::once(1)
+            .chain(sample.iter().map(|&s| s as u32))
+            .collect();
+        std::fs::write(&images, idx_bytes(0x08, &sizes, &[0; 4])).unwrap();
+        let mnist = Mnist::from_files(&images, &labels).unwrap();
+        assert_eq!(mnist.image_sizes(), *sample);
+        assert_eq!(mnist.get(0).0.sizes(), *sample);
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+fn floats(t: &Tensor) -> Vec<f32> {
+    let t = t.contiguous();
+    unsafe { std::slice::from_raw_parts(t.data_ptr_casted::<f32>(), t.numel()).to_vec() }
+}
+
+#[test]
+fn csv_dataset_and_stream() {
+    init_rovo();
+    let path = std::env::temp_dir().join(format!("rovo_csv_{}.csv", std::process::id()));
+    std::fs::write(
+        &path,
+        "age, city, income, label\n\
+         30, paris, 1.5, yes\n\
+         , tokyo, 2.5, no\n\
+         40, , NA, yes\n\
+         50, paris, 3.5,\n",
+    )
+    .unwrap();
+
+    let options = CsvOptionsBuilder::new()
+        .target("label")
+        .categorical(&["city", "label"])
+        .fill(Fill::Mean)
+        .build();
+    let dataset = CsvDataset::new(&path, &options).unwrap();
+    // The last row has no label.
+    assert_eq!(dataset.len(), 3);
+    assert_eq!(dataset.feature_names(), vec!["age", "income"]);
+    assert_eq!(dataset.categorical_names(), vec!["city"]);
+    assert_eq!(
+        dataset.categories("city").unwrap(),
+        &["paris".to_string(), "tokyo".to_string(), "".to_string()]
+    );
+    assert_eq!(
+        dataset.get(2),
+        CsvRow {
+            features: vec![40.0, 2.5],
+            categorical: vec![2],
+            target: Some(CsvTarget::Long(0)),
+        }
+    );
+    // The means include the unlabeled row.
+    assert_eq!(dataset.get(1).features, vec![40.0, 2.5]);
+
+    let mut loader = DataLoader::new(dataset, DataLoaderOptions::new(2)).num_workers(2);
+    let batch = loader.iter().next().unwrap();
+    let features = batch.features.unwrap();
+    assert_eq!(features.sizes(), &[2, 2]);
+    assert_eq!(floats(&features), vec![30.0, 1.5, 40.0, 2.5]);
+    assert_eq!(values(&batch.categorical.unwrap()), vec![0, 1]);
+    assert_eq!(values(&batch.target.unwrap()), vec![0, 1]);
+
+    let options = CsvOptionsBuilder::new()
+        .features(&["income"])
+        .target("age")
+        .target_dtype(ScalarType::Float)
+        .fill(Fill::Skip)
+        .build();
+    let stream = CsvStream::new(&path, &options).unwrap();
+    let loader = IterableDataLoader::new(stream, 4, false);
+    let batches: Vec<_> = loader.iter().collect();
+    assert_eq!(batches.len(), 1);
+    assert_eq!(
+        floats(batches[0].features.as_ref().unwrap()),
+        vec![1.5, 3.5]
+    );
+    assert_eq!(
+        floats(batches[0].target.as_ref().unwrap()),
+        vec![30.0, 50.0]
+    );
+    assert!(batches[0].categorical.is_none());
+
+    let options = CsvOptionsBuilder::new().target("missing").build();
+    assert!(CsvDataset::new(&path, &options).is_err());
+    // The target can't also be a feature.
+    let options = CsvOptionsBuilder::new()
+        .features(&["income", "age"])
+        .target("age")
+        .build();
+    assert!(CsvDataset::new(&path, &options).is_err());
+    assert!(CsvStream::new(&path, &options).is_err());
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,99 @@
+use rovo::aten::native;
+use rovo::autograd;
+use rovo::c10::{MemoryFormat, ScalarType, TensorOptions};
+use rovo::init_rovo;
+use rovo::tensor::Tensor;
+
+fn to_f64_vec(t: &Tensor) -> Vec<f64> {
+    let t = t.to_dtype(ScalarType::Double).contiguous();
+    unsafe { std::slice::from_raw_parts(t.data_ptr_casted::<f64>(), t.numel()).to_vec() }
+}
+
+fn nchw() -> Tensor {
+    let data: Vec<f32> = (0..24).map(|x| x as f32).collect();
+    autograd::tensor(data.as_slice(), None).view(&[2, 3, 2, 2])
+}
+
+#[test]
+fn contiguous_channels_last_strides() {
+    init_rovo();
+    let x = nchw();
+    assert!(x.is_contiguous());
+    assert!(!x.is_contiguous_(MemoryFormat::ChannelsLast));
+    assert_eq!(x.suggest_memory_format(false), MemoryFormat::Contiguous);
+
+    let y = x.contiguous_(MemoryFormat::ChannelsLast);
+    assert_eq!(y.sizes(), &[2, 3, 2, 2]);
+    assert_eq!(y.strides(), &[12, 1, 6, 3]);
+    assert!(y.is_contiguous_(MemoryFormat::ChannelsLast));
+    assert!(!y.is_contiguous());
+    assert!(y.is_non_overlapping_and_dense());
+    assert_eq!(y.suggest_memory_format(true), MemoryFormat::ChannelsLast);
+    assert_eq!(to_f64_vec(&y), to_f64_vec(&x));
+    // Already in the requested format, so nothing is copied.
+    assert_eq!(
+        y.contiguous_(MemoryFormat::ChannelsLast).data_ptr(),
+        y.data_ptr()
+    );
+}
+
+#[test]
+fn channels_last_permute_is_detected() {
+    init_rovo();
+    let nhwc = native::empty(&[2, 4, 5, 3], TensorOptions::default(), None);
+    // NHWC -> NCWH -> NCHW
+    let x = nhwc.transpose(1, 3).transpose(2, 3);
+    assert!(x.is_contiguous_(MemoryFormat::ChannelsLast));
+    assert_eq!(x.suggest_memory_format(false), MemoryFormat::ChannelsLast);
+    // A single pixel is ambiguous and stays contiguous.
+    let pixel = native::empty(&[2, 3, 1, 1], TensorOptions::default(), None);
+    assert_eq!(pixel.suggest_memory_format(false), MemoryFormat::Contiguous);
+}
+
+#[test]
+fn empty_with_channels_last_3d() {
+    init_rovo();
+    let x = native::empty(
+        &[2, 3, 4, 5, 6],
+        TensorOptions::default(),
+        MemoryFormat::ChannelsLast3d,
+    );
+    assert_eq!(x.strides(), &[360, 1, 90, 18, 3]);
+    assert!(x.is_contiguous_(MemoryFormat::ChannelsLast3d));
+    assert_eq!(x.suggest_memory_format(false), MemoryFormat::ChannelsLast3d);
+}
+
+#[test]
+#[should_panic(expected = "required rank 4 tensor to use channels_last format")]
+fn channels_last_needs_rank_4() {
+    init_rovo();
+    autograd::tensor(&[1.0f32, 2.0], None).contiguous_(MemoryFormat::ChannelsLast);
+}
+
+#[test]
+fn elementwise_ops_preserve_channels_last() {
+    init_rovo();
+    let x = nchw();
+    let y = x.contiguous_(MemoryFormat::ChannelsLast);
+    let sum = &y + &y;
+    assert!(sum.is_contiguous_(MemoryFormat::ChannelsLast));
+    assert_eq!(to_f64_vec(&sum), to_f64_vec(&(&x + &x)));
+
+    // Mixed formats follow the first input.
+    let mixed = &y * &x;
+    assert!(mixed.is_contiguous_(MemoryFormat::ChannelsLast));
+    assert_eq!(to_f64_vec(&mixed), to_f64_vec(&(&x * &x)));
+
+    let unary = y.exp();
+    assert!(unary.is_contiguous_(MemoryFormat::ChannelsLast));
+}
+
+#[test]
+fn to_preserves_channels_last() {
+    init_rovo();
+    let y = nchw().contiguous_(MemoryFormat::ChannelsLast);
+    let d = y.to_dtype(ScalarType::Double);
+    assert_eq!(d.strides(), y.strides());
+    let like = native::empty_like(&y, TensorOptions::default(), MemoryFormat::Preserve);
+    assert_eq!(like.strides(), y.strides());
+}
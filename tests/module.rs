@@ -2,13 +2,14 @@ use rovo::core::manual_seed;
 use rovo::init_rovo;
 use rovo::nn::Linear;
 use rovo::nn::Module;
-use rovo::optim::{Optimizer, SGDOptions, Sgd};
+use rovo::optim::{Optimizer, SGDOptions, SGDOptionsBuilder, Sgd};
+use rovo::serialize;
 use rovo::tensor::loss::Reduction;
 use rovo::tensor::sigmoid;
 use rovo::tensor::{binary_cross_entropy, Tensor};
 use rovo::{
     autograd::{self, backward, full, ones},
-    nn::Sequential,
+    nn::{ModuleDict, ModuleList, ParameterDict, ParameterList, Sequential},
 };
 use rovo::{c10::TensorOptions, nn::Functional};
 
@@ -131,3 +132,367 @@ fn sequential_sgd_two_steps() {
         println!("Result: {:?}", result);
     }
 }
+
+#[test]
+fn checkpoint_sequential_matches_forward() {
+    init_rovo();
+    manual_seed(0);
+    let x = full(&[4, 3], 1.5, TensorOptions::with_requires_grad());
+    let mut model = Sequential::new();
+    model.add(Linear::new(3, 4));
+    model.add(Functional::new(Functional::sigmoid()));
+    model.add(Linear::new(4, 4));
+    model.add(Functional::new(Functional::sigmoid()));
+    model.add(Linear::new(4, 2));
+    let sgd = Sgd::new(model.parameters().unwrap(), SGDOptions::new(0.1));
+    let grads = || {
+        let params = model.parameters().unwrap();
+        let grads: Vec<_> = params.iter().map(|p| p.grad()).collect();
+        format!("{:?} {:?}", grads, x.grad())
+    };
+
+    let y = model.forward(&[&x]);
+    backward(&vec![y.sum()], &vec![], false);
+    let expected = grads();
+
+    sgd.zero_grad();
+    x.grad().unwrap().zero_();
+    let y_checkpointed = autograd::checkpoint_sequential(&model, 2, &x);
+    assert_eq!(format!("{:?}", y), format!("{:?}", y_checkpointed));
+    backward(&vec![y_checkpointed.sum()], &vec![], false);
+    assert_eq!(expected, grads());
+}
+
+#[test]
+fn module_named_parameters_and_state() {
+    init_rovo();
+    manual_seed(0);
+    let mut inner = Sequential::new();
+    inner.add(Linear::new(4, 4));
+    let mut model = Sequential::new();
+    model.add(Linear::new(3, 4));
+    model.add(Functional::new(Functional::sigmoid()));
+    model.add(inner);
+
+    let names: Vec<_> = model.named_parameters().keys().cloned().collect();
+    assert_eq!(names, vec!["0.ws", "0.bs", "2.0.ws", "2.0.bs"]);
+    let modules: Vec<_> = model.named_modules().keys().cloned().collect();
+    assert_eq!(modules, vec!["0", "1", "2", "2.0"]);
+    assert_eq!(model.children().len(), 3);
+    assert!(model.buffers().is_empty());
+
+    assert!(model.is_training());
+    model.eval();
+    let mut visited = 0;
+    model.apply(&mut |m| {
+        assert!(!m.is_training());
+        visited += 1;
+    });
+    assert_eq!(visited, 5);
+    model.train(true);
+    assert!(model.modules().iter().all(|m| m.is_training()));
+
+    let x = full(&[2, 3], 1.5, None);
+    backward(&vec![model.forward(&[&x]).sum()], &vec![], false);
+    model.zero_grad();
+    for p in model.parameters().unwrap() {
+        let sum = p.grad().unwrap().sum();
+        assert_eq!(unsafe { *sum.data_ptr_casted::<f32>() }, 0.0);
+    }
+
+    model.requires_grad_(false);
+    assert!(model
+        .parameters()
+        .unwrap()
+        .iter()
+        .all(|p| !p.requires_grad()));
+}
+
+#[test]
+fn module_apply_visits_nested_modules() {
+    init_rovo();
+    let mut inner = Sequential::new();
+    inner.add(Linear::new(4, 4));
+    inner.add(Functional::new(Functional::sigmoid()));
+    let mut model = Sequential::new();
+    model.add(Linear::new(3, 4));
+    model.add(inner);
+
+    // (children, parameters) of each module, in the order apply visits them.
+    let mut visited = vec![];
+    model.apply(&mut |m| visited.push((m.children().len(), m.local_parameters().size())));
+    assert_eq!(visited, vec![(2, 0), (0, 2), (2, 0), (0, 2), (0, 0)]);
+
+    // Children are trait objects.
+    let inner = model.children()[1].clone();
+    let mut visited = 0;
+    inner.apply(&mut |_| visited += 1);
+    assert_eq!(visited, 3);
+}
+
+#[test]
+fn module_containers_in_module_tree() {
+    init_rovo();
+    let mut heads = ModuleList::new();
+    heads.push(Linear::new(4, 2)).push(Linear::new(4, 1));
+    let mut scales = ParameterList::new();
+    scales.push(ones(&[2], None));
+    let mut extra = ParameterDict::new();
+    extra.insert("bias", ones(&[1], None));
+    let mut model = ModuleDict::new();
+    model.insert("encoder", Linear::new(3, 4));
+    model.insert("heads", heads);
+    model.insert("scales", scales);
+    model.insert("extra", extra);
+
+    let names: Vec<_> = model.named_parameters().keys().cloned().collect();
+    assert_eq!(
+        names,
+        vec![
+            "encoder.ws",
+            "encoder.bs",
+            "heads.0.ws",
+            "heads.0.bs",
+            "heads.1.ws",
+            "heads.1.bs",
+            "scales.0",
+            "extra.bias"
+        ]
+    );
+    assert!(model
+        .parameters()
+        .unwrap()
+        .iter()
+        .all(|p| p.requires_grad()));
+    assert_eq!(model.len(), 4);
+    assert!(model.contains("heads") && !model.contains("decoder"));
+
+    let x = full(&[2, 3], 1.5, None);
+    let hidden = model["encoder"].forward(&[&x]);
+    assert_eq!(
+        model.named_modules().keys().collect::<Vec<_>>(),
+        vec!["encoder", "heads", "heads.0", "heads.1", "scales", "extra"]
+    );
+    model.eval();
+    assert!(model.modules().iter().all(|m| !m.is_training()));
+
+    let mut heads = ModuleList::new();
+    heads.push(Linear::new(4, 2));
+    heads.insert(0, Linear::new(4, 1));
+    assert_eq!(heads.len(), 2);
+    assert_eq!(heads[0].forward(&[&hidden]).sizes(), &[2, 1]);
+    let sizes: Vec<_> = heads
+        .iter()
+        .map(|head| head.forward(&[&hidden]).sizes().to_vec())
+        .collect();
+    assert_eq!(sizes, vec![vec![2, 1], vec![2, 2]]);
+}
+
+#[test]
+fn sequential_indexing_and_names() {
+    init_rovo();
+    let mut model = Sequential::new();
+    model.add(Linear::new(4, 2));
+    model.add_named("act", Functional::new(Functional::sigmoid()));
+    model.insert(0, Linear::new(3, 4));
+    assert_eq!(model.len(), 3);
+    assert!(model.get(3).is_none());
+    assert_eq!(
+        model.named_children().keys().collect::<Vec<_>>(),
+        vec!["0", "1", "act"]
+    );
+    assert_eq!(
+        model.named_parameters().keys().collect::<Vec<_>>(),
+        vec!["0.ws", "0.bs", "1.ws", "1.bs"]
+    );
+    let x = full(&[2, 3], 1.5, None);
+    let hidden = model.get(0).unwrap().forward(&[&x]);
+    assert_eq!(hidden.sizes(), &[2, 4]);
+    assert_eq!(model.forward(&[&x]).sizes(), &[2, 2]);
+}
+
+#[test]
+#[should_panic(expected = "Submodule name '0' is reserved for positional submodules")]
+fn sequential_rejects_positional_names() {
+    init_rovo();
+    let mut model = Sequential::new();
+    model.add(Linear::new(3, 4));
+    model.add_named("0", Linear::new(4, 2));
+}
+
+fn small_model() -> Sequential {
+    let mut model = Sequential::new();
+    model.add(Linear::new(3, 4));
+    model.add(Functional::new(Functional::sigmoid()));
+    model.add(Linear::new(4, 2));
+    model
+}
+
+fn train_step(model: &Sequential, sgd: &mut Sgd, x: &Tensor, target: &Tensor) {
+    sgd.zero_grad();
+    sgd.step(Some(|| {
+        let y = sigmoid(&model.forward(&[x]));
+        let loss = binary_cross_entropy(&y, target, None, Reduction::Mean);
+        backward::backward(&vec![loss.clone()], &vec![], false);
+        loss
+    }));
+}
+
+fn format_state(model: &Sequential) -> String {
+    let state_dict = model.state_dict();
+    format!("{:?}", state_dict.iter().collect::<Vec<_>>())
+}
+
+#[test]
+fn checkpoint_resumes_training_exactly() {
+    init_rovo();
+    manual_seed(0);
+    let dir = std::env::temp_dir();
+    let model_path = dir.join(format!("rovo_model_{}.ckpt", std::process::id()));
+    let optim_path = dir.join(format!("rovo_optim_{}.ckpt", std::process::id()));
+    let options = SGDOptionsBuilder::new(0.1).momentum(0.9).build();
+    let x = full(&[4, 3], 1.5, None);
+    let target = ones(&[4, 2], None);
+
+    let model = small_model();
+    let mut sgd = Sgd::new(model.parameters().unwrap(), options.clone());
+    for _ in 0..2 {
+        train_step(&model, &mut sgd, &x, &target);
+    }
+    serialize::save(&model.state_dict(), &model_path).unwrap();
+    serialize::save(&sgd.state_dict(), &optim_path).unwrap();
+    assert_eq!(sgd.state_dict().size(), 4);
+    for _ in 0..2 {
+        train_step(&model, &mut sgd, &x, &target);
+    }
+
+    let resumed = small_model();
+    let mut resumed_sgd = Sgd::new(resumed.parameters().unwrap(), options);
+    let missing = resumed.load_state_dict(&serialize::load(&model_path).unwrap(), true);
+    assert_eq!(missing, Default::default());
+    resumed_sgd.load_state_dict(&serialize::load(&optim_path).unwrap());
+    for _ in 0..2 {
+        train_step(&resumed, &mut resumed_sgd, &x, &target);
+    }
+    assert_eq!(format_state(&model), format_state(&resumed));
+
+    std::fs::remove_file(model_path).unwrap();
+    std::fs::remove_file(optim_path).unwrap();
+}
+
+#[test]
+fn sgd_weight_decay_scales_with_parameters() {
+    init_rovo();
+    let p = autograd::tensor(&[1.0f32, -2.0], None);
+    p.set_requires_grad(true);
+    let mut options = SGDOptions::new(1.0);
+    options.set_weight_decay(0.5);
+    let mut sgd = Sgd::new(vec![p.clone()], options);
+    backward(&vec![(&p * &full(&[2], 0.0, None)).sum()], &vec![], false);
+    sgd.step::<fn() -> Tensor>(None);
+    // p -= lr * (grad + weight_decay * p)
+    let values = unsafe { std::slice::from_raw_parts(p.data_ptr_casted::<f32>(), 2) };
+    assert_eq!(values, &[0.5, -1.0]);
+}
+
+#[test]
+#[should_panic(expected = "size mismatch for state.0.momentum_buffer")]
+fn sgd_load_state_dict_checks_buffer_sizes() {
+    init_rovo();
+    let options = SGDOptionsBuilder::new(0.1).momentum(0.9).build();
+    let mut sgd = Sgd::new(small_model().parameters().unwrap(), options);
+    let mut state_dict = rovo::util::OrderedDict::new_with_key_description("Tensor".to_string());
+    state_dict.insert("state.0.momentum_buffer".to_string(), ones(&[4, 4], None));
+    sgd.load_state_dict(&state_dict);
+}
+
+#[test]
+fn serialize_keeps_strides() {
+    init_rovo();
+    let path = std::env::temp_dir().join(format!("rovo_strides_{}.ckpt", std::process::id()));
+    let model = small_model();
+    let ws = model
+        .named_parameters()
+        .find(&"0.ws".to_string())
+        .unwrap()
+        .t();
+    let mut tensors = rovo::util::OrderedDict::new_with_key_description("Tensor".to_string());
+    tensors.insert("ws_t".to_string(), ws.tensor_data());
+    serialize::save(&tensors, &path).unwrap();
+    let loaded = serialize::load(&path).unwrap();
+    let loaded = loaded.find(&"ws_t".to_string()).unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(loaded.sizes(), ws.sizes());
+    assert_eq!(loaded.strides(), ws.strides());
+    assert_eq!(format!("{:?}", loaded), format!("{:?}", ws.tensor_data()));
+}
+
+// A checkpoint holding a float tensor [1.0, 2.0] under each of `keys`.
+fn checkpoint_bytes(keys: &[&str]) -> Vec<u8> {
+    let mut bytes = b"ROVOCKPT".to_vec();
+    bytes.extend_from_slice(&1u32.to_le_bytes());
+    bytes.extend_from_slice(&(keys.len() as u64).to_le_bytes());
+    for key in keys {
+        bytes.extend_from_slice(&(key.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(key.as_bytes());
+        bytes.push(2);
+        // ndim, size, stride, data length
+        for value in &[1u64, 2, 1, 8] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&1.0f32.to_le_bytes());
+        bytes.extend_from_slice(&2.0f32.to_le_bytes());
+    }
+    bytes
+}
+
+#[test]
+fn load_rejects_corrupt_checkpoints() {
+    init_rovo();
+    let path = std::env::temp_dir().join(format!("rovo_corrupt_{}.ckpt", std::process::id()));
+    let load = |bytes: &[u8]| {
+        std::fs::write(&path, bytes).unwrap();
+        serialize::load(&path)
+    };
+    let valid = checkpoint_bytes(&["a"]);
+    assert_eq!(load(&valid).unwrap().size(), 1);
+
+    let duplicate = load(&checkpoint_bytes(&["a", "a"])).unwrap_err();
+    assert_eq!(duplicate.kind(), std::io::ErrorKind::InvalidData);
+    assert!(duplicate.to_string().contains("duplicate key"));
+
+    // Offsets of the key length, ndim and data length of the first entry.
+    for offset in &[20, 30, 54] {
+        let mut corrupt = valid.clone();
+        corrupt[*offset..*offset + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        let error = load(&corrupt).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("bytes left in the file"));
+    }
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn load_state_dict_reports_keys() {
+    init_rovo();
+    let model = small_model();
+    let mut state_dict = model.state_dict();
+    state_dict.insert("3.ws".to_string(), ones(&[2], None));
+    let mut partial = Sequential::new();
+    partial.add(Linear::new(3, 4));
+    let keys = small_model().load_state_dict(&partial.state_dict(), false);
+    assert_eq!(keys.missing_keys, vec!["2.ws", "2.bs"]);
+    assert!(keys.unexpected_keys.is_empty());
+    let keys = small_model().load_state_dict(&state_dict, false);
+    assert!(keys.missing_keys.is_empty());
+    assert_eq!(keys.unexpected_keys, vec!["3.ws"]);
+}
+
+#[test]
+#[should_panic(expected = "Error(s) in loading state_dict: Missing key(s): [\"2.ws\", \"2.bs\"].")]
+fn load_state_dict_strict() {
+    init_rovo();
+    let mut partial = Sequential::new();
+    partial.add(Linear::new(3, 4));
+    small_model().load_state_dict(&partial.state_dict(), true);
+}
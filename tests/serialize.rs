@@ -0,0 +1,408 @@
+use rovo::autograd::tensor;
+use rovo::c10::ScalarType;
+use rovo::init_rovo;
+use rovo::nn::{Linear, Module};
+use rovo::serialize::npy;
+use rovo::tensor::Tensor;
+use rovo::util::OrderedDict;
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("rovo_{}_{}", std::process::id(), name))
+}
+
+// Bytes of a version 1.0 .npy file, laid out the way numpy.save writes them.
+fn npy_bytes(header: &str, data: &[u8]) -> Vec<u8> {
+    let mut header = header.to_string();
+    while (10 + header.len() + 1) % 64 != 0 {
+        header.push(' ');
+    }
+    header.push('\n');
+    let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+    bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(header.as_bytes());
+    bytes.extend_from_slice(data);
+    bytes
+}
+
+#[test]
+fn npy_round_trip() {
+    init_rovo();
+    let path = temp_path("round_trip.npy");
+    let t = tensor(&[1.5f64, -2.0, 3.25, 4.0, 0.5, 6.0], None).reshape(&[2, 3]);
+    t.to_npy(&path).unwrap();
+    let loaded = Tensor::from_npy(&path).unwrap();
+    assert_eq!(loaded.sizes(), &[2, 3]);
+    assert_eq!(format!("{:?}", loaded), format!("{:?}", t));
+
+    // A transposed matrix is written as is, in Fortran order.
+    let t_t = t.t();
+    t_t.to_npy(&path).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    let header_end = bytes.len() - t_t.numel() * t_t.element_size();
+    assert_eq!(header_end % 64, 0);
+    assert!(String::from_utf8_lossy(&bytes[10..header_end]).contains("'fortran_order': True"));
+    let loaded = Tensor::from_npy(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.sizes(), t_t.sizes());
+    assert_eq!(loaded.strides(), t_t.strides());
+    assert_eq!(
+        format!("{:?}", loaded.contiguous()),
+        format!("{:?}", t_t.contiguous())
+    );
+}
+
+#[test]
+fn npy_big_endian_fortran_order() {
+    init_rovo();
+    let path = temp_path("fortran.npy");
+    let data: Vec<u8> = (0..6i32).flat_map(|i| i.to_be_bytes().to_vec()).collect();
+    let header = "{'descr': '>i4', 'fortran_order': True, 'shape': (2, 3), }";
+    std::fs::write(&path, npy_bytes(header, &data)).unwrap();
+    let loaded = Tensor::from_npy(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.sizes(), &[2, 3]);
+    assert_eq!(loaded.strides(), &[1, 2]);
+    let expected = tensor(&[0i32, 2, 4, 1, 3, 5], None).reshape(&[2, 3]);
+    assert_eq!(
+        format!("{:?}", loaded.contiguous()),
+        format!("{:?}", expected)
+    );
+}
+
+#[test]
+fn npy_scalar_and_unsupported_dtype() {
+    init_rovo();
+    let path = temp_path("scalar.npy");
+    let header = "{'descr': '<f4', 'fortran_order': False, 'shape': (), }";
+    std::fs::write(&path, npy_bytes(header, &2.5f32.to_le_bytes())).unwrap();
+    let loaded = Tensor::from_npy(&path).unwrap();
+    assert!(loaded.sizes().is_empty());
+    assert_eq!(unsafe { *loaded.data_ptr_casted::<f32>() }, 2.5);
+
+    let header = "{'descr': '<u2', 'fortran_order': False, 'shape': (1,), }";
+    std::fs::write(&path, npy_bytes(header, &[0; 2])).unwrap();
+    let error = Tensor::from_npy(&path).unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(error.to_string(), "unsupported npy dtype '<u2'");
+}
+
+#[test]
+fn npz_round_trip() {
+    init_rovo();
+    let path = temp_path("arrays.npz");
+    let mut tensors = OrderedDict::new_with_key_description("Tensor".to_string());
+    tensors.insert(
+        "weight".to_string(),
+        tensor(&[1f32, 2.0, 3.0, 4.0], None).reshape(&[2, 2]),
+    );
+    tensors.insert("labels".to_string(), tensor(&[3i64, 1, 2], None));
+    npy::save_npz(&tensors, &path).unwrap();
+    let loaded = npy::load_npz(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.keys().collect::<Vec<_>>(), vec!["weight", "labels"]);
+    for (key, t) in tensors.iter() {
+        assert_eq!(
+            format!("{:?}", loaded.find(key).unwrap()),
+            format!("{:?}", t)
+        );
+    }
+}
+
+#[test]
+fn npy_rejects_malformed_files() {
+    use std::io::{ErrorKind, Write};
+    init_rovo();
+    // Shapes whose size overflows, or which are far larger than the data present.
+    for shape in &["(4294967296, 4294967296)", "(1099511627776,)", "(3,)"] {
+        let header = format!(
+            "{{'descr': '<f4', 'fortran_order': False, 'shape': {}, }}",
+            shape
+        );
+        let bytes = npy_bytes(&header, &[0; 8]);
+        let error = npy::read_npy(&mut bytes.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    // "a" and "a.npy" both load as the array "a".
+    let mut array = Vec::new();
+    npy::write_npy(&mut array, &tensor(&[1f32], None)).unwrap();
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for name in &["a", "a.npy"] {
+        zip.start_file(*name, zip::write::FileOptions::default())
+            .unwrap();
+        zip.write_all(&array).unwrap();
+    }
+    let archive = zip.finish().unwrap();
+    let error = npy::read_npz(archive).unwrap_err();
+    assert_eq!(error.to_string(), "duplicate npz member \"a\"");
+}
+
+#[test]
+fn mmap_tensor_reads_file() {
+    init_rovo();
+    let path = temp_path("mmap.bin");
+    let mut bytes = b"16 byte preamble".to_vec();
+    for value in &[1f32, 2.0, 3.0, 4.0, 5.0, 6.0] {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    std::fs::write(&path, &bytes).unwrap();
+    let t = Tensor::from_file_mmap(&path, ScalarType::Float, &[2, 3], 16).unwrap();
+    let expected = tensor(&[1f32, 2.0, 3.0, 4.0, 5.0, 6.0], None).reshape(&[2, 3]);
+    assert_eq!(format!("{:?}", t), format!("{:?}", expected));
+
+    // The mapping is private, the file is left untouched.
+    t.fill_(0.0);
+    drop(t);
+    assert_eq!(std::fs::read(&path).unwrap(), bytes);
+
+    assert!(Tensor::from_file_mmap(&path, ScalarType::Float, &[2, 3], 2).is_err());
+    assert!(Tensor::from_file_mmap(&path, ScalarType::Double, &[2, 3], 16).is_err());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn mmap_writable_tensor_writes_file() {
+    init_rovo();
+    let path = temp_path("mmap_writable.bin");
+    let _ = std::fs::remove_file(&path);
+    for i in 0..3i64 {
+        let batch = Tensor::from_file_mmap_writable(&path, ScalarType::Long, &[2], i as usize * 16)
+            .unwrap();
+        batch.copy(&tensor(&[i, 10 * i], None), None);
+    }
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), 48);
+    // Sizes that overflow are rejected instead of growing the file to a wrapped length.
+    let huge = Tensor::from_file_mmap_writable(&path, ScalarType::Long, &[1 << 62], 0);
+    assert_eq!(huge.unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+    let huge = Tensor::from_file_mmap_writable(&path, ScalarType::Long, &[2], usize::MAX - 8);
+    assert_eq!(huge.unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), 48);
+    let t = Tensor::from_file_mmap(&path, ScalarType::Long, &[3, 2], 0).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let expected = tensor(&[0i64, 0, 1, 10, 2, 20], None).reshape(&[3, 2]);
+    assert_eq!(format!("{:?}", t), format!("{:?}", expected));
+}
+
+#[test]
+fn half_precision_files() {
+    use rovo::serialize::safetensors;
+    init_rovo();
+    let path = temp_path("half.npy");
+    // 1.5 and -2.0 as little endian Half.
+    let header = "{'descr': '<f2', 'fortran_order': False, 'shape': (2,), }";
+    std::fs::write(&path, npy_bytes(header, &[0x00, 0x3E, 0x00, 0xC0])).unwrap();
+    let loaded = Tensor::from_npy(&path).unwrap();
+    assert_eq!(loaded.scalar_type(), ScalarType::Half);
+    assert_eq!(
+        format!("{:?}", loaded),
+        format!("{:?}", tensor(&[1.5f64, -2.0], None))
+    );
+    std::fs::remove_file(&path).unwrap();
+
+    let path = temp_path("half.safetensors");
+    let mut tensors = OrderedDict::new_with_key_description("Tensor".to_string());
+    tensors.insert("h".to_string(), loaded);
+    tensors.insert(
+        "b".to_string(),
+        tensor(&[0.25f32, 8.0], None).to_dtype(ScalarType::BFloat16),
+    );
+    safetensors::save(&tensors, &path).unwrap();
+    let reloaded = safetensors::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    for (name, t) in tensors.iter() {
+        let r = reloaded.find(name).unwrap();
+        assert_eq!(r.scalar_type(), t.scalar_type());
+        assert_eq!(format!("{:?}", r), format!("{:?}", t));
+    }
+}
+
+#[test]
+fn complex_files() {
+    use rovo::c10::ComplexDouble;
+    use rovo::serialize::safetensors;
+    init_rovo();
+    let path = temp_path("complex.npy");
+    // 1+2i as a big endian complex64.
+    let data: Vec<u8> = [1.0f32, 2.0]
+        .iter()
+        .flat_map(|x| x.to_be_bytes().to_vec())
+        .collect();
+    let header = "{'descr': '>c8', 'fortran_order': False, 'shape': (1,), }";
+    std::fs::write(&path, npy_bytes(header, &data)).unwrap();
+    let loaded = Tensor::from_npy(&path).unwrap();
+    assert_eq!(loaded.scalar_type(), ScalarType::ComplexFloat);
+    assert!(format!("{:?}", loaded).contains("1+2i"));
+
+    let z = tensor(
+        &[ComplexDouble::new(0.5, -1.0), ComplexDouble::new(3.0, 4.0)],
+        None,
+    )
+    .to_dtype(ScalarType::ComplexDouble);
+    z.to_npy(&path).unwrap();
+    let reloaded = Tensor::from_npy(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(reloaded.scalar_type(), ScalarType::ComplexDouble);
+    assert_eq!(format!("{:?}", reloaded), format!("{:?}", z));
+
+    let path = temp_path("complex.safetensors");
+    let mut tensors = OrderedDict::new_with_key_description("Tensor".to_string());
+    tensors.insert("z".to_string(), loaded);
+    safetensors::save(&tensors, &path).unwrap();
+    let reloaded = safetensors::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let r = reloaded.find(&"z".to_string()).unwrap();
+    assert_eq!(r.scalar_type(), ScalarType::ComplexFloat);
+    assert_eq!(
+        format!("{:?}", r),
+        format!("{:?}", tensors.find(&"z".to_string()).unwrap())
+    );
+}
+
+#[test]
+fn integral_and_bool_files() {
+    use rovo::serialize::safetensors;
+    init_rovo();
+    let tensors = [
+        tensor(&[255u8, 0, 7], None),
+        tensor(&[-128i8, 5, 127], None),
+        tensor(&[-300i16, 2, 300], None),
+        tensor(&[true, false, true], None),
+    ];
+    let path = temp_path("integral.npy");
+    for t in &tensors {
+        t.to_npy(&path).unwrap();
+        let loaded = Tensor::from_npy(&path).unwrap();
+        assert_eq!(loaded.scalar_type(), t.scalar_type());
+        assert_eq!(format!("{:?}", loaded), format!("{:?}", t));
+    }
+
+    // Bytes other than 0 and 1 are rejected for bools.
+    let header = "{'descr': '|b1', 'fortran_order': False, 'shape': (2,), }";
+    std::fs::write(&path, npy_bytes(header, &[1, 2])).unwrap();
+    assert!(Tensor::from_npy(&path).is_err());
+    std::fs::remove_file(&path).unwrap();
+
+    let mut dict = OrderedDict::new_with_key_description("Tensor".to_string());
+    for (i, t) in tensors.iter().enumerate() {
+        dict.insert(i.to_string(), t.clone());
+    }
+    for path in &[temp_path("integral.safetensors"), temp_path("integral.pt")] {
+        if path.extension().unwrap() == "pt" {
+            rovo::serialize::save(&dict, path).unwrap();
+        } else {
+            safetensors::save(&dict, path).unwrap();
+        }
+        let loaded = if path.extension().unwrap() == "pt" {
+            rovo::serialize::load(path).unwrap()
+        } else {
+            safetensors::load(path).unwrap()
+        };
+        std::fs::remove_file(path).unwrap();
+        for (i, t) in tensors.iter().enumerate() {
+            let l = loaded.find(&i.to_string()).unwrap();
+            assert_eq!(l.scalar_type(), t.scalar_type());
+            assert_eq!(format!("{:?}", l), format!("{:?}", t));
+        }
+    }
+}
+
+fn format_state(model: &dyn Module) -> String {
+    format!("{:?}", model.state_dict().iter().collect::<Vec<_>>())
+}
+
+#[test]
+fn safetensors_round_trip_into_state_dict() {
+    use rovo::serialize::safetensors;
+    init_rovo();
+    let path = temp_path("state_dict.safetensors");
+    let model = Linear::new(3, 4);
+    let mut state_dict = model.state_dict();
+    let ws_t = state_dict.find(&"ws".to_string()).unwrap().t();
+    state_dict.insert("ws_t".to_string(), ws_t.clone());
+    safetensors::save(&state_dict, &path).unwrap();
+
+    let loaded = safetensors::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        loaded.keys().collect::<Vec<_>>(),
+        state_dict.keys().collect::<Vec<_>>()
+    );
+    let loaded_ws_t = loaded.find(&"ws_t".to_string()).unwrap();
+    assert_eq!(loaded_ws_t.sizes(), ws_t.sizes());
+    assert!(loaded_ws_t.is_contiguous());
+    assert_eq!(format!("{:?}", loaded_ws_t), format!("{:?}", ws_t));
+
+    let resumed = Linear::new(3, 4);
+    let keys = resumed.load_state_dict(&loaded, false);
+    assert_eq!(keys.unexpected_keys, vec!["ws_t"]);
+    assert!(keys.missing_keys.is_empty());
+    assert_eq!(format_state(&model), format_state(&resumed));
+}
+
+fn write_safetensors(path: &std::path::Path, header: &str, data: &[u8]) {
+    let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+    bytes.extend_from_slice(header.as_bytes());
+    bytes.extend_from_slice(data);
+    std::fs::write(path, bytes).unwrap();
+}
+
+#[test]
+fn safetensors_load_external_file() {
+    use rovo::serialize::safetensors;
+    init_rovo();
+    let path = temp_path("external.safetensors");
+    // The header length isn't a multiple of 8, so the I64 data isn't aligned and is copied.
+    let header = r#"{"__metadata__":{"format":"pt"},"b":{"dtype":"F32","shape":[],"data_offsets":[16,20]},"a":{"dtype":"I64","shape":[2],"data_offsets":[0,16]}}"#;
+    let mut data = Vec::new();
+    data.extend_from_slice(&7i64.to_le_bytes());
+    data.extend_from_slice(&(-3i64).to_le_bytes());
+    data.extend_from_slice(&2.5f32.to_le_bytes());
+    write_safetensors(&path, header, &data);
+    let loaded = safetensors::load(&path).unwrap();
+    assert_eq!(loaded.keys().collect::<Vec<_>>(), vec!["a", "b"]);
+    let a = loaded.find(&"a".to_string()).unwrap();
+    assert_eq!(a.sizes(), &[2]);
+    assert_eq!(unsafe { *a.data_ptr_casted::<i64>().add(1) }, -3);
+    let b = loaded.find(&"b".to_string()).unwrap();
+    assert!(b.sizes().is_empty());
+    assert_eq!(unsafe { *b.data_ptr_casted::<f32>() }, 2.5);
+
+    let header = r#"{"a":{"dtype":"I64","shape":[3],"data_offsets":[0,16]}}"#;
+    write_safetensors(&path, header, &data[..16]);
+    assert!(safetensors::load(&path).is_err());
+    // A shape whose size overflows.
+    let header = r#"{"a":{"dtype":"F32","shape":[4294967296,4294967296],"data_offsets":[0,0]}}"#;
+    write_safetensors(&path, header, &data[..16]);
+    let error = safetensors::load(&path).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn safetensors_load_is_zero_copy() {
+    use rovo::serialize::safetensors;
+    init_rovo();
+    let path = temp_path("zero_copy.safetensors");
+    // Padded so the data starts 8 byte aligned and every tensor can point into the mapping.
+    let mut header = r#"{"a":{"dtype":"I64","shape":[2],"data_offsets":[0,16]},"b":{"dtype":"F32","shape":[2],"data_offsets":[16,24]}}"#.to_string();
+    while (8 + header.len()) % 8 != 0 {
+        header.push(' ');
+    }
+    let mut data = Vec::new();
+    data.extend_from_slice(&7i64.to_le_bytes());
+    data.extend_from_slice(&(-3i64).to_le_bytes());
+    data.extend_from_slice(&2.5f32.to_le_bytes());
+    data.extend_from_slice(&0.5f32.to_le_bytes());
+    write_safetensors(&path, &header, &data);
+    let loaded = safetensors::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let a = loaded.find(&"a".to_string()).unwrap();
+    let b = loaded.find(&"b".to_string()).unwrap();
+    assert_eq!(unsafe { *b.data_ptr_casted::<f32>().add(1) }, 0.5);
+    // The mapping is page aligned, so tensors in it sit at their file offset within a
+    // page and are laid out next to each other exactly as in the file.
+    let data_start = 8 + header.len();
+    let a_ptr = a.data_ptr_casted::<u8>() as usize;
+    assert_eq!(a_ptr % 4096, data_start % 4096);
+    assert_eq!(b.data_ptr_casted::<u8>() as usize - a_ptr, 16);
+}
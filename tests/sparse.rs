@@ -0,0 +1,374 @@
+use rovo::aten::native;
+use rovo::autograd::{self, backward};
+use rovo::c10::{Layout, ScalarType, TensorOptions};
+use rovo::core::manual_seed;
+use rovo::init_rovo;
+use rovo::nn::{self, Embedding, EmbeddingConfig, Module};
+use rovo::optim::{Optimizer, SGDOptions, SGDOptionsBuilder, Sgd};
+use rovo::sparse;
+use rovo::tensor::Tensor;
+
+fn to_f64_vec(t: &Tensor) -> Vec<f64> {
+    let t = t.to_dtype(ScalarType::Double).contiguous();
+    unsafe { std::slice::from_raw_parts(t.data_ptr_casted::<f64>(), t.numel()).to_vec() }
+}
+
+fn to_i64_vec(t: &Tensor) -> Vec<i64> {
+    let t = t.contiguous();
+    unsafe { std::slice::from_raw_parts(t.data_ptr_casted::<i64>(), t.numel()).to_vec() }
+}
+
+fn indices(data: &[i64], sparse_dim: usize) -> Tensor {
+    autograd::tensor(data, None).view(&[sparse_dim, data.len() / sparse_dim])
+}
+
+// [[0, 0, 3], [4, 0, 5]] with the last entry split in two.
+fn uncoalesced() -> Tensor {
+    autograd::sparse_coo_tensor(
+        &indices(&[1, 0, 1, 1, 2, 2, 0, 2], 2),
+        &autograd::tensor(&[2.0f32, 3.0, 4.0, 3.0], None),
+        &[2, 3],
+        None,
+    )
+}
+
+#[test]
+fn sparse_coo_construction() {
+    init_rovo();
+    let s = uncoalesced();
+    assert!(s.is_sparse());
+    assert_eq!(s.sizes(), &[2, 3]);
+    assert_eq!((s.nnz(), s.sparse_dim(), s.dense_dim()), (4, 2, 0));
+    assert!(!s.is_coalesced());
+    assert_eq!(
+        to_f64_vec(&s.to_dense()),
+        vec![0.0, 0.0, 3.0, 4.0, 0.0, 5.0]
+    );
+
+    let c = s.coalesce();
+    assert!(c.is_coalesced());
+    assert_eq!(to_i64_vec(&c.indices()), vec![0, 1, 1, 2, 0, 2]);
+    assert_eq!(to_f64_vec(&c.values()), vec![3.0, 4.0, 5.0]);
+
+    let t = s.t();
+    assert_eq!(t.sizes(), &[3, 2]);
+    assert_eq!(
+        to_f64_vec(&t.to_dense()),
+        vec![0.0, 4.0, 0.0, 0.0, 3.0, 5.0]
+    );
+
+    let doubles = s.to_dtype(ScalarType::Double);
+    assert!(doubles.is_sparse());
+    assert_eq!(doubles.values().scalar_type(), ScalarType::Double);
+}
+
+#[test]
+fn dense_sparse_round_trip() {
+    init_rovo();
+    let dense = autograd::tensor(&[0.0f32, 0.0, 1.0, 0.0, 0.0, 0.0, 2.0, 3.0], None).view(&[4, 2]);
+    let s = dense.to_sparse();
+    assert_eq!(s.nnz(), 3);
+    assert_eq!(to_i64_vec(&s.indices()), vec![1, 3, 3, 0, 0, 1]);
+    assert_eq!(to_f64_vec(&s.to_dense()), to_f64_vec(&dense));
+
+    // Hybrid tensors keep the rows that have any non zero value.
+    let hybrid = dense.to_sparse_dim(1);
+    assert_eq!((hybrid.nnz(), hybrid.dense_dim()), (2, 1));
+    assert_eq!(hybrid.values().sizes(), &[2, 2]);
+    assert_eq!(to_f64_vec(&hybrid.to_dense()), to_f64_vec(&dense));
+}
+
+#[test]
+fn sparse_arithmetic() {
+    init_rovo();
+    let s = uncoalesced();
+    let dense = autograd::tensor(&[1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0], None).view(&[2, 3]);
+
+    let sum = &s + &s;
+    assert!(sum.is_sparse());
+    assert_eq!(
+        to_f64_vec(&sum.to_dense()),
+        vec![0.0, 0.0, 6.0, 8.0, 0.0, 10.0]
+    );
+    let mixed = &dense + &s;
+    assert!(!mixed.is_sparse());
+    assert_eq!(to_f64_vec(&mixed), vec![1.0, 2.0, 6.0, 8.0, 5.0, 11.0]);
+
+    let product = &s * &dense;
+    assert!(product.is_sparse());
+    assert_eq!(
+        to_f64_vec(&product.to_dense()),
+        vec![0.0, 0.0, 9.0, 16.0, 0.0, 30.0]
+    );
+    let squared = &s * &s;
+    assert_eq!(squared.nnz(), 3);
+    assert_eq!(
+        to_f64_vec(&squared.to_dense()),
+        vec![0.0, 0.0, 9.0, 16.0, 0.0, 25.0]
+    );
+    let scaled = &s * 2.0;
+    assert_eq!(
+        to_f64_vec(&scaled.to_dense()),
+        vec![0.0, 0.0, 6.0, 8.0, 0.0, 10.0]
+    );
+
+    assert_eq!(to_f64_vec(&sparse::sum(&s)), vec![12.0]);
+
+    // In place updates of strided tensors only touch the rows of the sparse operand.
+    dense.add_with_alpha_(&s, -1.0);
+    assert_eq!(to_f64_vec(&dense), vec![1.0, 2.0, 0.0, 0.0, 5.0, 1.0]);
+}
+
+#[test]
+fn sparse_mm_matches_dense() {
+    init_rovo();
+    let s = uncoalesced();
+    let weight = autograd::tensor(&[1.0f32, -1.0, 2.0, 0.5, 3.0, 1.0], None).view(&[3, 2]);
+    weight.set_requires_grad(true);
+    let y = sparse::mm(&s, &weight);
+    assert!(!y.is_sparse());
+    assert_eq!(to_f64_vec(&y), vec![9.0, 3.0, 19.0, 1.0]);
+    backward(&vec![y.sum()], &vec![], false);
+    let sparse_grad = to_f64_vec(&weight.grad().unwrap());
+
+    let dense_weight = autograd::tensor(&[1.0f32, -1.0, 2.0, 0.5, 3.0, 1.0], None).view(&[3, 2]);
+    dense_weight.set_requires_grad(true);
+    let y = s.to_dense().mm(&dense_weight, false);
+    backward(&vec![y.sum()], &vec![], false);
+    assert_eq!(sparse_grad, to_f64_vec(&dense_weight.grad().unwrap()));
+    assert_eq!(sparse_grad, vec![4.0, 4.0, 0.0, 0.0, 8.0, 8.0]);
+}
+
+#[test]
+fn embedding_sparse_gradients() {
+    init_rovo();
+    let input = autograd::tensor(&[2i64, 0, 2, 3], None).view(&[2, 2]);
+    let scale = autograd::tensor(&[1.0f32, 2.0, 3.0, 4.0], None).view(&[4, 1]);
+    let mut grads = vec![];
+    for &sparse in [false, true].iter() {
+        let weight = autograd::ones(&[5, 3], TensorOptions::with_requires_grad());
+        let y = nn::embedding(&input, &weight, Some(3), sparse);
+        assert_eq!(y.sizes(), &[2, 2, 3]);
+        backward(&vec![(&y.view(&[4, 3]) * &scale).sum()], &vec![], false);
+        let grad = weight.grad().unwrap();
+        assert_eq!(grad.is_sparse(), sparse);
+        grads.push(to_f64_vec(&grad.to_dense()));
+    }
+    assert_eq!(grads[0], grads[1]);
+    #[rustfmt::skip]
+    let expected = vec![
+        2.0, 2.0, 2.0,
+        0.0, 0.0, 0.0,
+        4.0, 4.0, 4.0,
+        0.0, 0.0, 0.0,
+        0.0, 0.0, 0.0,
+    ];
+    assert_eq!(grads[0], expected);
+}
+
+#[test]
+fn embedding_sgd_with_sparse_gradients() {
+    init_rovo();
+    let input = autograd::tensor(&[1i64, 4, 1], None);
+    let mut weights = vec![];
+    for &sparse in [false, true].iter() {
+        manual_seed(0);
+        let config = EmbeddingConfig {
+            num_embeddings: 6,
+            embedding_dim: 2,
+            padding_idx: Some(0),
+            sparse,
+        };
+        let model = Embedding::new_with_config(config);
+        let weight = model.weight.as_ref().unwrap();
+        assert_eq!(to_f64_vec(weight)[..2], [0.0, 0.0]);
+        let before = to_f64_vec(weight);
+        let mut sgd = Sgd::new(model.parameters().unwrap(), SGDOptions::new(0.5));
+        for _ in 0..3 {
+            sgd.zero_grad();
+            let y = model.forward(&[&input]);
+            backward(&vec![(&y * &y).sum()], &vec![], false);
+            sgd.step::<fn() -> Tensor>(None);
+        }
+        let after = to_f64_vec(weight);
+        // Rows that were never looked up keep their values.
+        for row in [0, 2, 3, 5].iter() {
+            assert_eq!(after[row * 2..row * 2 + 2], before[row * 2..row * 2 + 2]);
+        }
+        weights.push(after);
+    }
+    for (dense, sparse) in weights[0].iter().zip(weights[1].iter()) {
+        assert!((dense - sparse).abs() < 1e-6, "{} != {}", dense, sparse);
+    }
+}
+
+#[test]
+#[should_panic(expected = "SGD does not support momentum or weight decay with sparse gradients")]
+fn sgd_rejects_momentum_with_sparse_gradients() {
+    init_rovo();
+    let model = Embedding::new_with_config(EmbeddingConfig {
+        num_embeddings: 4,
+        embedding_dim: 2,
+        sparse: true,
+        ..EmbeddingConfig::default()
+    });
+    let options = SGDOptionsBuilder::new(0.1).momentum(0.9).build();
+    let mut sgd = Sgd::new(model.parameters().unwrap(), options);
+    let y = model.forward(&[&autograd::tensor(&[1i64], None)]);
+    backward(&vec![y.sum()], &vec![], false);
+    sgd.step::<fn() -> Tensor>(None);
+}
+
+#[test]
+#[should_panic(expected = "has been modified by an inplace operation")]
+fn sparse_zero_invalidates_saved_tensor() {
+    init_rovo();
+    let mut s = uncoalesced();
+    let weight = autograd::ones(&[3, 2], TensorOptions::with_requires_grad());
+    let y = sparse::mm(&s, &weight);
+    s.zero_();
+    backward(&vec![y.sum()], &vec![], false);
+}
+
+#[test]
+#[should_panic(expected = "found index 3 out of bounds for dimension 1 of size 3")]
+fn sparse_coo_rejects_out_of_bounds_indices() {
+    init_rovo();
+    autograd::sparse_coo_tensor(
+        &indices(&[0, 3], 2),
+        &autograd::tensor(&[1.0f32], None),
+        &[2, 3],
+        None,
+    );
+}
+
+#[test]
+#[should_panic(expected = "TensorIterator does not support sparse tensors")]
+fn strided_kernels_reject_sparse_tensors() {
+    init_rovo();
+    uncoalesced().exp();
+}
+
+fn csr_example() -> Tensor {
+    autograd::tensor(&[0.0f32, 0.0, 3.0, 4.0, 0.0, 5.0], None).view(&[2, 3])
+}
+
+#[test]
+fn sparse_csr_conversions() {
+    init_rovo();
+    let csr = csr_example().to_sparse_csr();
+    assert!(csr.is_sparse_csr() && !csr.is_sparse());
+    assert_eq!(csr.layout(), Layout::SparseCsr);
+    assert_eq!(to_i64_vec(&csr.crow_indices()), vec![0, 1, 3]);
+    assert_eq!(to_i64_vec(&csr.col_indices()), vec![2, 0, 2]);
+    assert_eq!(to_f64_vec(&csr.values()), vec![3.0, 4.0, 5.0]);
+    assert_eq!(csr.nnz(), 3);
+    assert_eq!(to_f64_vec(&csr.to_dense()), to_f64_vec(&csr_example()));
+
+    let from_coo = uncoalesced().to_sparse_csr();
+    assert_eq!(to_i64_vec(&from_coo.crow_indices()), vec![0, 1, 3]);
+    assert_eq!(to_f64_vec(&from_coo.values()), vec![3.0, 4.0, 5.0]);
+    let coo = csr.to_sparse();
+    assert!(coo.is_sparse() && coo.is_coalesced());
+    assert_eq!(to_i64_vec(&coo.indices()), vec![0, 1, 1, 2, 0, 2]);
+
+    // The transpose has the compressed columns of the matrix as its rows.
+    let t = csr.t();
+    assert!(t.is_sparse_csr());
+    assert_eq!(t.sizes(), &[3, 2]);
+    assert_eq!(to_i64_vec(&t.crow_indices()), vec![0, 1, 1, 3]);
+    assert_eq!(to_i64_vec(&t.col_indices()), vec![1, 0, 1]);
+    assert_eq!(to_f64_vec(&t.values()), vec![4.0, 3.0, 5.0]);
+    assert_eq!(
+        to_f64_vec(&t.to_dense()),
+        vec![0.0, 4.0, 0.0, 0.0, 3.0, 5.0]
+    );
+}
+
+#[test]
+fn sparse_csr_layout_options() {
+    init_rovo();
+    let csr_options = TensorOptions::with_layout(Layout::SparseCsr);
+    let csr = native::to(&csr_example(), &csr_options, false, false, None);
+    assert!(csr.is_sparse_csr());
+    assert_eq!(to_i64_vec(&csr.crow_indices()), vec![0, 1, 3]);
+    let doubles = csr.to_dtype(ScalarType::Double);
+    assert!(doubles.is_sparse_csr());
+    assert_eq!(doubles.scalar_type(), ScalarType::Double);
+    let strided = native::to(
+        &csr,
+        TensorOptions::with_layout(Layout::Strided),
+        false,
+        false,
+        None,
+    );
+    assert_eq!(strided.layout(), Layout::Strided);
+    assert_eq!(to_f64_vec(&strided), to_f64_vec(&csr_example()));
+
+    let empty = autograd::empty(&[3, 4], csr_options, None);
+    assert!(empty.is_sparse_csr());
+    assert_eq!(empty.nnz(), 0);
+    assert_eq!(to_i64_vec(&empty.crow_indices()), vec![0, 0, 0, 0]);
+    assert_eq!(to_f64_vec(&empty.to_dense()), vec![0.0; 12]);
+}
+
+#[test]
+fn sparse_csr_mm_matches_dense() {
+    init_rovo();
+    let (n, k, m) = (13, 9, 5);
+    let entry = |i: usize, j: usize, seed: usize| -> f32 {
+        if (i * 7 + j * 3 + seed) % 4 == 0 {
+            ((i * 31 + j * 17 + seed) % 13) as f32 - 6.0
+        } else {
+            0.0
+        }
+    };
+    let a: Vec<f32> = (0..n * k).map(|x| entry(x / k, x % k, 0)).collect();
+    let b: Vec<f32> = (0..k * m).map(|x| entry(x / m, x % m, 1) + 0.5).collect();
+    let a = autograd::tensor(&a[..], None).view(&[n, k]);
+    let csr = a.to_sparse_csr();
+
+    let mut grads = vec![];
+    for sparse in [true, false].iter() {
+        let b = autograd::tensor(&b[..], None).view(&[k, m]);
+        b.set_requires_grad(true);
+        let y = if *sparse {
+            sparse::mm(&csr, &b)
+        } else {
+            a.mm(&b, false)
+        };
+        let scale: Vec<f32> = (0..n * m).map(|x| (x % 7) as f32).collect();
+        let scale = autograd::tensor(&scale[..], None).view(&[n, m]);
+        backward(&vec![(&y * &scale).sum()], &vec![], false);
+        grads.push((to_f64_vec(&y), to_f64_vec(&b.grad().unwrap())));
+    }
+    assert_eq!(grads[0], grads[1]);
+
+    let v = autograd::tensor(&[1.0f32, -2.0, 0.5], None);
+    let y = sparse::mv(&csr_example().to_sparse_csr(), &v);
+    assert_eq!(to_f64_vec(&y), vec![1.5, 6.5]);
+}
+
+#[test]
+#[should_panic(expected = "has been modified by an inplace operation")]
+fn sparse_csr_zero_invalidates_saved_tensor() {
+    init_rovo();
+    let mut csr = csr_example().to_sparse_csr();
+    let weight = autograd::ones(&[3, 2], TensorOptions::with_requires_grad());
+    let y = sparse::mm(&csr, &weight);
+    csr.zero_();
+    backward(&vec![y.sum()], &vec![], false);
+}
+
+#[test]
+#[should_panic(expected = "col_indices of row 1 must be strictly increasing")]
+fn sparse_csr_rejects_unsorted_columns() {
+    init_rovo();
+    autograd::sparse_csr_tensor(
+        &autograd::tensor(&[0i64, 1, 3], None),
+        &autograd::tensor(&[2i64, 2, 0], None),
+        &autograd::tensor(&[3.0f32, 5.0, 4.0], None),
+        &[2, 3],
+        None,
+    );
+}
@@ -1,5 +1,8 @@
 use rovo::{autograd, tensor::log_softmax};
-use rovo::{c10::TensorOptions, init_rovo};
+use rovo::{
+    c10::{ScalarType, TensorOptions},
+    init_rovo,
+};
 
 #[test]
 fn empty_tensor_and_fill_ones() {
@@ -99,3 +102,245 @@ fn logsoftmax() {
     let result = log_softmax(&x, 1, None);
     println!("Logsoftmax result: {:?}", result);
 }
+
+#[test]
+fn mm_with_expanded_operand() {
+    init_rovo();
+    // Stride 0 operands can't be passed to BLAS as is.
+    let ones = autograd::ones(&[], None).expand(&[4, 2], false).t();
+    let x = autograd::full(&[4, 3], 0.5, None);
+    let result = ones.mm(&x, false);
+    assert_eq!(result.sizes(), &[2, 3]);
+    let data = unsafe { std::slice::from_raw_parts(result.data_ptr_casted::<f32>(), 6) };
+    assert_eq!(data, &[2.0; 6]);
+}
+
+fn to_f32_vec(t: &rovo::tensor::Tensor) -> Vec<f32> {
+    let t = t.to_dtype(ScalarType::Float).contiguous();
+    unsafe { std::slice::from_raw_parts(t.data_ptr_casted::<f32>(), t.numel()).to_vec() }
+}
+
+#[test]
+fn half_and_bfloat16_tensors() {
+    init_rovo();
+    for &dtype in &[ScalarType::Half, ScalarType::BFloat16] {
+        let t = autograd::tensor(&[1.0f32, 2.5, -3.0, 4.0], None).to_dtype(dtype);
+        assert_eq!(t.scalar_type(), dtype);
+        assert_eq!(t.element_size(), 2);
+        let r = &(&t + &t) * &t;
+        assert_eq!(r.scalar_type(), dtype);
+        assert_eq!(to_f32_vec(&r), vec![2.0, 12.5, 18.0, 32.0]);
+        assert_eq!(to_f32_vec(&-&t), vec![-1.0, -2.5, 3.0, -4.0]);
+        assert!(format!("{:?}", t).contains("2.5"));
+
+        // Sums are accumulated in f32, in 16 bits the rounding errors would add up.
+        let tenths = autograd::full(&[20000], 0.1, None).to_dtype(dtype);
+        let sum = tenths.sum();
+        assert_eq!(sum.scalar_type(), dtype);
+        assert_eq!(to_f32_vec(&sum), vec![2000.0]);
+        let ones = autograd::full(&[4096], 1.0, None).to_dtype(dtype);
+        assert_eq!(to_f32_vec(&ones.mean()), vec![1.0]);
+
+        let u = autograd::empty(&[64], TensorOptions::with_dtype(dtype), None);
+        u.uniform_with_gen(-1.0, 1.0, None);
+        assert!(to_f32_vec(&u).iter().all(|x| (-1.0..=1.0).contains(x)));
+    }
+    let h = autograd::tensor(&[rovo::c10::Half::from_f32(0.5)], None);
+    assert_eq!(h.scalar_type(), ScalarType::Half);
+}
+
+fn to_c64_vec(t: &rovo::tensor::Tensor) -> Vec<rovo::c10::ComplexDouble> {
+    let t = t.to_dtype(ScalarType::ComplexDouble).contiguous();
+    unsafe {
+        std::slice::from_raw_parts(t.data_ptr_casted::<rovo::c10::ComplexDouble>(), t.numel())
+            .to_vec()
+    }
+}
+
+#[test]
+fn complex_tensors() {
+    use rovo::c10::{ComplexDouble, ComplexFloat};
+    init_rovo();
+    let c = |re, im| ComplexDouble::new(re, im);
+    let z = autograd::tensor(
+        &[ComplexFloat::new(3.0, 4.0), ComplexFloat::new(-1.0, 0.0)],
+        None,
+    );
+    assert_eq!(z.scalar_type(), ScalarType::ComplexFloat);
+    assert_eq!(z.element_size(), 8);
+    assert_eq!(to_f32_vec(&z.real()), vec![3.0, -1.0]);
+    assert_eq!(to_f32_vec(&z.imag()), vec![4.0, 0.0]);
+    assert_eq!(to_f32_vec(&z.abs()), vec![5.0, 1.0]);
+    assert_eq!(z.abs().scalar_type(), ScalarType::Float);
+    assert_eq!(to_c64_vec(&z.conj()), vec![c(3.0, -4.0), c(-1.0, 0.0)]);
+    assert_eq!(to_f32_vec(&z.view_as_real()), vec![3.0, 4.0, -1.0, 0.0]);
+    assert_eq!(
+        to_c64_vec(&z.view_as_real().view_as_complex()),
+        to_c64_vec(&z)
+    );
+    assert!(format!("{:?}", z).contains("3+4i"));
+
+    // real and imag are views sharing the storage.
+    z.imag().fill_(1.0);
+    assert_eq!(to_c64_vec(&z), vec![c(3.0, 1.0), c(-1.0, 1.0)]);
+
+    // Real operands are promoted to complex.
+    let x = autograd::tensor(&[2.0f32, 4.0], None);
+    let sum = &z + &x;
+    assert_eq!(sum.scalar_type(), ScalarType::ComplexFloat);
+    assert_eq!(to_c64_vec(&sum), vec![c(5.0, 1.0), c(3.0, 1.0)]);
+    assert_eq!(to_c64_vec(&(&z * &z)), vec![c(8.0, 6.0), c(0.0, -2.0)]);
+    assert_eq!(to_c64_vec(&(&z / &x)), vec![c(1.5, 0.5), c(-0.25, 0.25)]);
+    assert_eq!(to_c64_vec(&z.sum()), vec![c(2.0, 2.0)]);
+
+    let a = autograd::tensor(&[c(1.0, 1.0), c(0.0, 2.0), c(1.0, 0.0), c(0.0, -1.0)], None)
+        .view(&[2, 2]);
+    let b =
+        autograd::tensor(&[c(2.0, 0.0), c(1.0, 0.0), c(0.0, 1.0), c(1.0, 1.0)], None).view(&[2, 2]);
+    assert_eq!(
+        to_c64_vec(&a.mm(&b, false)),
+        vec![c(0.0, 2.0), c(-1.0, 3.0), c(3.0, 0.0), c(2.0, -1.0)]
+    );
+
+    let r = autograd::tensor(&[2.0f64], None);
+    let theta = autograd::tensor(&[std::f64::consts::FRAC_PI_2], None);
+    let p = to_c64_vec(&rovo::tensor::polar(&r, &theta))[0];
+    assert!((p - c(0.0, 2.0)).norm() < 1e-6);
+    assert_eq!(
+        to_c64_vec(&rovo::tensor::complex(&r, &r)),
+        vec![c(2.0, 2.0)]
+    );
+    assert_eq!(
+        to_f32_vec(&rovo::tensor::angle(&autograd::tensor(
+            &[-1.0f32, 2.0],
+            None
+        ))),
+        vec![std::f32::consts::PI, 0.0]
+    );
+}
+
+fn to_i64_vec(t: &rovo::tensor::Tensor) -> Vec<i64> {
+    let t = t.to_dtype(ScalarType::Long).contiguous();
+    unsafe { std::slice::from_raw_parts(t.data_ptr_casted::<i64>(), t.numel()).to_vec() }
+}
+
+#[test]
+fn integral_and_bool_tensors() {
+    use rovo::aten::native::RoundingMode;
+    init_rovo();
+    let b = autograd::tensor(&[250u8, 5], None);
+    assert_eq!(b.scalar_type(), ScalarType::Byte);
+    assert_eq!(b.element_size(), 1);
+    assert_eq!(
+        format!("{:?}", b),
+        format!("{:?}", autograd::tensor(&[250i64, 5], None))
+    );
+    // Integer arithmetic wraps around.
+    assert_eq!(to_i64_vec(&(&b + &b)), vec![244, 10]);
+    assert_eq!(to_i64_vec(&(&b * &b)), vec![36, 25]);
+    assert_eq!(to_i64_vec(&-&b), vec![6, 251]);
+    let c = autograd::tensor(&[-128i8, 127], None);
+    assert_eq!(c.scalar_type(), ScalarType::Char);
+    assert_eq!(
+        to_i64_vec(&(&c - &autograd::tensor(&[1i8, -1], None))),
+        vec![127, -128]
+    );
+    let s = autograd::tensor(&[-7i16, 7], None);
+    assert_eq!(s.scalar_type(), ScalarType::Short);
+    assert_eq!(to_i64_vec(&s.abs()), vec![7, 7]);
+    assert_eq!(to_i64_vec(&s.sgn()), vec![-1, 1]);
+
+    // Casting to u8 wraps negative values.
+    let f = autograd::tensor(&[-1.0f32, 2.9], None);
+    assert_eq!(to_i64_vec(&f.to_dtype(ScalarType::Byte)), vec![255, 2]);
+
+    // True division promotes to the default dtype, rounded division keeps integers.
+    let n = autograd::tensor(&[7i16, -7], None);
+    let d = autograd::tensor(&[2i16, 2], None);
+    let q = &n / &d;
+    assert_eq!(q.scalar_type(), ScalarType::Float);
+    assert_eq!(to_f32_vec(&q), vec![3.5, -3.5]);
+    let trunc = n.div_mode(&d, Some(RoundingMode::Trunc));
+    assert_eq!(trunc.scalar_type(), ScalarType::Short);
+    assert_eq!(to_i64_vec(&trunc), vec![3, -3]);
+    assert_eq!(to_i64_vec(&n.floor_divide(&d)), vec![3, -4]);
+
+    // Integer sums accumulate in Long.
+    let sum = autograd::full(&[300], 1.0, None)
+        .to_dtype(ScalarType::Byte)
+        .sum();
+    assert_eq!(sum.scalar_type(), ScalarType::Long);
+    assert_eq!(to_i64_vec(&sum), vec![300]);
+
+    let m = autograd::tensor(&[1i16, 2, 3, 4], None).view(&[2, 2]);
+    let p = m.mm(&m, false);
+    assert_eq!(p.scalar_type(), ScalarType::Short);
+    assert_eq!(to_i64_vec(&p), vec![7, 10, 15, 22]);
+}
+
+#[test]
+fn bool_tensors() {
+    init_rovo();
+    let t = autograd::tensor(&[true, false, true], None);
+    assert_eq!(t.scalar_type(), ScalarType::Bool);
+    assert_eq!(t.element_size(), 1);
+    assert!(format!("{:?}", t).contains("[true, false, true]"));
+    let u = autograd::tensor(&[true, true, false], None);
+    assert_eq!(to_i64_vec(&(&t + &u)), vec![1, 1, 1]);
+    assert_eq!(to_i64_vec(&(&t * &u)), vec![1, 0, 0]);
+    assert_eq!((&t + &u).scalar_type(), ScalarType::Bool);
+    assert_eq!(to_i64_vec(&t.sum()), vec![2]);
+
+    let f = autograd::tensor(&[0.0f32, -0.5, 2.0], None).to_dtype(ScalarType::Bool);
+    assert_eq!(to_i64_vec(&f), vec![0, 1, 1]);
+    assert_eq!(to_f32_vec(&t), vec![1.0, 0.0, 1.0]);
+
+    let z = autograd::empty(&[2], TensorOptions::with_dtype(ScalarType::Bool), None);
+    z.fill_(true);
+    assert_eq!(to_i64_vec(&z), vec![1, 1]);
+}
+
+#[test]
+#[should_panic(expected = "ZeroDivisionError")]
+fn integer_division_by_zero() {
+    init_rovo();
+    let n = autograd::tensor(&[7i16, -7], None);
+    n.floor_divide(&autograd::tensor(&[0i16, 1], None));
+}
+
+fn mean_and_std(data: &[f64]) -> (f64, f64) {
+    let n = data.len() as f64;
+    let mean = data.iter().sum::<f64>() / n;
+    let var = data.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n;
+    (mean, var.sqrt())
+}
+
+#[test]
+fn normal_matches_mean_and_std() {
+    init_rovo();
+    // Large contiguous tensors use the Box-Muller fill, the size isn't a multiple of 16
+    // so the tail is redrawn too.
+    for dtype in [ScalarType::Float, ScalarType::Double] {
+        let t = autograd::empty(&[20001], TensorOptions::with_dtype(dtype), None);
+        t.normal(2.0, 3.0);
+        let data = t.to_dtype(ScalarType::Double);
+        let data = unsafe { std::slice::from_raw_parts(data.data_ptr_casted::<f64>(), 20001) };
+        assert!(data.iter().all(|x| x.is_finite()));
+        let (mean, std) = mean_and_std(data);
+        assert!((mean - 2.0).abs() < 0.1, "mean {}", mean);
+        assert!((std - 3.0).abs() < 0.1, "std {}", std);
+    }
+    // Small and non contiguous tensors are filled one value at a time.
+    let small = autograd::empty(&[8], TensorOptions::with_dtype(ScalarType::Double), None);
+    small.normal(0.0, 1.0);
+    let strided = autograd::full(&[16, 4], 0.0, None).t();
+    strided.normal(5.0, 0.0);
+    assert_eq!(to_f32_vec(&strided), vec![5.0; 64]);
+}
+
+#[test]
+#[should_panic(expected = "normal expects std >= 0.0")]
+fn normal_rejects_negative_std() {
+    init_rovo();
+    autograd::empty(&[4], None, None).normal(0.0, -1.0);
+}
@@ -0,0 +1,153 @@
+use rovo::aten::native::{promote_types, scalar_tensor};
+use rovo::autograd;
+use rovo::c10::{ComplexDouble, Scalar, ScalarType, TensorOptions};
+use rovo::init_rovo;
+use rovo::tensor::Tensor;
+
+use ScalarType::{
+    BFloat16 as bf, Bool as b1, Byte as u1, Char as i1, ComplexDouble as c8, ComplexFloat as c4,
+    Double as f8, Float as f4, Half as f2, Int as i4, Long as i8, Short as i2,
+};
+
+const DTYPES: [ScalarType; 12] = [u1, i1, i2, i4, i8, f2, f4, f8, c4, c8, b1, bf];
+
+// Expected promote_types(DTYPES[row], DTYPES[column]).
+#[rustfmt::skip]
+const PROMOTED: [[ScalarType; 12]; 12] = [
+    /*        u1  i1  i2  i4  i8  f2  f4  f8  c4  c8  b1  bf */
+    /* u1 */ [u1, i2, i2, i4, i8, f2, f4, f8, c4, c8, u1, bf],
+    /* i1 */ [i2, i1, i2, i4, i8, f2, f4, f8, c4, c8, i1, bf],
+    /* i2 */ [i2, i2, i2, i4, i8, f2, f4, f8, c4, c8, i2, bf],
+    /* i4 */ [i4, i4, i4, i4, i8, f2, f4, f8, c4, c8, i4, bf],
+    /* i8 */ [i8, i8, i8, i8, i8, f2, f4, f8, c4, c8, i8, bf],
+    /* f2 */ [f2, f2, f2, f2, f2, f2, f4, f8, c4, c8, f2, f4],
+    /* f4 */ [f4, f4, f4, f4, f4, f4, f4, f8, c4, c8, f4, f4],
+    /* f8 */ [f8, f8, f8, f8, f8, f8, f8, f8, c8, c8, f8, f8],
+    /* c4 */ [c4, c4, c4, c4, c4, c4, c4, c8, c4, c8, c4, c4],
+    /* c8 */ [c8, c8, c8, c8, c8, c8, c8, c8, c8, c8, c8, c8],
+    /* b1 */ [u1, i1, i2, i4, i8, f2, f4, f8, c4, c8, b1, bf],
+    /* bf */ [bf, bf, bf, bf, bf, f4, f4, f8, c4, c8, bf, bf],
+];
+
+fn ones(dtype: ScalarType) -> Tensor {
+    autograd::full(&[2], 1.0, None).to_dtype(dtype)
+}
+
+fn zero_dim(value: impl Into<Scalar>, dtype: ScalarType) -> Tensor {
+    scalar_tensor(value.into(), TensorOptions::with_dtype(dtype))
+}
+
+#[test]
+fn promote_types_table() {
+    for (i, &a) in DTYPES.iter().enumerate() {
+        for (j, &b) in DTYPES.iter().enumerate() {
+            assert_eq!(
+                promote_types(a, b),
+                PROMOTED[i][j],
+                "promote_types({:?}, {:?})",
+                a,
+                b
+            );
+        }
+    }
+}
+
+#[test]
+fn binary_ops_promote_dimensioned_tensors() {
+    init_rovo();
+    for (i, &a) in DTYPES.iter().enumerate() {
+        for (j, &b) in DTYPES.iter().enumerate() {
+            let expected = PROMOTED[i][j];
+            let (x, y) = (ones(a), ones(b));
+            assert_eq!((&x + &y).scalar_type(), expected, "{:?} + {:?}", a, b);
+            assert_eq!((&x * &y).scalar_type(), expected, "{:?} * {:?}", a, b);
+            if expected != b1 {
+                assert_eq!((&x - &y).scalar_type(), expected, "{:?} - {:?}", a, b);
+            }
+        }
+    }
+}
+
+#[test]
+fn binary_ops_promote_by_category() {
+    init_rovo();
+    let c = ComplexDouble::new(1.0, 1.0);
+    // Zero dimensional tensors and wrapped numbers only raise the category of the
+    // result, dimensioned tensors decide the dtype within it.
+    let cases = [
+        (ones(i4), zero_dim(1.0, f8), f8),
+        (ones(f4), zero_dim(1.0, f8), f4),
+        (ones(f2), zero_dim(1.0, f8), f2),
+        (ones(u1), zero_dim(1, i8), u1),
+        (ones(b1), zero_dim(1, i4), i4),
+        (ones(i4), zero_dim(c, c8), c8),
+        (ones(f8), zero_dim(c, c4), c8),
+        (ones(f4), zero_dim(c, c8), c4),
+        (ones(f2), zero_dim(c, c8), c8),
+        (zero_dim(1, i4), zero_dim(1.0, f8), f8),
+        (zero_dim(1, u1), zero_dim(1, i1), i2),
+        (zero_dim(1, i8), ones(i1), i1),
+        (zero_dim(1.0, f8), ones(i4), f8),
+    ];
+    for (a, b, expected) in cases.iter() {
+        let result = a + b;
+        assert_eq!(
+            result.scalar_type(),
+            *expected,
+            "{:?} {:?} + {:?} {:?}",
+            a.scalar_type(),
+            a.sizes(),
+            b.scalar_type(),
+            b.sizes()
+        );
+        assert_eq!(result.sizes(), a.sizes().max(b.sizes()));
+    }
+
+    // Wrapped numbers rank below zero dimensional tensors, and floating ones take the
+    // default dtype.
+    let wrapped = [
+        (&ones(i4) + 2.5, f4),
+        (&ones(i8) + 2, i8),
+        (&ones(u1) + 2, u1),
+        (&ones(b1) + 2, i8),
+        (&ones(b1) + true, b1),
+        (&ones(f2) + 2.5, f2),
+        (&ones(bf) + 2.5, bf),
+        (&ones(f8) + 2.5, f8),
+        (&ones(i2) + c, c4),
+        (&ones(f8) + c, c8),
+        (&ones(f2) + c, c4),
+        (&zero_dim(1, i4) + 2.5, f4),
+        (&zero_dim(1, i4) + 2, i4),
+        (&zero_dim(1, b1) + 2, i8),
+    ];
+    for (i, (result, expected)) in wrapped.iter().enumerate() {
+        assert_eq!(result.scalar_type(), *expected, "case {}", i);
+    }
+}
+
+#[test]
+fn promoted_values() {
+    init_rovo();
+    let to_f64 = |t: &Tensor| -> Vec<f64> {
+        let t = t.to_dtype(f8).contiguous();
+        unsafe { std::slice::from_raw_parts(t.data_ptr_casted::<f64>(), t.numel()).to_vec() }
+    };
+    let bytes = autograd::tensor(&[200u8, 100], None);
+    let chars = autograd::tensor(&[-100i8, 100], None);
+    assert_eq!(to_f64(&(&bytes + &chars)), vec![100.0, 200.0]);
+    assert_eq!(to_f64(&(&bytes + 0.5)), vec![200.5, 100.5]);
+    let half = autograd::tensor(&[1.5f32, 2.0], None).to_dtype(f2);
+    let bfloat = autograd::tensor(&[0.5f32, 256.0], None).to_dtype(bf);
+    assert_eq!(to_f64(&(&half * &bfloat)), vec![0.75, 512.0]);
+    let long = autograd::tensor(&[3i64, 4], None);
+    assert_eq!(to_f64(&(&long * &zero_dim(0.5, f8))), vec![1.5, 2.0]);
+}
+
+#[test]
+#[should_panic(expected = "can't be cast to the desired output type")]
+fn in_place_ops_reject_unsafe_casts() {
+    init_rovo();
+    let x = ones(i4);
+    x.add_(&ones(f4));
+}